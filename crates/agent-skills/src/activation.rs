@@ -0,0 +1,396 @@
+//! File-pattern activation from the `globs` frontmatter field.
+//!
+//! Patterns follow gitignore conventions: a pattern without a `/` matches a
+//! file name at any depth, a leading `/` anchors it to the workspace root, a
+//! trailing `/` matches everything under a directory and a leading `!`
+//! negates it. When several patterns match a file the last one decides.
+//! A single `globs` string may also hold several comma-separated patterns,
+//! as Cursor writes them.
+
+use std::path::{Component, Path, PathBuf};
+
+use globset::{GlobBuilder, GlobMatcher};
+use serde::Serialize;
+
+use crate::loader::SkillMetadata;
+use crate::{Error, Result};
+
+/// One compiled `globs` entry.
+#[derive(Debug, Clone)]
+pub struct Pattern {
+    /// The pattern as written, including any `!`.
+    pub source: String,
+    pub negated: bool,
+    matcher: GlobMatcher,
+}
+
+/// The compiled `globs` of one skill.
+#[derive(Debug, Clone, Default)]
+pub struct Patterns {
+    patterns: Vec<Pattern>,
+}
+
+impl Patterns {
+    pub fn new(globs: &[String]) -> std::result::Result<Self, PatternError> {
+        let patterns = globs
+            .iter()
+            .flat_map(|g| split_list(g))
+            .map(|source| compile(&source))
+            .collect::<std::result::Result<_, _>>()?;
+        Ok(Self { patterns })
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.patterns.is_empty()
+    }
+
+    pub fn patterns(&self) -> &[Pattern] {
+        &self.patterns
+    }
+
+    /// The last pattern matching `path`, which decides whether it activates
+    /// the skill. `path` is relative to the workspace root.
+    pub fn decide(&self, path: &str) -> Option<&Pattern> {
+        self.patterns
+            .iter()
+            .rev()
+            .find(|p| p.matcher.is_match(path))
+    }
+
+    pub fn is_match(&self, path: &str) -> bool {
+        self.decide(path).is_some_and(|p| !p.negated)
+    }
+
+    /// Whether any positive pattern matches `path`, regardless of negations.
+    fn includes(&self, path: &str) -> bool {
+        self.patterns
+            .iter()
+            .any(|p| !p.negated && p.matcher.is_match(path))
+    }
+}
+
+/// A glob that failed to compile.
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+#[error("invalid glob `{pattern}`: {message}")]
+pub struct PatternError {
+    pub pattern: String,
+    pub message: String,
+}
+
+/// Split `"*.ts, *.tsx"` on commas outside `{...}` alternations.
+pub(crate) fn split_list(globs: &str) -> Vec<String> {
+    let mut parts = Vec::new();
+    let mut depth = 0usize;
+    let mut current = String::new();
+    for c in globs.chars() {
+        match c {
+            '{' => depth += 1,
+            '}' => depth = depth.saturating_sub(1),
+            ',' if depth == 0 => {
+                parts.push(std::mem::take(&mut current));
+                continue;
+            }
+            _ => {}
+        }
+        current.push(c);
+    }
+    parts.push(current);
+    parts
+        .into_iter()
+        .map(|p| p.trim().to_string())
+        .filter(|p| !p.is_empty())
+        .collect()
+}
+
+/// Every comma-separated pattern in `globs` rewritten as a plain glob
+/// relative to the workspace root, with whether it was negated. This is the
+/// form tools without gitignore semantics expect.
+pub fn root_globs(globs: &[String]) -> Vec<(bool, String)> {
+    globs
+        .iter()
+        .flat_map(|g| split_list(g))
+        .map(|source| root_glob(&source))
+        .collect()
+}
+
+fn root_glob(source: &str) -> (bool, String) {
+    let (negated, body) = match source.strip_prefix('!') {
+        Some(rest) => (true, rest),
+        None => (false, source),
+    };
+    // A trailing `/` only says the pattern names a directory; it does not
+    // anchor it, so it comes off before looking for a separator.
+    let (dir, body) = match body.strip_suffix('/') {
+        Some(rest) => (true, rest),
+        None => (false, body),
+    };
+    let mut glob = match body.strip_prefix('/') {
+        Some(anchored) => anchored.to_string(),
+        None if !body.contains('/') => format!("**/{body}"),
+        None => body.to_string(),
+    };
+    if dir {
+        glob.push_str("/**");
+    }
+    (negated, glob)
+}
+
+fn compile(source: &str) -> std::result::Result<Pattern, PatternError> {
+    let (negated, glob) = root_glob(source);
+    let matcher = GlobBuilder::new(&glob)
+        .literal_separator(true)
+        .build()
+        .map_err(|e| PatternError {
+            pattern: source.to_string(),
+            message: e.kind().to_string(),
+        })?
+        .compile_matcher();
+    Ok(Pattern {
+        source: source.to_string(),
+        negated,
+        matcher,
+    })
+}
+
+/// Why a file does or does not activate a skill.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct FileMatch {
+    pub file: PathBuf,
+    /// The deciding pattern as written in `globs`.
+    pub pattern: String,
+}
+
+/// The outcome for one skill whose patterns matched at least one file.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Activation {
+    pub skill: String,
+    /// Files that activate the skill.
+    pub matched: Vec<FileMatch>,
+    /// Files a positive pattern matched but a later `!` pattern excluded.
+    pub excluded: Vec<FileMatch>,
+}
+
+impl Activation {
+    pub fn is_active(&self) -> bool {
+        !self.matched.is_empty()
+    }
+}
+
+/// Evaluates every skill's `globs` against workspace files.
+#[derive(Debug, Clone, Default)]
+pub struct Activator {
+    root: Option<PathBuf>,
+    skills: Vec<(String, Patterns)>,
+}
+
+impl Activator {
+    /// Compile the `globs` of every skill. Skills without globs never
+    /// activate by file.
+    pub fn new<'a>(skills: impl IntoIterator<Item = &'a SkillMetadata>) -> Result<Self> {
+        let skills = skills
+            .into_iter()
+            .filter(|s| !s.globs.is_empty())
+            .map(|s| {
+                Patterns::new(&s.globs)
+                    .map(|p| (s.name.clone(), p))
+                    .map_err(|source| Error::Glob {
+                        skill: s.name.clone(),
+                        source,
+                    })
+            })
+            .collect::<Result<_>>()?;
+        Ok(Self { root: None, skills })
+    }
+
+    /// Workspace root that absolute file paths are made relative to.
+    pub fn root(mut self, root: impl Into<PathBuf>) -> Self {
+        self.root = Some(root.into());
+        self
+    }
+
+    /// Skills matched by any of `files`, in skill order. Skills whose
+    /// patterns only excluded files are included with nothing `matched`.
+    pub fn activate(&self, files: &[impl AsRef<Path>]) -> Vec<Activation> {
+        let files: Vec<(PathBuf, String)> = files
+            .iter()
+            .map(|f| {
+                let f = f.as_ref();
+                (f.to_path_buf(), self.normalize(f))
+            })
+            .collect();
+        self.skills
+            .iter()
+            .filter_map(|(skill, patterns)| {
+                let mut activation = Activation {
+                    skill: skill.clone(),
+                    matched: Vec::new(),
+                    excluded: Vec::new(),
+                };
+                for (file, normalized) in &files {
+                    let Some(pattern) = patterns.decide(normalized) else {
+                        continue;
+                    };
+                    let entry = FileMatch {
+                        file: file.clone(),
+                        pattern: pattern.source.clone(),
+                    };
+                    if !pattern.negated {
+                        activation.matched.push(entry);
+                    } else if patterns.includes(normalized) {
+                        activation.excluded.push(entry);
+                    }
+                }
+                let touched = !activation.matched.is_empty() || !activation.excluded.is_empty();
+                touched.then_some(activation)
+            })
+            .collect()
+    }
+
+    /// Names of the skills `files` activate.
+    pub fn active(&self, files: &[impl AsRef<Path>]) -> Vec<String> {
+        self.activate(files)
+            .into_iter()
+            .filter(Activation::is_active)
+            .map(|a| a.skill)
+            .collect()
+    }
+
+    /// Forward-slash path relative to the root, without `./` or `..`.
+    fn normalize(&self, file: &Path) -> String {
+        let file = match &self.root {
+            Some(root) if file.is_absolute() => {
+                let root = root.canonicalize().unwrap_or_else(|_| root.clone());
+                file.strip_prefix(&root).unwrap_or(file).to_path_buf()
+            }
+            _ => file.to_path_buf(),
+        };
+        let mut parts: Vec<String> = Vec::new();
+        for component in file.components() {
+            match component {
+                Component::Normal(part) => parts.push(part.to_string_lossy().into_owned()),
+                Component::ParentDir => {
+                    parts.pop();
+                }
+                _ => {}
+            }
+        }
+        parts.join("/")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn patterns(globs: &[&str]) -> Patterns {
+        let globs: Vec<String> = globs.iter().map(|g| g.to_string()).collect();
+        Patterns::new(&globs).unwrap()
+    }
+
+    fn metadata(name: &str, globs: &[&str]) -> SkillMetadata {
+        SkillMetadata {
+            name: name.into(),
+            description: String::new(),
+            globs: globs.iter().map(|g| g.to_string()).collect(),
+            path: PathBuf::from(name),
+            tokens: 0,
+        }
+    }
+
+    #[test]
+    fn root_globs_follow_gitignore_anchoring() {
+        let cases = [
+            ("*.rs", "**/*.rs"),
+            ("dir/", "**/dir/**"),
+            ("node_modules/", "**/node_modules/**"),
+            ("/dir", "dir"),
+            ("/dir/", "dir/**"),
+            ("a/b", "a/b"),
+            ("a/b/", "a/b/**"),
+        ];
+        for (source, expected) in cases {
+            assert_eq!(root_glob(source), (false, expected.to_string()), "{source}");
+        }
+        assert_eq!(root_glob("!/dist/"), (true, "dist/**".to_string()));
+    }
+
+    #[test]
+    fn directory_patterns_match_at_any_depth() {
+        let p = patterns(&["node_modules/"]);
+        assert!(p.is_match("node_modules/a.js"));
+        assert!(p.is_match("pkg/node_modules/a.js"));
+        assert!(!p.is_match("node_modules.js"));
+    }
+
+    #[test]
+    fn anchored_patterns_only_match_from_the_root() {
+        let p = patterns(&["/dir"]);
+        assert!(p.is_match("dir"));
+        assert!(!p.is_match("src/dir"));
+        let p = patterns(&["a/b"]);
+        assert!(p.is_match("a/b"));
+        assert!(!p.is_match("x/a/b"));
+    }
+
+    #[test]
+    fn name_patterns_do_not_cross_directories() {
+        let p = patterns(&["*.rs"]);
+        assert!(p.is_match("main.rs"));
+        assert!(p.is_match("src/bin/main.rs"));
+        let p = patterns(&["src/*.rs"]);
+        assert!(!p.is_match("src/bin/main.rs"));
+    }
+
+    #[test]
+    fn the_last_matching_pattern_decides() {
+        let p = patterns(&["*.ts", "!*.d.ts", "/types/keep.d.ts"]);
+        assert!(p.is_match("src/app.ts"));
+        assert!(!p.is_match("src/app.d.ts"));
+        assert!(p.is_match("types/keep.d.ts"));
+        assert_eq!(p.decide("src/app.d.ts").unwrap().source, "!*.d.ts");
+    }
+
+    #[test]
+    fn comma_lists_split_outside_alternations() {
+        assert_eq!(split_list("*.ts, *.{js,jsx} ,,"), ["*.ts", "*.{js,jsx}"]);
+        assert_eq!(patterns(&["*.ts, *.{js,jsx}"]).patterns().len(), 2);
+    }
+
+    #[test]
+    fn invalid_globs_name_the_pattern() {
+        let error = Patterns::new(&["src/[".to_string()]).unwrap_err();
+        assert_eq!(error.pattern, "src/[");
+        let skills = [metadata("broken", &["src/["])];
+        assert!(matches!(
+            Activator::new(&skills),
+            Err(Error::Glob { skill, .. }) if skill == "broken"
+        ));
+    }
+
+    #[test]
+    fn activation_reports_matched_and_excluded_files() {
+        let skills = [
+            metadata("typescript", &["*.ts", "!*.d.ts"]),
+            metadata("docs", &["docs/"]),
+            metadata("prose", &[]),
+        ];
+        let activator = Activator::new(&skills).unwrap();
+        let activations = activator.activate(&["src/app.ts", "./src/../lib/index.d.ts"]);
+        assert_eq!(activations.len(), 1);
+        let typescript = &activations[0];
+        assert_eq!(typescript.matched[0].file, Path::new("src/app.ts"));
+        assert_eq!(typescript.excluded[0].pattern, "!*.d.ts");
+        assert_eq!(activator.active(&["docs/intro.md"]), ["docs"]);
+        assert!(activator.active(&["lib/index.d.ts"]).is_empty());
+    }
+
+    #[test]
+    fn absolute_paths_are_made_relative_to_the_root() {
+        let root = std::env::temp_dir();
+        let activator = Activator::new(&[metadata("docs", &["/docs/"])])
+            .unwrap()
+            .root(&root);
+        let file = root.canonicalize().unwrap().join("docs/intro.md");
+        assert_eq!(activator.active(&[file]), ["docs"]);
+    }
+}
@@ -0,0 +1,236 @@
+//! Project settings read from `skills.toml` at the project root.
+//!
+//! ```toml
+//! [lint]
+//! max-lines = 500
+//! allow = ["encoded-payload"]  # rules silenced for every skill
+//!
+//! [tokens]
+//! tokenizer = "cl100k"
+//!
+//! [tokens.budgets]
+//! metadata = 100       # Level 1, per skill
+//! instructions = 5000  # Level 2, per skill
+//! startup = 1000       # Level 1, all skills together
+//! reference = 0        # Level 3, per file; 0 disables a budget
+//!
+//! [export]
+//! inline-threshold = 2000  # inline references up to this many tokens
+//!
+//! [scripts]
+//! timeout = 30             # seconds
+//! max-output = 32768       # bytes kept per stream
+//! env = ["NODE_ENV"]       # passed through on top of the defaults
+//! network = false
+//! sandbox = true
+//!
+//! [trust]
+//! policy = "require-signature"  # or "warn" (default) or "off"
+//!
+//! [trust.keys]
+//! alice = "ed25519:0Oq3...="   # publishers whose packages are trusted
+//!
+//! [registry]
+//! url = "http://skills.internal:8080"
+//! ```
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+
+use serde::Deserialize;
+
+use crate::signing::{Policy, PublicKey};
+use crate::{Error, Result};
+
+pub const CONFIG_FILE: &str = "skills.toml";
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Config {
+    pub lint: LintConfig,
+    pub tokens: TokensConfig,
+    pub export: ExportConfig,
+    pub scripts: ScriptsConfig,
+    pub trust: TrustConfig,
+    pub registry: RegistryConfig,
+}
+
+impl Config {
+    /// Read a `skills.toml`. A missing file yields the defaults.
+    pub fn load(path: &Path) -> Result<Self> {
+        let text = match fs::read_to_string(path) {
+            Ok(text) => text,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Self::default()),
+            Err(e) => return Err(Error::io(path, e)),
+        };
+        toml::from_str(&text).map_err(|e| Error::Config {
+            path: path.to_path_buf(),
+            message: e.message().to_string(),
+        })
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(default, rename_all = "kebab-case", deny_unknown_fields)]
+pub struct LintConfig {
+    /// Maximum lines in a `SKILL.md`.
+    pub max_lines: usize,
+    /// Rules silenced for every skill. Unlike a skill's own `lint.allow`,
+    /// this may name security rules.
+    pub allow: Vec<String>,
+}
+
+impl Default for LintConfig {
+    fn default() -> Self {
+        Self {
+            max_lines: 500,
+            allow: Vec::new(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct TokensConfig {
+    /// Tokenizer name, see [`crate::tokens::tokenizer`]. Defaults to the
+    /// bundled BPE vocabulary when available.
+    pub tokenizer: Option<String>,
+    pub budgets: Budgets,
+}
+
+/// Token budgets per progressive-disclosure level. Zero disables a budget.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Budgets {
+    /// Level 1 metadata of a single skill.
+    pub metadata: usize,
+    /// Level 2 cost of a whole `SKILL.md`.
+    pub instructions: usize,
+    /// Level 1 metadata of every skill together, paid at session start.
+    pub startup: usize,
+    /// Level 3 cost of a single file under `references/`.
+    pub reference: usize,
+}
+
+impl Default for Budgets {
+    /// The figures the README promises: ~100 tokens of metadata and under
+    /// 5k tokens of instructions per skill.
+    fn default() -> Self {
+        Self {
+            metadata: 100,
+            instructions: 5000,
+            startup: 0,
+            reference: 0,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(default, rename_all = "kebab-case", deny_unknown_fields)]
+pub struct ExportConfig {
+    /// References of up to this many tokens are copied into exported rules;
+    /// larger ones are linked. Zero links every reference.
+    pub inline_threshold: usize,
+}
+
+impl Default for ExportConfig {
+    fn default() -> Self {
+        Self {
+            inline_threshold: crate::export::DEFAULT_INLINE_THRESHOLD,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(default, rename_all = "kebab-case", deny_unknown_fields)]
+pub struct ScriptsConfig {
+    /// Seconds a script may run.
+    pub timeout: u64,
+    /// Bytes kept from each of stdout and stderr. Zero keeps everything.
+    pub max_output: usize,
+    /// Environment variables passed through in addition to
+    /// [`crate::script::DEFAULT_ENV`].
+    pub env: Vec<String>,
+    /// Let sandboxed scripts use the network.
+    pub network: bool,
+    /// Isolate scripts; see [`crate::script::Runner::sandbox`].
+    pub sandbox: bool,
+}
+
+impl Default for ScriptsConfig {
+    fn default() -> Self {
+        Self {
+            timeout: crate::script::DEFAULT_TIMEOUT.as_secs(),
+            max_output: crate::script::DEFAULT_MAX_OUTPUT,
+            env: Vec::new(),
+            network: false,
+            sandbox: true,
+        }
+    }
+}
+
+/// Who may publish `.skill` packages to this project.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct TrustConfig {
+    /// Whether unpacking requires a signature; see [`crate::signing::check`].
+    pub policy: Policy,
+    /// Trusted public keys by publisher name.
+    pub keys: BTreeMap<String, PublicKey>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct RegistryConfig {
+    /// Registry the `skills registry` client talks to.
+    pub url: Option<String>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::TempDir;
+
+    #[test]
+    fn a_missing_file_yields_the_defaults() {
+        let dir = TempDir::new();
+        let config = Config::load(&dir.path().join(CONFIG_FILE)).unwrap();
+        assert_eq!(config, Config::default());
+        assert_eq!(config.lint.max_lines, 500);
+        assert_eq!(config.tokens.budgets.metadata, 100);
+    }
+
+    #[test]
+    fn budgets_override_only_the_keys_given() {
+        let dir = TempDir::new();
+        let path = dir.write(
+            CONFIG_FILE,
+            "[tokens]\ntokenizer = \"chars\"\n\n[tokens.budgets]\nstartup = 2000\nmetadata = 0\n",
+        );
+        let config = Config::load(&path).unwrap();
+        assert_eq!(config.tokens.tokenizer.as_deref(), Some("chars"));
+        assert_eq!(
+            config.tokens.budgets,
+            Budgets {
+                metadata: 0,
+                instructions: 5000,
+                startup: 2000,
+                reference: 0,
+            }
+        );
+    }
+
+    #[test]
+    fn unknown_keys_are_rejected_with_the_path() {
+        let dir = TempDir::new();
+        let path = dir.write(CONFIG_FILE, "[tokens.budgets]\nlevel1 = 10\n");
+        match Config::load(&path) {
+            Err(Error::Config { path: at, message }) => {
+                assert_eq!(at, path);
+                assert!(message.contains("level1"), "{message}");
+            }
+            other => panic!("expected a config error, got {other:?}"),
+        }
+    }
+}
@@ -0,0 +1,861 @@
+//! Skills that overlap or contradict each other.
+//!
+//! "Evitar: duplicar información entre skills" gets hard to keep once a
+//! project has `npm-extension`, `pnpm-extension` and `bun-extension` side by
+//! side. [`Detector`] compares every pair of skills and reports:
+//!
+//! - overlapping activation: globs that match the same files, and
+//!   descriptions that share most of their terms, so both skills are likely
+//!   to be loaded for the same request;
+//! - duplicated paragraphs, found by comparing sets of word shingles so
+//!   that light rewording still counts;
+//! - contradicting commands: different package managers recommended for
+//!   the same task, or a command one skill recommends and the other warns
+//!   against.
+//!
+//! Globs are compared on example paths built from both skills' patterns
+//! rather than on the project's files, so an overlap shows up before any
+//! matching file exists.
+
+use std::collections::hash_map::DefaultHasher;
+use std::collections::{BTreeMap, BTreeSet, HashSet};
+use std::fmt;
+use std::hash::{Hash, Hasher};
+
+use serde::Serialize;
+
+use crate::activation::{root_globs, split_list, Patterns};
+use crate::parse::{heading, markdown_lines};
+use crate::project::Project;
+use crate::relevance::{fold, Analyzer};
+use crate::skill::Skill;
+use crate::span::LineIndex;
+use crate::Result;
+
+/// Words per shingle when comparing paragraphs.
+const SHINGLE: usize = 4;
+
+/// Paragraphs shorter than this, in words, are never reported as
+/// duplicates; short lines such as "Instalar dependencias" are expected to
+/// repeat.
+const MIN_PARAGRAPH_WORDS: usize = 8;
+
+/// Characters of a paragraph quoted in a report.
+const EXCERPT: usize = 72;
+
+/// What a recommended command is for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Task {
+    Install,
+    Add,
+    Remove,
+    Run,
+    Execute,
+}
+
+impl Task {
+    const ALL: [Task; 5] = [
+        Task::Install,
+        Task::Add,
+        Task::Remove,
+        Task::Run,
+        Task::Execute,
+    ];
+}
+
+impl fmt::Display for Task {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Task::Install => "install dependencies",
+            Task::Add => "add a dependency",
+            Task::Remove => "remove a dependency",
+            Task::Run => "run a script",
+            Task::Execute => "run a package binary",
+        })
+    }
+}
+
+/// A path both skills' globs match, and the pattern deciding it in each.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct GlobOverlap {
+    pub path: String,
+    pub a: String,
+    pub b: String,
+}
+
+/// Descriptions sharing enough terms to compete for the same requests.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct DescriptionOverlap {
+    /// Jaccard similarity of the descriptions' stems, from 0 to 1.
+    pub similarity: f64,
+    /// Words of the first description that the second one shares.
+    pub shared: Vec<String>,
+}
+
+/// The start of a paragraph and its 1-based line in `SKILL.md`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Excerpt {
+    pub line: usize,
+    pub text: String,
+}
+
+/// A paragraph of one skill that another repeats, possibly reworded.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Duplicate {
+    /// Jaccard similarity of the paragraphs' shingles, from 0 to 1.
+    pub similarity: f64,
+    pub a: Excerpt,
+    pub b: Excerpt,
+}
+
+/// A package manager command found in a skill's body.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Recommendation {
+    /// `npm`, `pnpm`, `yarn` or `bun`; `npx` and `bunx` count as `npm` and
+    /// `bun`.
+    pub tool: String,
+    pub task: Task,
+    pub command: String,
+    pub line: usize,
+    /// Whether the text around the command warns against it ("never",
+    /// "instead of", "en lugar de", ...).
+    pub discouraged: bool,
+}
+
+/// Two skills disagreeing on how to do `task`: they recommend different
+/// tools, or one discourages what the other recommends.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Contradiction {
+    pub task: Task,
+    pub a: Recommendation,
+    pub b: Recommendation,
+}
+
+/// Everything found for one pair of skills, `a` before `b` by name.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct PairReport {
+    pub a: String,
+    pub b: String,
+    pub globs: Vec<GlobOverlap>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub descriptions: Option<DescriptionOverlap>,
+    pub duplicates: Vec<Duplicate>,
+    pub contradictions: Vec<Contradiction>,
+}
+
+impl PairReport {
+    /// Whether both skills are likely to be active at the same time.
+    pub fn overlaps(&self) -> bool {
+        !self.globs.is_empty() || self.descriptions.is_some()
+    }
+
+    /// Whether the skills repeat or contradict each other, as opposed to
+    /// merely overlapping.
+    pub fn has_conflicts(&self) -> bool {
+        !self.duplicates.is_empty() || !self.contradictions.is_empty()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        !self.overlaps() && !self.has_conflicts()
+    }
+}
+
+#[derive(Debug, Clone)]
+struct Paragraph {
+    excerpt: Excerpt,
+    shingles: HashSet<u64>,
+}
+
+/// What the detector keeps of a skill.
+#[derive(Debug, Clone)]
+struct Profile {
+    patterns: Patterns,
+    examples: Vec<String>,
+    words: Vec<(String, Vec<String>)>,
+    stems: BTreeSet<String>,
+    paragraphs: Vec<Paragraph>,
+    recommendations: Vec<Recommendation>,
+}
+
+/// Compares skills pairwise.
+#[derive(Debug, Clone)]
+pub struct Detector {
+    skills: BTreeMap<String, Profile>,
+    description_threshold: f64,
+    duplicate_threshold: f64,
+}
+
+impl Default for Detector {
+    fn default() -> Self {
+        Self {
+            skills: BTreeMap::new(),
+            description_threshold: 0.5,
+            duplicate_threshold: 0.5,
+        }
+    }
+}
+
+impl Detector {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The skills installed in `project`, by
+    /// [`SkillDir::skill_name`](crate::SkillDir::skill_name). Skills that
+    /// fail to parse are left out; `skills lint` reports them.
+    pub fn from_project(project: &Project) -> Result<Self> {
+        let mut detector = Self::new();
+        for dir in project.skills()? {
+            if let Ok(skill) = Skill::from_path(dir.skill_file()) {
+                detector = detector.skill(dir.skill_name(&skill), &skill);
+            }
+        }
+        Ok(detector)
+    }
+
+    pub fn skill(mut self, name: impl Into<String>, skill: &Skill) -> Self {
+        self.skills.insert(name.into(), profile(skill));
+        self
+    }
+
+    /// Similarity from which two descriptions count as overlapping; 0.5 by
+    /// default.
+    pub fn description_threshold(mut self, threshold: f64) -> Self {
+        self.description_threshold = threshold;
+        self
+    }
+
+    /// Similarity from which two paragraphs count as duplicates; 0.5 by
+    /// default.
+    pub fn duplicate_threshold(mut self, threshold: f64) -> Self {
+        self.duplicate_threshold = threshold;
+        self
+    }
+
+    pub fn contains(&self, name: &str) -> bool {
+        self.skills.contains_key(name)
+    }
+
+    /// A report for every pair with findings, sorted by name.
+    pub fn reports(&self) -> Vec<PairReport> {
+        let names: Vec<&String> = self.skills.keys().collect();
+        let mut reports = Vec::new();
+        for (i, a) in names.iter().enumerate() {
+            for b in &names[i + 1..] {
+                let report = self.compare(a, &self.skills[*a], b, &self.skills[*b]);
+                if !report.is_empty() {
+                    reports.push(report);
+                }
+            }
+        }
+        reports
+    }
+
+    /// The report for `a` and `b`, empty or not, or `None` when either is
+    /// unknown.
+    pub fn pair(&self, a: &str, b: &str) -> Option<PairReport> {
+        let (a, b) = if a <= b { (a, b) } else { (b, a) };
+        Some(self.compare(a, self.skills.get(a)?, b, self.skills.get(b)?))
+    }
+
+    fn compare(&self, a_name: &str, a: &Profile, b_name: &str, b: &Profile) -> PairReport {
+        PairReport {
+            a: a_name.to_string(),
+            b: b_name.to_string(),
+            globs: glob_overlaps(a, b),
+            descriptions: description_overlap(a, b)
+                .filter(|d| d.similarity >= self.description_threshold),
+            duplicates: duplicates(a, b, self.duplicate_threshold),
+            contradictions: contradictions(a, b),
+        }
+    }
+}
+
+fn profile(skill: &Skill) -> Profile {
+    let index = LineIndex::new(skill.source());
+    let patterns = Patterns::new(&skill.frontmatter().globs).unwrap_or_default();
+    let examples = patterns
+        .patterns()
+        .iter()
+        .filter(|p| !p.negated)
+        .flat_map(|p| root_globs(std::slice::from_ref(&p.source)))
+        .flat_map(|(_, glob)| examples(&glob))
+        .collect();
+    let words = Analyzer::new().analyze(skill.description().unwrap_or_default());
+    let stems = words.iter().flat_map(|(_, s)| s.iter().cloned()).collect();
+    Profile {
+        patterns,
+        examples,
+        words,
+        stems,
+        paragraphs: paragraphs(skill, &index),
+        recommendations: recommendations(skill, &index),
+    }
+}
+
+/// Paths a root-relative glob matches, each wildcard filled in plainly:
+/// nothing for `**/`, `x` for `*` and `?`, the first character of a class
+/// and one path per `{a,b}` alternative.
+fn examples(glob: &str) -> Vec<String> {
+    let mut path = String::new();
+    let mut rest = glob;
+    while let Some(c) = rest.chars().next() {
+        if let Some(after) = rest.strip_prefix("**/") {
+            rest = after;
+            continue;
+        }
+        match c {
+            '*' => {
+                path.push('x');
+                rest = rest.trim_start_matches('*');
+            }
+            '?' => {
+                path.push('x');
+                rest = &rest[1..];
+            }
+            '[' if rest.contains(']') => {
+                let end = rest.find(']').unwrap_or(rest.len());
+                let first = rest[1..end]
+                    .chars()
+                    .next()
+                    .filter(|c| !matches!(c, '!' | '^'))
+                    .unwrap_or('x');
+                path.push(first);
+                rest = &rest[end + 1..];
+            }
+            '{' => {
+                let Some(close) = closing_brace(rest) else {
+                    path.push(c);
+                    rest = &rest[1..];
+                    continue;
+                };
+                let after = &rest[close + 1..];
+                return split_list(&rest[1..close])
+                    .iter()
+                    .flat_map(|alternative| examples(&format!("{alternative}{after}")))
+                    .map(|tail| format!("{path}{tail}"))
+                    .collect();
+            }
+            '\\' => {
+                rest = &rest[1..];
+                if let Some(escaped) = rest.chars().next() {
+                    path.push(escaped);
+                    rest = &rest[escaped.len_utf8()..];
+                }
+            }
+            _ => {
+                path.push(c);
+                rest = &rest[c.len_utf8()..];
+            }
+        }
+    }
+    vec![path]
+}
+
+fn closing_brace(glob: &str) -> Option<usize> {
+    let mut depth = 0usize;
+    for (i, c) in glob.char_indices() {
+        match c {
+            '{' => depth += 1,
+            '}' => {
+                depth -= 1;
+                if depth == 0 {
+                    return Some(i);
+                }
+            }
+            _ => {}
+        }
+    }
+    None
+}
+
+/// Paths both skills activate on, one per pair of deciding patterns. Each
+/// skill's examples are tried, and so is every file name of one placed in
+/// every directory of the other, which catches `src/**` against `*.ts`.
+fn glob_overlaps(a: &Profile, b: &Profile) -> Vec<GlobOverlap> {
+    let mut candidates: Vec<String> = a.examples.iter().chain(&b.examples).cloned().collect();
+    for x in &a.examples {
+        for y in &b.examples {
+            for (dir, file) in [(x, y), (y, x)] {
+                if let (Some((dir, _)), Some(file)) =
+                    (dir.rsplit_once('/'), file.rsplit('/').next())
+                {
+                    candidates.push(format!("{dir}/{file}"));
+                }
+            }
+        }
+    }
+    let mut overlaps: Vec<GlobOverlap> = Vec::new();
+    for path in candidates {
+        let (Some(pa), Some(pb)) = (a.patterns.decide(&path), b.patterns.decide(&path)) else {
+            continue;
+        };
+        if pa.negated || pb.negated {
+            continue;
+        }
+        if !overlaps
+            .iter()
+            .any(|o| o.a == pa.source && o.b == pb.source)
+        {
+            overlaps.push(GlobOverlap {
+                a: pa.source.clone(),
+                b: pb.source.clone(),
+                path,
+            });
+        }
+    }
+    overlaps
+}
+
+fn description_overlap(a: &Profile, b: &Profile) -> Option<DescriptionOverlap> {
+    let union = a.stems.union(&b.stems).count();
+    if union == 0 || a.stems.is_empty() || b.stems.is_empty() {
+        return None;
+    }
+    let similarity = a.stems.intersection(&b.stems).count() as f64 / union as f64;
+    let mut shared: Vec<String> = Vec::new();
+    for (word, stems) in &a.words {
+        if stems.iter().any(|s| b.stems.contains(s)) && !shared.contains(word) {
+            shared.push(word.clone());
+        }
+    }
+    Some(DescriptionOverlap { similarity, shared })
+}
+
+/// Runs of prose lines separated by blank lines, headings and code.
+fn paragraphs(skill: &Skill, index: &LineIndex) -> Vec<Paragraph> {
+    let mut found = Vec::new();
+    let mut current: Option<(usize, Vec<&str>)> = None;
+    for (line, code) in markdown_lines(skill.source(), skill.body_span().range()) {
+        let text = line.text.trim();
+        if code || text.is_empty() || heading(text).is_some() {
+            found.extend(current.take().and_then(|p| paragraph(p, index)));
+            continue;
+        }
+        current.get_or_insert((line.start, Vec::new())).1.push(text);
+    }
+    found.extend(current.and_then(|p| paragraph(p, index)));
+    found
+}
+
+fn paragraph((start, lines): (usize, Vec<&str>), index: &LineIndex) -> Option<Paragraph> {
+    let text = lines.join(" ");
+    let words: Vec<String> = text
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|w| !w.is_empty())
+        .map(|w| fold(&w.to_lowercase()))
+        .collect();
+    if words.len() < MIN_PARAGRAPH_WORDS {
+        return None;
+    }
+    let shingles = words
+        .windows(SHINGLE)
+        .map(|shingle| {
+            let mut hasher = DefaultHasher::new();
+            shingle.hash(&mut hasher);
+            hasher.finish()
+        })
+        .collect();
+    let text = match text.char_indices().nth(EXCERPT) {
+        Some((end, _)) => format!("{}…", text[..end].trim_end()),
+        None => text,
+    };
+    Some(Paragraph {
+        excerpt: Excerpt {
+            line: index.position(start).line,
+            text,
+        },
+        shingles,
+    })
+}
+
+/// Each paragraph of `a` with the most similar one of `b`, when they are
+/// similar enough.
+fn duplicates(a: &Profile, b: &Profile, threshold: f64) -> Vec<Duplicate> {
+    let mut found = Vec::new();
+    for pa in &a.paragraphs {
+        let best = b
+            .paragraphs
+            .iter()
+            .map(|pb| (jaccard(&pa.shingles, &pb.shingles), pb))
+            .max_by(|x, y| x.0.total_cmp(&y.0));
+        if let Some((similarity, pb)) = best.filter(|(s, _)| *s >= threshold) {
+            found.push(Duplicate {
+                similarity,
+                a: pa.excerpt.clone(),
+                b: pb.excerpt.clone(),
+            });
+        }
+    }
+    found
+}
+
+fn jaccard(a: &HashSet<u64>, b: &HashSet<u64>) -> f64 {
+    let union = a.union(b).count();
+    match union {
+        0 => 0.0,
+        _ => a.intersection(b).count() as f64 / union as f64,
+    }
+}
+
+/// Package manager commands in code blocks and inline code. A code block
+/// is discouraged as a whole when the prose line introducing it is, as in
+/// "Nunca uses:".
+fn recommendations(skill: &Skill, index: &LineIndex) -> Vec<Recommendation> {
+    let mut found = Vec::new();
+    let mut introduction = "";
+    let mut in_block = false;
+    let mut block_discouraged = false;
+    for (line, code) in markdown_lines(skill.source(), skill.body_span().range()) {
+        let text = line.text.trim();
+        let number = index.position(line.start).line;
+        if !code {
+            in_block = false;
+            if !text.is_empty() {
+                introduction = text;
+            }
+            for (prefix, command) in inline_code(text) {
+                if !command.trim().contains(' ') {
+                    continue;
+                }
+                let discouraged = discourages(prefix);
+                found.extend(recommendation(command, number, discouraged));
+            }
+            continue;
+        }
+        if !in_block {
+            in_block = true;
+            block_discouraged = introduction.ends_with(':') && discourages(introduction);
+            continue;
+        }
+        if text.starts_with('#') {
+            continue;
+        }
+        let text = text.split(" #").next().unwrap_or_default();
+        let text = text.strip_prefix("$ ").unwrap_or(text);
+        for command in text.split(['&', '|', ';']) {
+            found.extend(recommendation(command, number, block_discouraged));
+        }
+    }
+    found
+}
+
+/// Inline code spans of a line, each with the text before it.
+fn inline_code(line: &str) -> Vec<(&str, &str)> {
+    let mut spans = Vec::new();
+    let mut offset = 0;
+    for (i, segment) in line.split('`').enumerate() {
+        if i % 2 == 1 && offset + segment.len() < line.len() {
+            spans.push((&line[..offset - 1], segment));
+        }
+        offset += segment.len() + 1;
+    }
+    spans
+}
+
+fn recommendation(command: &str, line: usize, discouraged: bool) -> Option<Recommendation> {
+    let command = command.trim();
+    let (tool, task) = classify(command)?;
+    Some(Recommendation {
+        tool: tool.to_string(),
+        task,
+        command: command.to_string(),
+        line,
+        discouraged,
+    })
+}
+
+/// The package manager and task of a command such as `pnpm add -D vitest`.
+fn classify(command: &str) -> Option<(&'static str, Task)> {
+    let mut words = command
+        .split_whitespace()
+        .skip_while(|w| *w == "sudo" || w.contains('='));
+    let tool = words.next()?;
+    let args: Vec<&str> = words.collect();
+    let packages = args.iter().skip(1).any(|w| !w.starts_with('-'));
+    let install = match packages {
+        true => Task::Add,
+        false => Task::Install,
+    };
+    let found = match (tool, args.first().copied()) {
+        ("npx", _) => ("npm", Task::Execute),
+        ("bunx", _) => ("bun", Task::Execute),
+        ("npm", Some("install" | "i" | "add")) => ("npm", install),
+        ("npm", Some("ci")) => ("npm", Task::Install),
+        ("npm", Some("uninstall" | "remove" | "rm" | "un")) => ("npm", Task::Remove),
+        ("npm", Some("run" | "run-script" | "test" | "start")) => ("npm", Task::Run),
+        ("npm", Some("exec")) => ("npm", Task::Execute),
+        ("pnpm", Some("install" | "i")) => ("pnpm", install),
+        ("bun", Some("install" | "i")) => ("bun", install),
+        ("yarn", None | Some("install")) => ("yarn", Task::Install),
+        ("pnpm" | "yarn" | "bun", Some("add")) => (manager(tool), Task::Add),
+        ("pnpm" | "yarn" | "bun", Some("remove" | "rm")) | ("pnpm", Some("uninstall")) => {
+            (manager(tool), Task::Remove)
+        }
+        ("pnpm" | "yarn" | "bun", Some("run" | "test")) => (manager(tool), Task::Run),
+        ("pnpm" | "yarn", Some("dlx")) | ("pnpm", Some("exec")) | ("bun", Some("x")) => {
+            (manager(tool), Task::Execute)
+        }
+        _ => return None,
+    };
+    Some(found)
+}
+
+fn manager(tool: &str) -> &'static str {
+    match tool {
+        "pnpm" => "pnpm",
+        "yarn" => "yarn",
+        _ => "bun",
+    }
+}
+
+/// Whether the clause `prefix` ends with, the text since the last `.`,
+/// `;` or `,`, warns against what follows it.
+fn discourages(prefix: &str) -> bool {
+    let clause = prefix
+        .rsplit(['.', ';', ',', '!', '?'])
+        .next()
+        .unwrap_or_default();
+    let clause = fold(&clause.to_lowercase());
+    if clause.contains("n't")
+        || ["instead of", "rather than", "en lugar de", "en vez de"]
+            .iter()
+            .any(|phrase| clause.contains(phrase))
+    {
+        return true;
+    }
+    clause
+        .split(|c: char| !c.is_alphanumeric())
+        .any(|word| NEGATIONS.contains(&word))
+}
+
+const NEGATIONS: &[&str] = &[
+    "avoid", "never", "not", "no", "nunca", "evita", "evitar", "evites", "jamas",
+];
+
+/// Recommendations for `task` that are not discouraged.
+fn recommended(profile: &Profile, task: Task) -> Vec<&Recommendation> {
+    profile
+        .recommendations
+        .iter()
+        .filter(|r| r.task == task && !r.discouraged)
+        .collect()
+}
+
+/// For each task: both skills recommend tools but none in common, or one
+/// discourages a tool the other recommends.
+fn contradictions(a: &Profile, b: &Profile) -> Vec<Contradiction> {
+    let mut found: Vec<Contradiction> = Vec::new();
+    let mut push = |task, a: &Recommendation, b: &Recommendation| {
+        let same = |c: &Contradiction| {
+            c.task == task
+                && (c.a.tool.as_str(), c.a.discouraged) == (a.tool.as_str(), a.discouraged)
+                && (c.b.tool.as_str(), c.b.discouraged) == (b.tool.as_str(), b.discouraged)
+        };
+        if !found.iter().any(same) {
+            found.push(Contradiction {
+                task,
+                a: a.clone(),
+                b: b.clone(),
+            });
+        }
+    };
+    for task in Task::ALL {
+        let (in_a, in_b) = (recommended(a, task), recommended(b, task));
+        let agree = in_a.iter().any(|x| in_b.iter().any(|y| x.tool == y.tool));
+        if let (Some(x), Some(y), false) = (in_a.first(), in_b.first(), agree) {
+            push(task, x, y);
+        }
+        for x in &in_a {
+            let warning = b
+                .recommendations
+                .iter()
+                .find(|y| y.discouraged && y.task == task && y.tool == x.tool);
+            if let Some(y) = warning {
+                push(task, x, y);
+            }
+        }
+        for y in &in_b {
+            let warning = a
+                .recommendations
+                .iter()
+                .find(|x| x.discouraged && x.task == task && x.tool == y.tool);
+            if let Some(x) = warning {
+                push(task, x, y);
+            }
+        }
+    }
+    found
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::TempDir;
+
+    fn skill(extra: &str, body: &str) -> Skill {
+        Skill::parse(format!("---\ndescription: x\n{extra}---\n{body}")).unwrap()
+    }
+
+    fn pair(a: &Skill, b: &Skill) -> PairReport {
+        Detector::new()
+            .skill("a", a)
+            .skill("b", b)
+            .pair("a", "b")
+            .unwrap()
+    }
+
+    #[test]
+    fn examples_fill_in_wildcards() {
+        assert_eq!(examples("src/**/*.{ts,tsx}"), ["src/x.ts", "src/x.tsx"]);
+        assert_eq!(examples("[!a]?/[bc].md"), ["xx/b.md"]);
+        assert_eq!(examples("a\\*b{c"), ["a*b{c"]);
+    }
+
+    #[test]
+    fn commands_are_classified_by_tool_and_task() {
+        let cases = [
+            ("npm ci", Some(("npm", Task::Install))),
+            ("npm i -D", Some(("npm", Task::Install))),
+            ("sudo npm i -D vitest", Some(("npm", Task::Add))),
+            ("CI=1 pnpm install", Some(("pnpm", Task::Install))),
+            ("yarn", Some(("yarn", Task::Install))),
+            ("bun rm zod", Some(("bun", Task::Remove))),
+            ("pnpm test", Some(("pnpm", Task::Run))),
+            ("npx tsc", Some(("npm", Task::Execute))),
+            ("pnpm dlx create-vite", Some(("pnpm", Task::Execute))),
+            ("cargo build", None),
+            ("npm publish", None),
+        ];
+        for (command, expected) in cases {
+            assert_eq!(classify(command), expected, "{command}");
+        }
+    }
+
+    #[test]
+    fn negations_are_found_in_the_last_clause() {
+        assert!(discourages("Never run"));
+        assert!(discourages("Usa pnpm en lugar de"));
+        assert!(discourages("Nunca uses:"));
+        assert!(discourages("Don't use"));
+        assert!(!discourages("Not npm. Run"));
+        assert!(!discourages("Install with"));
+    }
+
+    #[test]
+    fn inline_code_keeps_the_text_before_each_span() {
+        assert_eq!(
+            inline_code("Use `pnpm i`, not `npm i` or `yarn"),
+            [("Use ", "pnpm i"), ("Use `pnpm i`, not ", "npm i")]
+        );
+    }
+
+    #[test]
+    fn overlapping_globs_report_the_deciding_patterns() {
+        let a = skill("globs: ['src/**', '!src/gen/**']\n", "");
+        let b = skill("globs: ['*.ts']\n", "");
+        let globs = pair(&a, &b).globs;
+        assert_eq!(globs.len(), 1);
+        assert_eq!(
+            (globs[0].a.as_str(), globs[0].b.as_str()),
+            ("src/**", "*.ts")
+        );
+
+        let c = skill("globs: ['docs/**/*.md']\n", "");
+        assert!(pair(&b, &c).globs.is_empty());
+    }
+
+    #[test]
+    fn similar_descriptions_overlap_above_the_threshold() {
+        let a = Skill::parse("---\ndescription: Install packages with pnpm\n---\n").unwrap();
+        let b = Skill::parse("---\ndescription: Install packages with npm\n---\n").unwrap();
+        let detector = Detector::new().skill("a", &a).skill("b", &b);
+        let overlap = detector.pair("b", "a").unwrap().descriptions.unwrap();
+        assert_eq!(overlap.shared, ["install", "packages"]);
+        assert!(overlap.similarity >= 0.5 && overlap.similarity < 1.0);
+        let strict = detector.description_threshold(0.9);
+        assert!(strict.pair("a", "b").unwrap().descriptions.is_none());
+    }
+
+    #[test]
+    fn reworded_paragraphs_are_duplicates_and_short_ones_are_not() {
+        let a = skill(
+            "",
+            "# A\n\nAlways run the full test suite before you push any change to the main branch.\n\nShort line here.\n",
+        );
+        let b = skill(
+            "",
+            "Intro.\n\nAlways run the full test suite before you push any change to the release branch.\n\nShort line here.\n",
+        );
+        let duplicates = pair(&a, &b).duplicates;
+        assert_eq!(duplicates.len(), 1);
+        assert_eq!((duplicates[0].a.line, duplicates[0].b.line), (6, 6));
+        assert!(duplicates[0].similarity >= 0.5);
+        let report = Detector::new()
+            .duplicate_threshold(0.95)
+            .skill("a", &a)
+            .skill("b", &b)
+            .pair("a", "b")
+            .unwrap();
+        assert!(report.duplicates.is_empty());
+    }
+
+    #[test]
+    fn different_tools_for_one_task_contradict() {
+        let a = skill("", "```sh\npnpm install\n```\n");
+        let b = skill("", "Then run `npm install` and `npm run build`.\n");
+        let report = pair(&a, &b);
+        let found: Vec<_> = report
+            .contradictions
+            .iter()
+            .map(|c| (c.task, c.a.tool.as_str(), c.b.tool.as_str()))
+            .collect();
+        assert_eq!(found, [(Task::Install, "pnpm", "npm")]);
+        assert_eq!(report.contradictions[0].a.line, 5);
+        assert!(report.has_conflicts() && !report.overlaps());
+    }
+
+    #[test]
+    fn discouraging_what_the_other_recommends_contradicts() {
+        let a = skill(
+            "",
+            "Nunca uses:\n\n```sh\nnpm install # slow\n```\n\nUse `pnpm install`.\n",
+        );
+        let b = skill("", "```sh\n$ npm install && pnpm install\n```\n");
+        let report = pair(&a, &b);
+        let found: Vec<_> = report
+            .contradictions
+            .iter()
+            .map(|c| (c.a.tool.as_str(), c.a.discouraged, c.b.tool.as_str()))
+            .collect();
+        assert_eq!(found, [("npm", true, "npm")]);
+    }
+
+    #[test]
+    fn reports_leave_out_unrelated_pairs() {
+        let dir = TempDir::new();
+        dir.write(
+            ".claude/skills/one/SKILL.md",
+            "---\nname: pnpm\ndescription: x\n---\nRun `pnpm install`.\n",
+        );
+        dir.write(
+            ".claude/skills/two/SKILL.md",
+            "---\nname: npm\ndescription: y\n---\nRun `npm install`.\n",
+        );
+        dir.write(
+            ".claude/skills/three/SKILL.md",
+            "---\nname: rust\ndescription: z\n---\nRun `cargo build`.\n",
+        );
+        let detector = Detector::from_project(&Project::new(dir.path())).unwrap();
+        assert!(detector.contains("pnpm") && !detector.contains("one"));
+        let reports = detector.reports();
+        let pairs: Vec<_> = reports
+            .iter()
+            .map(|r| (r.a.as_str(), r.b.as_str()))
+            .collect();
+        assert_eq!(pairs, [("npm", "pnpm")]);
+        assert!(detector.pair("npm", "rust").unwrap().is_empty());
+        assert_eq!(detector.pair("npm", "missing"), None);
+    }
+}
@@ -0,0 +1,82 @@
+//! Crate-wide error type.
+
+use std::io;
+use std::path::{Path, PathBuf};
+
+use crate::activation::PatternError;
+use crate::lint::Diagnostic;
+use crate::parse::ParseError;
+use crate::resolve::Conflict;
+use crate::secrets::Leak;
+
+pub type Result<T, E = Error> = std::result::Result<T, E>;
+
+#[derive(Debug, thiserror::Error)]
+pub enum Error {
+    #[error("{}: {source}", path.display())]
+    Io { path: PathBuf, source: io::Error },
+    #[error("{}:{source}", path.display())]
+    Parse { path: PathBuf, source: ParseError },
+    #[error("{}: {message}", path.display())]
+    Config { path: PathBuf, message: String },
+    #[error("{}: {message}", path.display())]
+    Package { path: PathBuf, message: String },
+    #[error("{}: {message}", path.display())]
+    Signature { path: PathBuf, message: String },
+    #[error("`{skill}` {version} is already published; bump its `version`")]
+    AlreadyPublished { skill: String, version: String },
+    #[error("registry {url}: {message}")]
+    Registry { url: String, message: String },
+    #[error("{0}")]
+    Conflict(Box<Conflict>),
+    #[error("skills depend on each other: {}", .0.join(" -> "))]
+    Cycle(Vec<String>),
+    #[error("skill `{skill}`: {source}")]
+    Glob { skill: String, source: PatternError },
+    #[error("`{0}` is not a valid skill name; use kebab-case like `my-new-skill`")]
+    InvalidName(String),
+    #[error("{} already exists; pass --force to overwrite", .0.display())]
+    AlreadyExists(PathBuf),
+    #[error("skill `{skill}` is already installed from {location}; use `skills update`")]
+    AlreadyInstalled { skill: String, location: String },
+    #[error("skill `{0}` is not installed")]
+    NotInstalled(String),
+    #[error("skill `{0}` was edited since it was installed; pass --force to discard the changes")]
+    LocallyModified(String),
+    #[error("no skill `{skill}` in {location}")]
+    NotInSource { skill: String, location: String },
+    #[error("`{command}` failed: {message}")]
+    Git { command: String, message: String },
+    #[error("no skill named `{0}`")]
+    UnknownSkill(String),
+    #[error("skill `{name}` is defined twice, in {} and {}", first.display(), second.display())]
+    DuplicateSkill {
+        name: String,
+        first: PathBuf,
+        second: PathBuf,
+    },
+    #[error("`{}` is outside skill `{skill}`", path.display())]
+    OutsideSkill { skill: String, path: PathBuf },
+    #[error("unknown tokenizer `{0}`")]
+    UnknownTokenizer(String),
+    #[error("unknown export target `{0}`")]
+    UnknownTarget(String),
+    #[error("cannot sandbox script: {0}")]
+    Sandbox(String),
+    #[error("skill `{skill}` hides content from review; inspect it, then pass --allow-hidden to install it anyway")]
+    HiddenContent {
+        skill: String,
+        findings: Vec<Diagnostic>,
+    },
+    #[error("skills contain {} not accepted in skills.secrets.toml; remove them or run `skills secrets --update-baseline`", crate::secrets::count(.0.len()))]
+    Secrets(Vec<Leak>),
+}
+
+impl Error {
+    pub(crate) fn io(path: impl AsRef<Path>, source: io::Error) -> Self {
+        Error::Io {
+            path: path.as_ref().to_path_buf(),
+            source,
+        }
+    }
+}
@@ -0,0 +1,144 @@
+//! Amp: an `AGENT.md` with the global instructions that `@`-mentions one
+//! `.amp/rules/<name>.md` per skill.
+//!
+//! Amp only pulls a mentioned file into context when the agent reads a
+//! file matching the mention's `globs` frontmatter, which is how skills
+//! activate by file there:
+//!
+//! ```text
+//! ---
+//! description: "Workflow para testing de APIs con Postman y Jest"
+//! globs: ["*.test.ts"]
+//! ---
+//! ```
+
+use std::fmt::Write as _;
+use std::path::{Path, PathBuf};
+
+use super::{ExportedFile, Exporter, Fields, ReferencePolicy, Rule, GLOBAL_RULE};
+
+pub const AGENT_FILE: &str = "AGENT.md";
+/// Directory of the mentioned per-skill files.
+pub const RULES_DIR: &str = ".amp/rules";
+
+/// Exports a project as `AGENT.md` plus mentioned rule files.
+#[derive(Clone)]
+pub struct Amp {
+    references: ReferencePolicy,
+}
+
+impl Amp {
+    pub fn new(references: ReferencePolicy) -> Self {
+        Self { references }
+    }
+}
+
+impl Exporter for Amp {
+    fn target(&self) -> &'static str {
+        "amp"
+    }
+
+    fn references(&self) -> &ReferencePolicy {
+        &self.references
+    }
+
+    /// An `@` mention from the repository root.
+    fn link(&self, target: &Path) -> String {
+        format!("@{}", super::slash(target))
+    }
+
+    fn render(&self, rules: &[Rule]) -> Vec<ExportedFile> {
+        let mut index = String::new();
+        let mut files = Vec::new();
+        for rule in rules {
+            if rule.always {
+                index.push_str(&rule.body);
+                index.push('\n');
+                continue;
+            }
+            let path = PathBuf::from(RULES_DIR).join(format!("{}.md", rule.name));
+            let _ = writeln!(index, "- @{}", super::slash(&path));
+            let mut fields = Vec::new();
+            if let Some(description) = &rule.description {
+                fields.push(("description", super::quote(description)));
+            }
+            if !rule.globs.is_empty() {
+                fields.push(("globs", super::quote_list(&rule.globs)));
+            }
+            files.push(ExportedFile::new(
+                path,
+                super::frontmatter(&fields, &rule.body),
+            ));
+        }
+        files.insert(
+            0,
+            ExportedFile::new(AGENT_FILE, super::with_newline(index.trim_end())),
+        );
+        files
+    }
+
+    fn parse(&self, files: &[ExportedFile]) -> Vec<Rule> {
+        let mut rules = Vec::new();
+        for file in files {
+            if file.path == Path::new(AGENT_FILE) {
+                let global: Vec<&str> = file
+                    .contents
+                    .lines()
+                    .filter(|l| !l.starts_with(&format!("- @{RULES_DIR}/")))
+                    .collect();
+                let global = global.join("\n");
+                if !global.trim().is_empty() {
+                    rules.push(Rule {
+                        name: GLOBAL_RULE.to_string(),
+                        body: super::with_newline(global.trim_end()),
+                        always: true,
+                        ..Rule::default()
+                    });
+                }
+            } else if file.path.parent() == Some(Path::new(RULES_DIR)) {
+                let Some(name) = super::stem(&file.path, ".md") else {
+                    continue;
+                };
+                let (fields, body) = Fields::split(&file.contents);
+                rules.push(Rule {
+                    name: name.to_string(),
+                    description: fields.get("description"),
+                    globs: fields.globs("globs"),
+                    body: super::with_newline(body),
+                    always: false,
+                });
+            }
+        }
+        rules
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::export::test_policy;
+
+    #[test]
+    fn agent_md_mentions_every_skill_file() {
+        let rules = [
+            Rule {
+                name: GLOBAL_RULE.into(),
+                body: "Use pnpm.\n".into(),
+                always: true,
+                ..Rule::default()
+            },
+            Rule {
+                name: "zod".into(),
+                globs: vec!["*.ts".into()],
+                body: "Body\n".into(),
+                ..Rule::default()
+            },
+        ];
+        let amp = Amp::new(test_policy());
+        let files = amp.render(&rules);
+        assert_eq!(files[0].path, Path::new(AGENT_FILE));
+        assert_eq!(files[0].contents, "Use pnpm.\n\n- @.amp/rules/zod.md\n");
+        assert_eq!(files[1].contents, "---\nglobs: [\"*.ts\"]\n---\n\nBody\n");
+        assert_eq!(amp.parse(&files), rules);
+    }
+}
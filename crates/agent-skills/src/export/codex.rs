@@ -0,0 +1,47 @@
+//! OpenAI Codex: everything in the `AGENTS.md` at the repository root, laid
+//! out with one delimited section per skill under a `## Skills` heading.
+
+use std::path::Path;
+
+use super::{combined, ExportedFile, Exporter, ReferencePolicy, Rule};
+
+pub const AGENTS_FILE: &str = "AGENTS.md";
+
+/// Exports a project as a single `AGENTS.md`.
+#[derive(Clone)]
+pub struct Codex {
+    references: ReferencePolicy,
+}
+
+impl Codex {
+    pub fn new(references: ReferencePolicy) -> Self {
+        Self { references }
+    }
+}
+
+impl Exporter for Codex {
+    fn target(&self) -> &'static str {
+        "codex"
+    }
+
+    fn references(&self) -> &ReferencePolicy {
+        &self.references
+    }
+
+    /// A markdown link from the repository root.
+    fn link(&self, target: &Path) -> String {
+        format!("[{0}]({0})", super::slash(target))
+    }
+
+    fn render(&self, rules: &[Rule]) -> Vec<ExportedFile> {
+        vec![ExportedFile::new(AGENTS_FILE, combined::render(rules))]
+    }
+
+    fn parse(&self, files: &[ExportedFile]) -> Vec<Rule> {
+        files
+            .iter()
+            .filter(|f| f.path == Path::new(AGENTS_FILE))
+            .flat_map(|f| combined::parse(&f.contents))
+            .collect()
+    }
+}
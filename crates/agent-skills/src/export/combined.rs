@@ -0,0 +1,159 @@
+//! A single markdown file holding every rule, for tools that read one
+//! instructions file per project (Codex `AGENTS.md`, Goose `.goosehints`).
+//!
+//! ```text
+//! <global instructions>
+//!
+//! ## Skills
+//!
+//! <!-- skill: api-testing -->
+//! > **api-testing**: Workflow para testing de APIs con Postman y Jest
+//! > Applies to: `*.test.ts`
+//!
+//! <SKILL.md body>
+//! <!-- /skill: api-testing -->
+//! ```
+//!
+//! The comments delimit each skill so the file can be parsed back; the
+//! quoted header is what the agent reads.
+
+use std::fmt::Write as _;
+
+use super::{Rule, GLOBAL_RULE};
+
+const HEADING: &str = "## Skills";
+const APPLIES_TO: &str = "Applies to: ";
+
+pub(crate) fn render(rules: &[Rule]) -> String {
+    let mut out = String::new();
+    for rule in rules.iter().filter(|r| r.always) {
+        out.push_str(&rule.body);
+        out.push('\n');
+    }
+    let skills: Vec<&Rule> = rules.iter().filter(|r| !r.always).collect();
+    if !skills.is_empty() {
+        let _ = writeln!(out, "{HEADING}");
+    }
+    for rule in skills {
+        let _ = writeln!(out, "\n<!-- skill: {} -->", rule.name);
+        let _ = match &rule.description {
+            Some(description) => writeln!(out, "> **{}**: {description}", rule.name),
+            None => writeln!(out, "> **{}**", rule.name),
+        };
+        if !rule.globs.is_empty() {
+            let globs: Vec<String> = rule.globs.iter().map(|g| format!("`{g}`")).collect();
+            let _ = writeln!(out, "> {APPLIES_TO}{}", globs.join(", "));
+        }
+        let _ = write!(out, "\n{}", rule.body);
+        let _ = writeln!(out, "<!-- /skill: {} -->", rule.name);
+    }
+    super::with_newline(out.trim_end())
+}
+
+pub(crate) fn parse(text: &str) -> Vec<Rule> {
+    let mut rules = Vec::new();
+    let mut lines = text.lines().peekable();
+
+    let mut global = Vec::new();
+    while let Some(line) = lines.next_if(|l| opening(l).is_none()) {
+        global.push(line);
+    }
+    while global.last().is_some_and(|l| l.trim().is_empty()) {
+        global.pop();
+    }
+    if global.last().map(|l| l.trim()) == Some(HEADING) {
+        global.pop();
+    }
+    let global = global.join("\n");
+    if !global.trim().is_empty() {
+        rules.push(Rule {
+            name: GLOBAL_RULE.to_string(),
+            body: super::with_newline(global.trim_end()),
+            always: true,
+            ..Rule::default()
+        });
+    }
+
+    while let Some(line) = lines.next() {
+        let Some(name) = opening(line) else {
+            continue;
+        };
+        let closing = format!("<!-- /skill: {name} -->");
+        let mut rule = Rule {
+            name: name.to_string(),
+            ..Rule::default()
+        };
+        while let Some(quoted) = lines.next_if(|l| l.starts_with('>')) {
+            let quoted = quoted.trim_start_matches('>').trim();
+            if let Some(globs) = quoted.strip_prefix(APPLIES_TO) {
+                rule.globs = globs
+                    .split('`')
+                    .skip(1)
+                    .step_by(2)
+                    .map(String::from)
+                    .collect();
+            } else if let Some((_, description)) = quoted.split_once("**: ") {
+                rule.description = Some(description.to_string());
+            }
+        }
+        lines.next_if(|l| l.trim().is_empty());
+        let body: Vec<&str> = lines.by_ref().take_while(|l| *l != closing).collect();
+        rule.body = super::with_newline(&body.join("\n"));
+        rules.push(rule);
+    }
+    rules
+}
+
+fn opening(line: &str) -> Option<&str> {
+    line.strip_prefix("<!-- skill: ")?.strip_suffix(" -->")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rules() -> Vec<Rule> {
+        vec![
+            Rule {
+                name: GLOBAL_RULE.into(),
+                body: "Use pnpm.\n".into(),
+                always: true,
+                ..Rule::default()
+            },
+            Rule {
+                name: "api-testing".into(),
+                description: Some("Test APIs".into()),
+                globs: vec!["*.test.ts".into(), "!fixtures/".into()],
+                body: "# API\n\nRun it.\n".into(),
+                always: false,
+            },
+        ]
+    }
+
+    #[test]
+    fn skills_are_delimited_under_one_heading() {
+        assert_eq!(
+            render(&rules()),
+            "Use pnpm.\n\n## Skills\n\n<!-- skill: api-testing -->\n> **api-testing**: Test APIs\n> Applies to: `*.test.ts`, `!fixtures/`\n\n# API\n\nRun it.\n<!-- /skill: api-testing -->\n"
+        );
+        assert_eq!(parse(&render(&rules())), rules());
+    }
+
+    #[test]
+    fn a_file_without_skills_is_all_global() {
+        let rules = parse("# Project\n\nUse pnpm.\n");
+        assert_eq!(rules.len(), 1);
+        assert!(rules[0].always);
+        assert_eq!(rules[0].body, "# Project\n\nUse pnpm.\n");
+        assert!(parse("").is_empty());
+    }
+
+    #[test]
+    fn an_unclosed_skill_runs_to_the_end() {
+        let rules = parse("<!-- skill: a -->\n> **a**\n\nBody\n");
+        assert_eq!(rules.len(), 1);
+        assert_eq!(rules[0].name, "a");
+        assert_eq!(rules[0].description, None);
+        assert_eq!(rules[0].body, "Body\n");
+    }
+}
@@ -0,0 +1,209 @@
+//! GitHub Copilot custom instructions.
+//!
+//! The global `.claude/SKILL.md` becomes `.github/copilot-instructions.md`,
+//! which Copilot applies to every request, and each skill becomes
+//! `.github/instructions/<name>.instructions.md`:
+//!
+//! ```text
+//! ---
+//! description: "Workflow para testing de APIs con Postman y Jest"
+//! applyTo: "**/*.test.ts"
+//! ---
+//! ```
+//!
+//! `applyTo` globs are matched from the repository root without gitignore
+//! semantics, so `globs` are rewritten with [`root_globs`]. Copilot has no
+//! negation; `!` patterns are dropped, which makes this the one lossy
+//! export.
+
+use std::path::{Path, PathBuf};
+
+use super::{ExportedFile, Exporter, Fields, ReferencePolicy, Rule, GLOBAL_RULE};
+use crate::activation::root_globs;
+
+/// Repository-wide instructions file.
+pub const INSTRUCTIONS_FILE: &str = ".github/copilot-instructions.md";
+/// Directory of path-specific instruction files.
+pub const INSTRUCTIONS_DIR: &str = ".github/instructions";
+const SUFFIX: &str = ".instructions.md";
+
+/// Exports a project as Copilot instruction files.
+#[derive(Clone)]
+pub struct Copilot {
+    references: ReferencePolicy,
+}
+
+impl Copilot {
+    pub fn new(references: ReferencePolicy) -> Self {
+        Self { references }
+    }
+}
+
+impl Exporter for Copilot {
+    fn target(&self) -> &'static str {
+        "copilot"
+    }
+
+    fn references(&self) -> &ReferencePolicy {
+        &self.references
+    }
+
+    /// A markdown link, resolved from `.github/instructions/`.
+    fn link(&self, target: &Path) -> String {
+        format!("[{0}](../../{0})", super::slash(target))
+    }
+
+    fn render(&self, rules: &[Rule]) -> Vec<ExportedFile> {
+        rules
+            .iter()
+            .map(|rule| {
+                if rule.always {
+                    return ExportedFile::new(INSTRUCTIONS_FILE, rule.body.clone());
+                }
+                let apply_to: Vec<String> = root_globs(&rule.globs)
+                    .into_iter()
+                    .filter(|(negated, _)| !negated)
+                    .map(|(_, glob)| glob)
+                    .collect();
+                let mut fields = Vec::new();
+                if let Some(description) = &rule.description {
+                    fields.push(("description", super::quote(description)));
+                }
+                if !apply_to.is_empty() {
+                    fields.push(("applyTo", super::quote(&apply_to.join(","))));
+                }
+                ExportedFile::new(
+                    PathBuf::from(INSTRUCTIONS_DIR).join(format!("{}{SUFFIX}", rule.name)),
+                    super::frontmatter(&fields, &rule.body),
+                )
+            })
+            .collect()
+    }
+
+    fn parse(&self, files: &[ExportedFile]) -> Vec<Rule> {
+        files
+            .iter()
+            .filter_map(|f| {
+                if f.path == Path::new(INSTRUCTIONS_FILE) {
+                    return Some(Rule {
+                        name: GLOBAL_RULE.to_string(),
+                        body: super::with_newline(&f.contents),
+                        always: true,
+                        ..Rule::default()
+                    });
+                }
+                if f.path.parent() != Some(Path::new(INSTRUCTIONS_DIR)) {
+                    return None;
+                }
+                Some(parse_instructions(
+                    super::stem(&f.path, SUFFIX)?,
+                    &f.contents,
+                ))
+            })
+            .collect()
+    }
+}
+
+/// One `.instructions.md` file, whether exported or hand-written.
+pub(crate) fn parse_instructions(name: &str, text: &str) -> Rule {
+    let (fields, body) = Fields::split(text);
+    Rule {
+        name: name.to_string(),
+        description: fields.get("description"),
+        globs: fields.globs("applyTo"),
+        body: super::with_newline(body),
+        always: false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use super::*;
+    use crate::export::{self, test_policy};
+    use crate::test_support::TempDir;
+    use crate::{Error, Project};
+
+    #[test]
+    fn the_global_rule_becomes_the_repository_instructions() {
+        let global = Rule {
+            name: GLOBAL_RULE.into(),
+            body: "Use pnpm.\n".into(),
+            always: true,
+            ..Rule::default()
+        };
+        let files = Copilot::new(test_policy()).render(&[global]);
+        assert_eq!(files, [ExportedFile::new(INSTRUCTIONS_FILE, "Use pnpm.\n")]);
+    }
+
+    #[test]
+    fn apply_to_is_rooted_and_drops_negations() {
+        let rule = Rule {
+            name: "api-testing".into(),
+            description: Some("Test \"APIs\"".into()),
+            globs: vec!["*.test.ts".into(), "src/".into(), "!fixtures/".into()],
+            body: "# API\n".into(),
+            always: false,
+        };
+        let files = Copilot::new(test_policy()).render(&[rule]);
+        assert_eq!(
+            files[0].path,
+            Path::new(".github/instructions/api-testing.instructions.md")
+        );
+        assert_eq!(
+            files[0].contents,
+            "---\ndescription: \"Test \\\"APIs\\\"\"\napplyTo: \"**/*.test.ts,**/src/**\"\n---\n\n# API\n"
+        );
+        let parsed = parse_instructions("api-testing", &files[0].contents);
+        assert_eq!(parsed.description.as_deref(), Some("Test \"APIs\""));
+        assert_eq!(parsed.globs, ["**/*.test.ts", "**/src/**"]);
+    }
+
+    #[test]
+    fn rules_without_globs_have_no_apply_to() {
+        let rule = Rule {
+            name: "prose".into(),
+            body: "Body\n".into(),
+            ..Rule::default()
+        };
+        let files = Copilot::new(test_policy()).render(&[rule]);
+        assert_eq!(files[0].contents, "---\n---\n\nBody\n");
+    }
+
+    #[test]
+    fn links_are_relative_to_the_instructions_directory() {
+        let link = Copilot::new(test_policy()).link(Path::new(".claude/skills/a/references/b.md"));
+        assert_eq!(
+            link,
+            "[.claude/skills/a/references/b.md](../../.claude/skills/a/references/b.md)"
+        );
+    }
+
+    #[test]
+    fn instruction_files_stay_in_the_instructions_directory() {
+        let dir = TempDir::new();
+        dir.write("README.md", "keep\n");
+        dir.write(
+            ".claude/skills/evil/SKILL.md",
+            "---\nname: ../../README\ndescription: x\n---\nBody\n",
+        );
+        let project = Project::new(dir.path());
+        let copilot = Copilot::new(test_policy());
+        assert!(matches!(
+            copilot.export(&project),
+            Err(Error::InvalidName(_))
+        ));
+        let rule = Rule {
+            name: "../../README".into(),
+            body: "Body\n".into(),
+            ..Rule::default()
+        };
+        let files = copilot.render(&[rule]);
+        assert!(export::write(&project, &files).is_err());
+        assert_eq!(
+            fs::read_to_string(dir.path().join("README.md")).unwrap(),
+            "keep\n"
+        );
+    }
+}
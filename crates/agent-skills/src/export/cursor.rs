@@ -0,0 +1,165 @@
+//! Cursor project rules: one `.cursor/rules/<name>.mdc` per skill.
+//!
+//! ```text
+//! ---
+//! description: Workflow para testing de APIs con Postman y Jest
+//! globs: *.test.ts,*.spec.ts
+//! alwaysApply: false
+//! ---
+//! ```
+//!
+//! Cursor reads `globs` as an unquoted comma-separated list rather than
+//! YAML, so the frontmatter is written by hand. The global `.claude/SKILL.md`
+//! becomes an `alwaysApply` rule.
+
+use std::path::{Path, PathBuf};
+
+use super::{ExportedFile, Exporter, Fields, ReferencePolicy, Rule};
+
+/// Where Cursor looks for project rules.
+pub const RULES_DIR: &str = ".cursor/rules";
+
+/// Exports a project as Cursor `.mdc` rules.
+#[derive(Clone)]
+pub struct Cursor {
+    references: ReferencePolicy,
+}
+
+impl Cursor {
+    pub fn new(references: ReferencePolicy) -> Self {
+        Self { references }
+    }
+}
+
+impl Exporter for Cursor {
+    fn target(&self) -> &'static str {
+        "cursor"
+    }
+
+    fn references(&self) -> &ReferencePolicy {
+        &self.references
+    }
+
+    /// Cursor's `@file` syntax, resolved from the workspace root.
+    fn link(&self, target: &Path) -> String {
+        format!("@{}", super::slash(target))
+    }
+
+    fn render(&self, rules: &[Rule]) -> Vec<ExportedFile> {
+        rules
+            .iter()
+            .map(|rule| {
+                let fields = [
+                    ("description", rule.description.clone().unwrap_or_default()),
+                    ("globs", rule.globs.join(",")),
+                    ("alwaysApply", rule.always.to_string()),
+                ];
+                ExportedFile::new(
+                    PathBuf::from(RULES_DIR).join(format!("{}.mdc", rule.name)),
+                    super::frontmatter(&fields, &rule.body),
+                )
+            })
+            .collect()
+    }
+
+    fn parse(&self, files: &[ExportedFile]) -> Vec<Rule> {
+        files
+            .iter()
+            .filter(|f| f.path.parent() == Some(Path::new(RULES_DIR)))
+            .filter_map(|f| {
+                let name = super::stem(&f.path, ".mdc")?;
+                Some(parse_rule(name, &f.contents))
+            })
+            .collect()
+    }
+}
+
+/// One `.mdc` rule, whether exported or written in Cursor.
+pub(crate) fn parse_rule(name: &str, text: &str) -> Rule {
+    let (fields, body) = Fields::split(text);
+    Rule {
+        name: name.to_string(),
+        description: fields.get("description"),
+        globs: fields.globs("globs"),
+        body: super::with_newline(body),
+        always: fields.flag("alwaysApply"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use super::*;
+    use crate::export::{self, test_policy};
+    use crate::test_support::TempDir;
+    use crate::{Error, Project};
+
+    fn rule() -> Rule {
+        Rule {
+            name: "api-testing".into(),
+            description: Some("Test APIs with Jest".into()),
+            globs: vec!["*.test.ts".into(), "!fixtures/".into()],
+            body: "# API Testing\n".into(),
+            always: false,
+        }
+    }
+
+    #[test]
+    fn rules_render_as_mdc_with_unquoted_globs() {
+        let files = Cursor::new(test_policy()).render(&[rule()]);
+        assert_eq!(files[0].path, Path::new(".cursor/rules/api-testing.mdc"));
+        assert_eq!(
+            files[0].contents,
+            "---\ndescription: Test APIs with Jest\nglobs: *.test.ts,!fixtures/\nalwaysApply: false\n---\n\n# API Testing\n"
+        );
+    }
+
+    #[test]
+    fn rules_written_in_cursor_parse() {
+        let text = "---\ndescription: \"Quoted: with a colon\"\nglobs: src/**/*.ts, *.{js,jsx}\nalwaysApply: true\n---\nBody";
+        let rule = parse_rule("mine", text);
+        assert_eq!(rule.description.as_deref(), Some("Quoted: with a colon"));
+        assert_eq!(rule.globs, ["src/**/*.ts", "*.{js,jsx}"]);
+        assert!(rule.always);
+        assert_eq!(rule.body, "Body\n");
+    }
+
+    #[test]
+    fn files_outside_the_rules_directory_are_ignored() {
+        let files = [
+            ExportedFile::new(".cursor/rules/a.mdc", "---\n---\nA"),
+            ExportedFile::new(".cursor/rules/nested/b.mdc", "B"),
+            ExportedFile::new(".cursor/rules/c.md", "C"),
+        ];
+        let rules = Cursor::new(test_policy()).parse(&files);
+        assert_eq!(rules.len(), 1);
+        assert_eq!(rules[0].name, "a");
+        assert_eq!(rules[0].description, None);
+    }
+
+    #[test]
+    fn a_name_that_climbs_out_is_not_exported_or_written() {
+        let dir = TempDir::new();
+        dir.write("README.md", "keep\n");
+        dir.write(
+            ".claude/skills/evil/SKILL.md",
+            "---\nname: ../../README\ndescription: x\n---\nBody\n",
+        );
+        let project = Project::new(dir.path());
+        let cursor = Cursor::new(test_policy());
+        assert!(matches!(
+            cursor.export(&project),
+            Err(Error::InvalidName(_))
+        ));
+        let rule = Rule {
+            name: "../../README".into(),
+            ..rule()
+        };
+        assert!(export::write(&project, &cursor.render(&[rule])).is_err());
+        assert_eq!(
+            fs::read_to_string(dir.path().join("README.md")).unwrap(),
+            "keep\n"
+        );
+    }
+}
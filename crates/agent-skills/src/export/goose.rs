@@ -0,0 +1,48 @@
+//! Goose: everything in the `.goosehints` file at the project root, laid
+//! out with one delimited section per skill under a `## Skills` heading.
+
+use std::path::Path;
+
+use super::{combined, ExportedFile, Exporter, ReferencePolicy, Rule};
+
+pub const HINTS_FILE: &str = ".goosehints";
+
+/// Exports a project as a single `.goosehints`.
+#[derive(Clone)]
+pub struct Goose {
+    references: ReferencePolicy,
+}
+
+impl Goose {
+    pub fn new(references: ReferencePolicy) -> Self {
+        Self { references }
+    }
+}
+
+impl Exporter for Goose {
+    fn target(&self) -> &'static str {
+        "goose"
+    }
+
+    fn references(&self) -> &ReferencePolicy {
+        &self.references
+    }
+
+    /// A plain project-relative path, which Goose can read with its
+    /// developer tools.
+    fn link(&self, target: &Path) -> String {
+        format!("`{}`", super::slash(target))
+    }
+
+    fn render(&self, rules: &[Rule]) -> Vec<ExportedFile> {
+        vec![ExportedFile::new(HINTS_FILE, combined::render(rules))]
+    }
+
+    fn parse(&self, files: &[ExportedFile]) -> Vec<Rule> {
+        files
+            .iter()
+            .filter(|f| f.path == Path::new(HINTS_FILE))
+            .flat_map(|f| combined::parse(&f.contents))
+            .collect()
+    }
+}
@@ -0,0 +1,497 @@
+//! Converting skills into the rule formats of other agent tools.
+//!
+//! Every backend implements [`Exporter`]: the project is first reduced to
+//! [`Rule`]s (the global `.claude/SKILL.md` plus one per skill) and each
+//! backend renders those in its tool's layout. Backends can also parse
+//! their own output back into rules, which keeps conversions honest about
+//! what they lose.
+//!
+//! Exporters only build file contents; [`write()`] puts them on disk, so
+//! callers can preview or diff an export first.
+
+pub mod amp;
+pub mod codex;
+pub(crate) mod combined;
+pub mod copilot;
+pub mod cursor;
+pub mod goose;
+pub mod opencode;
+pub mod windsurf;
+
+use std::fmt::Write as _;
+use std::fs;
+use std::io;
+use std::path::{Component, Path, PathBuf};
+
+use crate::activation::split_list;
+use crate::project::{Project, SkillDir, REFERENCES_DIR};
+use crate::skill::{is_kebab_case, Skill};
+use crate::tokens::TokenCounter;
+use crate::{Error, Result};
+
+pub use amp::Amp;
+pub use codex::Codex;
+pub use copilot::Copilot;
+pub use cursor::Cursor;
+pub use goose::Goose;
+pub use opencode::OpenCode;
+pub use windsurf::Windsurf;
+
+/// Default for [`crate::config::ExportConfig::inline_threshold`].
+pub const DEFAULT_INLINE_THRESHOLD: usize = 2000;
+
+/// Rule name for the global `.claude/SKILL.md` when it has no `name`.
+pub const GLOBAL_RULE: &str = "project";
+
+/// Names accepted by [`exporter`].
+pub const TARGETS: &[&str] = &[
+    "amp", "codex", "copilot", "cursor", "goose", "opencode", "windsurf",
+];
+
+/// A tool-specific rendering of a project's skills.
+pub trait Exporter {
+    /// Name accepted by `skills export --target`.
+    fn target(&self) -> &'static str;
+
+    /// How `references/` are carried into the export.
+    fn references(&self) -> &ReferencePolicy;
+
+    /// How a linked reference is written, given its project-relative path.
+    fn link(&self, target: &Path) -> String;
+
+    /// Lay `rules` out as the tool expects them.
+    fn render(&self, rules: &[Rule]) -> Vec<ExportedFile>;
+
+    /// Recover the rules from files this exporter rendered. Files it does
+    /// not recognize are ignored.
+    fn parse(&self, files: &[ExportedFile]) -> Vec<Rule>;
+
+    /// Render every rule of `project`.
+    fn export(&self, project: &Project) -> Result<Vec<ExportedFile>> {
+        let rules = rules(project, self.references(), |target| self.link(target))?;
+        Ok(self.render(&rules))
+    }
+}
+
+/// Look an exporter up by name; see [`TARGETS`].
+pub fn exporter(target: &str, references: ReferencePolicy) -> Result<Box<dyn Exporter>> {
+    Ok(match target {
+        "amp" => Box::new(Amp::new(references)),
+        "codex" => Box::new(Codex::new(references)),
+        "copilot" => Box::new(Copilot::new(references)),
+        "cursor" => Box::new(Cursor::new(references)),
+        "goose" => Box::new(Goose::new(references)),
+        "opencode" => Box::new(OpenCode::new(references)),
+        "windsurf" => Box::new(Windsurf::new(references)),
+        other => return Err(Error::UnknownTarget(other.to_string())),
+    })
+}
+
+/// What every target format carries of a skill.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Rule {
+    pub name: String,
+    pub description: Option<String>,
+    pub globs: Vec<String>,
+    /// Markdown after the frontmatter, with inlined and linked references
+    /// appended.
+    pub body: String,
+    /// Applied to every request; true only for the global file.
+    pub always: bool,
+}
+
+impl Rule {
+    /// The rule for a parsed skill, without its references.
+    pub fn from_skill(name: &str, skill: &Skill) -> Self {
+        Self {
+            name: skill.name().unwrap_or(name).to_string(),
+            description: skill.description().map(one_line),
+            globs: skill
+                .frontmatter()
+                .globs
+                .iter()
+                .flat_map(|g| split_list(g))
+                .collect(),
+            body: with_newline(skill.body().trim_start_matches('\n')),
+            always: false,
+        }
+    }
+}
+
+/// One generated file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ExportedFile {
+    /// Relative to the project root.
+    pub path: PathBuf,
+    pub contents: String,
+}
+
+impl ExportedFile {
+    pub fn new(path: impl Into<PathBuf>, contents: impl Into<String>) -> Self {
+        Self {
+            path: path.into(),
+            contents: contents.into(),
+        }
+    }
+}
+
+/// Write `files` under the project root, creating directories as needed,
+/// and return the paths written. Nothing is written when a path is absolute
+/// or steps out of the root.
+pub fn write(project: &Project, files: &[ExportedFile]) -> Result<Vec<PathBuf>> {
+    for file in files {
+        let inside = file
+            .path
+            .components()
+            .all(|c| matches!(c, Component::Normal(_)));
+        if !inside || file.path.as_os_str().is_empty() {
+            let outside = io::Error::new(io::ErrorKind::InvalidInput, "is outside the project");
+            return Err(Error::io(&file.path, outside));
+        }
+    }
+    let mut written = Vec::new();
+    for file in files {
+        let path = project.join(&file.path);
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).map_err(|e| Error::io(parent, e))?;
+        }
+        fs::write(&path, &file.contents).map_err(|e| Error::io(&path, e))?;
+        written.push(path);
+    }
+    Ok(written)
+}
+
+/// Inlines references of up to a token threshold and links larger ones.
+#[derive(Clone)]
+pub struct ReferencePolicy {
+    counter: TokenCounter,
+    inline_threshold: usize,
+}
+
+impl ReferencePolicy {
+    pub fn new(counter: TokenCounter) -> Self {
+        Self {
+            counter,
+            inline_threshold: DEFAULT_INLINE_THRESHOLD,
+        }
+    }
+
+    /// Inline references of up to this many tokens. Zero links every
+    /// reference.
+    pub fn inline_threshold(mut self, tokens: usize) -> Self {
+        self.inline_threshold = tokens;
+        self
+    }
+
+    /// Append a skill's references to `body`: small ones as sections, the
+    /// rest under `## References`, each written with `link`.
+    fn append(
+        &self,
+        project: &Project,
+        dir: &SkillDir,
+        body: &mut String,
+        link: impl Fn(&Path) -> String,
+    ) -> Result<()> {
+        let mut links = Vec::new();
+        for file in dir.files_in(REFERENCES_DIR)? {
+            let path = slash(file.strip_prefix(&dir.path).unwrap_or(&file));
+            let text = fs::read_to_string(&file).map_err(|e| Error::io(&file, e))?;
+            let inline =
+                self.inline_threshold > 0 && self.counter.count(&text) <= self.inline_threshold;
+            match inline {
+                true => {
+                    let _ = write!(body, "\n## {path}\n\n{}\n", text.trim());
+                }
+                false => {
+                    let target = file.strip_prefix(project.root()).unwrap_or(&file);
+                    links.push((path, link(target)));
+                }
+            }
+        }
+        if !links.is_empty() {
+            body.push_str("\n## References\n\n");
+            for (path, link) in links {
+                let _ = writeln!(body, "- `{path}`: {link}");
+            }
+        }
+        Ok(())
+    }
+}
+
+/// The global rule followed by one rule per skill, references appended.
+/// Directories without a `SKILL.md` are skipped, but unlike token
+/// accounting an export stops at a skill that does not parse rather than
+/// silently dropping it. Rule names become file names, so a name that is
+/// not kebab-case is an error.
+pub(crate) fn rules(
+    project: &Project,
+    references: &ReferencePolicy,
+    link: impl Fn(&Path) -> String,
+) -> Result<Vec<Rule>> {
+    let mut rules = Vec::new();
+    let global = project.global_skill_file();
+    if global.is_file() {
+        let skill = Skill::from_path(&global)?;
+        rules.push(Rule {
+            always: true,
+            ..named(Rule::from_skill(GLOBAL_RULE, &skill))?
+        });
+    }
+    for dir in project.skills()? {
+        if !dir.skill_file().is_file() {
+            continue;
+        }
+        let skill = Skill::from_path(dir.skill_file())?;
+        let mut rule = named(Rule::from_skill(&dir.name, &skill))?;
+        references.append(project, &dir, &mut rule.body, &link)?;
+        rules.push(rule);
+    }
+    Ok(rules)
+}
+
+fn named(rule: Rule) -> Result<Rule> {
+    match is_kebab_case(&rule.name) {
+        true => Ok(rule),
+        false => Err(Error::InvalidName(rule.name)),
+    }
+}
+
+/// A single-line frontmatter value: surrounding space trimmed and inner
+/// newlines folded, as rule formats without full YAML expect.
+pub(crate) fn one_line(text: &str) -> String {
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// `path` with `/` separators, as rule files write them.
+pub(crate) fn slash(path: &Path) -> String {
+    crate::lock::slash_path(path)
+}
+
+pub(crate) fn with_newline(text: &str) -> String {
+    match text.ends_with('\n') || text.is_empty() {
+        true => text.to_string(),
+        false => format!("{text}\n"),
+    }
+}
+
+/// A double-quoted YAML scalar; JSON string syntax is valid YAML.
+pub(crate) fn quote(value: &str) -> String {
+    serde_json::Value::from(value).to_string()
+}
+
+/// A YAML flow sequence of double-quoted strings.
+pub(crate) fn quote_list(values: &[String]) -> String {
+    serde_json::Value::from(values.to_vec()).to_string()
+}
+
+/// `---` frontmatter of `key: value` lines followed by the body.
+pub(crate) fn frontmatter(fields: &[(&str, String)], body: &str) -> String {
+    let mut out = String::from("---\n");
+    for (key, value) in fields {
+        match value.is_empty() {
+            true => {
+                let _ = writeln!(out, "{key}:");
+            }
+            false => {
+                let _ = writeln!(out, "{key}: {value}");
+            }
+        }
+    }
+    out.push_str("---\n\n");
+    out.push_str(body);
+    with_newline(&out)
+}
+
+/// Frontmatter written by [`frontmatter`] or by the tools themselves, read
+/// line by line: Cursor's unquoted `globs: *.ts` is not valid YAML.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct Fields<'a> {
+    fields: Vec<(&'a str, &'a str)>,
+}
+
+impl<'a> Fields<'a> {
+    /// Split `text` into fields and body. Text without frontmatter is all
+    /// body.
+    pub(crate) fn split(text: &'a str) -> (Self, &'a str) {
+        let Some(rest) = text
+            .strip_prefix("---\n")
+            .or_else(|| text.strip_prefix("---\r\n"))
+        else {
+            return (Self::default(), text);
+        };
+        let (head, body) = match rest.strip_prefix("---") {
+            Some(body) => ("", body),
+            None => match rest.find("\n---") {
+                Some(end) => (&rest[..end], &rest[end + 4..]),
+                None => return (Self::default(), text),
+            },
+        };
+        let fields = head
+            .lines()
+            .filter_map(|line| line.split_once(':'))
+            .map(|(key, value)| (key.trim(), value.trim()))
+            .collect();
+        (Self { fields }, body.trim_start_matches(['\r', '\n']))
+    }
+
+    /// A scalar, unquoted when quoted. Empty values are absent.
+    pub(crate) fn get(&self, key: &str) -> Option<String> {
+        let (_, raw) = self.fields.iter().find(|(k, _)| *k == key)?;
+        let value = match raw.starts_with(['"', '\'']) {
+            true => serde_yaml::from_str(raw).unwrap_or_else(|_| raw.to_string()),
+            false => raw.to_string(),
+        };
+        (!value.is_empty()).then_some(value)
+    }
+
+    /// A glob list written either as a flow sequence or comma-separated.
+    pub(crate) fn globs(&self, key: &str) -> Vec<String> {
+        let Some((_, raw)) = self.fields.iter().find(|(k, _)| *k == key) else {
+            return Vec::new();
+        };
+        if raw.starts_with('[') {
+            if let Ok(list) = serde_yaml::from_str::<Vec<String>>(raw) {
+                return list.iter().flat_map(|g| split_list(g)).collect();
+            }
+        }
+        self.get(key).map(|v| split_list(&v)).unwrap_or_default()
+    }
+
+    pub(crate) fn flag(&self, key: &str) -> bool {
+        self.get(key).as_deref() == Some("true")
+    }
+}
+
+/// File name of `path` without `suffix`, when it has it.
+pub(crate) fn stem<'a>(path: &'a Path, suffix: &str) -> Option<&'a str> {
+    path.file_name()?.to_str()?.strip_suffix(suffix)
+}
+
+/// A policy counting characters, for tests that need one.
+#[cfg(test)]
+pub(crate) fn test_policy() -> ReferencePolicy {
+    ReferencePolicy::new(TokenCounter::new(std::sync::Arc::new(
+        crate::tokens::CharEstimate,
+    )))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::TempDir;
+
+    #[test]
+    fn fields_split_frontmatter_from_the_body() {
+        let (fields, body) = Fields::split("---\r\nname: a\nempty:\n---\r\n\nBody\n");
+        assert_eq!(fields.get("name").as_deref(), Some("a"));
+        assert_eq!(fields.get("empty"), None);
+        assert_eq!(body, "Body\n");
+        let (fields, body) = Fields::split("---\n---\nBody");
+        assert_eq!(fields.get("name"), None);
+        assert_eq!(body, "Body");
+    }
+
+    #[test]
+    fn text_without_closed_frontmatter_is_all_body() {
+        for text in ["Body", "---\nname: a\nno end"] {
+            let (fields, body) = Fields::split(text);
+            assert_eq!(fields.get("name"), None);
+            assert_eq!(body, text);
+        }
+    }
+
+    #[test]
+    fn globs_read_as_flow_sequences_or_comma_lists() {
+        let (fields, _) =
+            Fields::split("---\na: [\"*.ts\", \"*.{js,jsx}\"]\nb: *.ts, *.md\nc: \"*.rs\"\n---\n");
+        assert_eq!(fields.globs("a"), ["*.ts", "*.{js,jsx}"]);
+        assert_eq!(fields.globs("b"), ["*.ts", "*.md"]);
+        assert_eq!(fields.globs("c"), ["*.rs"]);
+        assert!(fields.globs("missing").is_empty());
+    }
+
+    #[test]
+    fn values_are_quoted_as_yaml() {
+        assert_eq!(quote("a: \"b\""), "\"a: \\\"b\\\"\"");
+        assert_eq!(quote_list(&["*.ts".into()]), "[\"*.ts\"]");
+        assert_eq!(one_line("  two\n  lines "), "two lines");
+    }
+
+    #[test]
+    fn rules_from_skills_prefer_the_frontmatter_name() {
+        let skill = Skill::parse(
+            "---\nname: real\ndescription: |\n  two\n  lines\nglobs: \"*.ts,*.md\"\n---\n\nBody",
+        )
+        .unwrap();
+        let rule = Rule::from_skill("dir", &skill);
+        assert_eq!(rule.name, "real");
+        assert_eq!(rule.description.as_deref(), Some("two lines"));
+        assert_eq!(rule.globs, ["*.ts", "*.md"]);
+        assert_eq!(rule.body, "Body\n");
+    }
+
+    #[test]
+    fn exporters_are_looked_up_by_target() {
+        for target in TARGETS {
+            assert_eq!(exporter(target, test_policy()).unwrap().target(), *target);
+        }
+        assert!(matches!(
+            exporter("notepad", test_policy()),
+            Err(Error::UnknownTarget(_))
+        ));
+    }
+
+    #[test]
+    fn write_creates_directories_under_the_root() {
+        let dir = TempDir::new();
+        let project = Project::new(dir.path());
+        let written = write(&project, &[ExportedFile::new(".cursor/rules/a.mdc", "A\n")]).unwrap();
+        assert_eq!(written, [dir.path().join(".cursor/rules/a.mdc")]);
+        assert_eq!(fs::read_to_string(&written[0]).unwrap(), "A\n");
+    }
+
+    #[test]
+    fn write_refuses_paths_outside_the_root() {
+        let dir = TempDir::new();
+        let project = Project::new(dir.path().join("project"));
+        let outside = dir.path().join("README.md");
+        for path in [
+            PathBuf::from(".windsurf/rules/../../../README.md"),
+            PathBuf::from("./a.md"),
+            outside.clone(),
+            PathBuf::new(),
+        ] {
+            let files = [
+                ExportedFile::new("a.md", "A\n"),
+                ExportedFile::new(&path, "B\n"),
+            ];
+            assert!(
+                matches!(write(&project, &files), Err(Error::Io { .. })),
+                "{}",
+                path.display()
+            );
+        }
+        assert!(!outside.exists());
+        assert!(!dir.path().join("project/a.md").exists());
+    }
+
+    #[test]
+    fn rules_reject_names_that_are_not_skill_names() {
+        let dir = TempDir::new();
+        dir.write("README.md", "keep\n");
+        dir.write(
+            ".claude/skills/evil/SKILL.md",
+            "---\nname: ../../README\ndescription: x\n---\nBody\n",
+        );
+        let project = Project::new(dir.path());
+        assert!(matches!(
+            rules(&project, &test_policy(), slash),
+            Err(Error::InvalidName(name)) if name == "../../README"
+        ));
+        dir.write(".claude/SKILL.md", "---\nname: /etc/x\n---\nBody\n");
+        assert!(matches!(
+            rules(&project, &test_policy(), slash),
+            Err(Error::InvalidName(name)) if name == "/etc/x"
+        ));
+    }
+}
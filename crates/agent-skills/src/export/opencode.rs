@@ -0,0 +1,189 @@
+//! OpenCode: one `.opencode/rules/<name>.md` per rule, loaded through the
+//! `instructions` list of `opencode.json`.
+//!
+//! OpenCode loads every instructions file up front, so `description` and
+//! `globs` are kept in frontmatter for the agent to read rather than for
+//! OpenCode to act on. The global `.claude/SKILL.md` is written without
+//! frontmatter.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use serde_json::{json, Value};
+
+use super::{ExportedFile, Exporter, Fields, ReferencePolicy, Rule};
+use crate::project::Project;
+use crate::{Error, Result};
+
+pub const CONFIG_FILE: &str = "opencode.json";
+/// Directory of the generated instruction files.
+pub const RULES_DIR: &str = ".opencode/rules";
+
+/// Exports a project as OpenCode instruction files.
+#[derive(Clone)]
+pub struct OpenCode {
+    references: ReferencePolicy,
+}
+
+impl OpenCode {
+    pub fn new(references: ReferencePolicy) -> Self {
+        Self { references }
+    }
+}
+
+impl Exporter for OpenCode {
+    fn target(&self) -> &'static str {
+        "opencode"
+    }
+
+    fn references(&self) -> &ReferencePolicy {
+        &self.references
+    }
+
+    /// A markdown link, resolved from `.opencode/rules/`.
+    fn link(&self, target: &Path) -> String {
+        format!("[{0}](../../{0})", super::slash(target))
+    }
+
+    fn render(&self, rules: &[Rule]) -> Vec<ExportedFile> {
+        let mut files = vec![ExportedFile::new(CONFIG_FILE, config(Value::Null))];
+        for rule in rules {
+            let path = PathBuf::from(RULES_DIR).join(format!("{}.md", rule.name));
+            if rule.always {
+                files.push(ExportedFile::new(path, rule.body.clone()));
+                continue;
+            }
+            let mut fields = Vec::new();
+            if let Some(description) = &rule.description {
+                fields.push(("description", super::quote(description)));
+            }
+            if !rule.globs.is_empty() {
+                fields.push(("globs", super::quote_list(&rule.globs)));
+            }
+            files.push(ExportedFile::new(
+                path,
+                super::frontmatter(&fields, &rule.body),
+            ));
+        }
+        files
+    }
+
+    fn parse(&self, files: &[ExportedFile]) -> Vec<Rule> {
+        files
+            .iter()
+            .filter(|f| f.path.parent() == Some(Path::new(RULES_DIR)))
+            .filter_map(|f| {
+                let name = super::stem(&f.path, ".md")?.to_string();
+                if !f.contents.starts_with("---") {
+                    return Some(Rule {
+                        name,
+                        body: super::with_newline(&f.contents),
+                        always: true,
+                        ..Rule::default()
+                    });
+                }
+                let (fields, body) = Fields::split(&f.contents);
+                Some(Rule {
+                    name,
+                    description: fields.get("description"),
+                    globs: fields.globs("globs"),
+                    body: super::with_newline(body),
+                    always: false,
+                })
+            })
+            .collect()
+    }
+
+    /// Like the default, but an existing `opencode.json` keeps its other
+    /// settings and only gains the rules directory in `instructions`.
+    fn export(&self, project: &Project) -> Result<Vec<ExportedFile>> {
+        let rules = super::rules(project, &self.references, |target| self.link(target))?;
+        let mut files = self.render(&rules);
+        let path = project.join(CONFIG_FILE);
+        let existing = match fs::read_to_string(&path) {
+            Ok(text) => serde_json::from_str(&text).map_err(|e| Error::Config {
+                path: path.clone(),
+                message: e.to_string(),
+            })?,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Value::Null,
+            Err(e) => return Err(Error::io(&path, e)),
+        };
+        files[0].contents = config(existing);
+        Ok(files)
+    }
+}
+
+/// `config` with the rules glob added to `instructions`.
+fn config(mut config: Value) -> String {
+    let glob = format!("{RULES_DIR}/*.md");
+    if !config.is_object() {
+        config = json!({ "$schema": "https://opencode.ai/config.json" });
+    }
+    let instructions = config
+        .as_object_mut()
+        .expect("config is an object")
+        .entry("instructions")
+        .or_insert_with(|| json!([]));
+    match instructions.as_array_mut() {
+        Some(list) if list.iter().any(|v| v == &glob) => {}
+        Some(list) => list.push(Value::from(glob)),
+        None => *instructions = json!([glob]),
+    }
+    let mut text = serde_json::to_string_pretty(&config).unwrap_or_default();
+    text.push('\n');
+    text
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::export::test_policy;
+    use crate::test_support::TempDir;
+
+    #[test]
+    fn the_config_gains_the_rules_glob_once() {
+        let fresh: Value = serde_json::from_str(&config(Value::Null)).unwrap();
+        assert_eq!(fresh["instructions"], json!([".opencode/rules/*.md"]));
+        let again: Value = serde_json::from_str(&config(fresh.clone())).unwrap();
+        assert_eq!(again, fresh);
+        let replaced: Value =
+            serde_json::from_str(&config(json!({ "instructions": "x" }))).unwrap();
+        assert_eq!(replaced["instructions"], json!([".opencode/rules/*.md"]));
+    }
+
+    #[test]
+    fn export_keeps_existing_settings() {
+        let dir = TempDir::new();
+        dir.write(
+            CONFIG_FILE,
+            r#"{ "model": "m", "instructions": ["CONTRIBUTING.md"] }"#,
+        );
+        let project = Project::new(dir.path());
+        let files = OpenCode::new(test_policy()).export(&project).unwrap();
+        let config: Value = serde_json::from_str(&files[0].contents).unwrap();
+        assert_eq!(config["model"], "m");
+        assert_eq!(
+            config["instructions"],
+            json!(["CONTRIBUTING.md", ".opencode/rules/*.md"])
+        );
+        dir.write(CONFIG_FILE, "{ not json");
+        assert!(matches!(
+            OpenCode::new(test_policy()).export(&project),
+            Err(Error::Config { .. })
+        ));
+    }
+
+    #[test]
+    fn the_global_rule_has_no_frontmatter() {
+        let global = Rule {
+            name: "project".into(),
+            body: "Use pnpm.\n".into(),
+            always: true,
+            ..Rule::default()
+        };
+        let opencode = OpenCode::new(test_policy());
+        let files = opencode.render(std::slice::from_ref(&global));
+        assert_eq!(files[1].contents, "Use pnpm.\n");
+        assert_eq!(opencode.parse(&files), [global]);
+    }
+}
@@ -0,0 +1,113 @@
+//! Windsurf workspace rules: one `.windsurf/rules/<name>.md` per skill.
+//!
+//! ```text
+//! ---
+//! trigger: glob
+//! description: Workflow para testing de APIs con Postman y Jest
+//! globs: *.test.ts
+//! ---
+//! ```
+//!
+//! Skills with `globs` use the `glob` trigger and the rest `model_decision`,
+//! which lets Cascade pick them by description. The global
+//! `.claude/SKILL.md` is `always_on`.
+
+use std::path::{Path, PathBuf};
+
+use super::{ExportedFile, Exporter, Fields, ReferencePolicy, Rule};
+
+/// Where Windsurf looks for workspace rules.
+pub const RULES_DIR: &str = ".windsurf/rules";
+
+/// Exports a project as Windsurf rules.
+#[derive(Clone)]
+pub struct Windsurf {
+    references: ReferencePolicy,
+}
+
+impl Windsurf {
+    pub fn new(references: ReferencePolicy) -> Self {
+        Self { references }
+    }
+}
+
+impl Exporter for Windsurf {
+    fn target(&self) -> &'static str {
+        "windsurf"
+    }
+
+    fn references(&self) -> &ReferencePolicy {
+        &self.references
+    }
+
+    /// Windsurf's `@file` mention, resolved from the workspace root.
+    fn link(&self, target: &Path) -> String {
+        format!("@{}", super::slash(target))
+    }
+
+    fn render(&self, rules: &[Rule]) -> Vec<ExportedFile> {
+        rules
+            .iter()
+            .map(|rule| {
+                let trigger = match (rule.always, rule.globs.is_empty()) {
+                    (true, _) => "always_on",
+                    (false, false) => "glob",
+                    (false, true) => "model_decision",
+                };
+                let fields = [
+                    ("trigger", trigger.to_string()),
+                    ("description", rule.description.clone().unwrap_or_default()),
+                    ("globs", rule.globs.join(",")),
+                ];
+                ExportedFile::new(
+                    PathBuf::from(RULES_DIR).join(format!("{}.md", rule.name)),
+                    super::frontmatter(&fields, &rule.body),
+                )
+            })
+            .collect()
+    }
+
+    fn parse(&self, files: &[ExportedFile]) -> Vec<Rule> {
+        files
+            .iter()
+            .filter(|f| f.path.parent() == Some(Path::new(RULES_DIR)))
+            .filter_map(|f| {
+                let name = super::stem(&f.path, ".md")?;
+                let (fields, body) = Fields::split(&f.contents);
+                Some(Rule {
+                    name: name.to_string(),
+                    description: fields.get("description"),
+                    globs: fields.globs("globs"),
+                    body: super::with_newline(body),
+                    always: fields.get("trigger").as_deref() == Some("always_on"),
+                })
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::export::test_policy;
+
+    #[test]
+    fn triggers_follow_the_rule_kind() {
+        let rule = |always: bool, globs: &[&str]| Rule {
+            name: "a".into(),
+            globs: globs.iter().map(|g| g.to_string()).collect(),
+            always,
+            ..Rule::default()
+        };
+        let windsurf = Windsurf::new(test_policy());
+        let triggers: Vec<String> = [rule(true, &[]), rule(false, &["*.ts"]), rule(false, &[])]
+            .iter()
+            .map(|r| {
+                let file = &windsurf.render(std::slice::from_ref(r))[0];
+                let (fields, _) = Fields::split(&file.contents);
+                fields.get("trigger").unwrap()
+            })
+            .collect();
+        assert_eq!(triggers, ["always_on", "glob", "model_decision"]);
+    }
+}
@@ -0,0 +1,618 @@
+//! How skills build on each other through `depends_on` and `extends`.
+//!
+//! ```yaml
+//! name: react-extension
+//! extends: typescript-extension
+//! depends_on: [pnpm-extension]
+//! ```
+//!
+//! `depends_on` names skills whose instructions must be in context before
+//! this one's; a [`SkillLoader`](crate::SkillLoader) pulls them in, once per
+//! session, when the dependent skill is loaded at Level 2. `extends` names a
+//! single base skill that [`compose`] merges with this one: a `##` section
+//! titled like one of the base's replaces it, the others follow the base's.
+//! Either kind of edge may not form a cycle.
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt::{self, Write as _};
+use std::path::PathBuf;
+
+use serde::Serialize;
+
+use crate::project::Project;
+use crate::skill::{Frontmatter, Skill};
+use crate::{Error, Result};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EdgeKind {
+    DependsOn,
+    Extends,
+}
+
+impl EdgeKind {
+    /// The frontmatter key that declares the edge.
+    pub fn key(self) -> &'static str {
+        match self {
+            EdgeKind::DependsOn => "depends_on",
+            EdgeKind::Extends => "extends",
+        }
+    }
+}
+
+impl fmt::Display for EdgeKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            EdgeKind::DependsOn => "depends on",
+            EdgeKind::Extends => "extends",
+        })
+    }
+}
+
+/// `from` depends on, or extends, `to`.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+pub struct Edge {
+    pub from: String,
+    pub to: String,
+    pub kind: EdgeKind,
+}
+
+#[derive(Debug, Clone, Default)]
+struct Links {
+    extends: Option<String>,
+    depends_on: Vec<String>,
+}
+
+impl Links {
+    /// Outgoing edges, the base first.
+    fn targets(&self) -> impl Iterator<Item = (&str, EdgeKind)> {
+        self.extends
+            .iter()
+            .map(|to| (to.as_str(), EdgeKind::Extends))
+            .chain(
+                self.depends_on
+                    .iter()
+                    .map(|to| (to.as_str(), EdgeKind::DependsOn)),
+            )
+    }
+}
+
+/// Skills by name with the edges their frontmatter declares.
+#[derive(Debug, Clone, Default)]
+pub struct Graph {
+    nodes: BTreeMap<String, Links>,
+}
+
+impl Graph {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The skills installed in `project`, by
+    /// [`SkillDir::skill_name`](crate::SkillDir::skill_name). Skills that
+    /// fail to parse are left out; `skills lint` reports them. Two skills
+    /// with the same name are an error.
+    pub fn from_project(project: &Project) -> Result<Self> {
+        let mut graph = Self::new();
+        let mut paths: BTreeMap<String, PathBuf> = BTreeMap::new();
+        for dir in project.skills()? {
+            let Ok(skill) = Skill::from_path(dir.skill_file()) else {
+                continue;
+            };
+            let name = dir.skill_name(&skill).to_string();
+            if let Some(first) = paths.get(&name) {
+                return Err(Error::DuplicateSkill {
+                    name,
+                    first: first.clone(),
+                    second: dir.path,
+                });
+            }
+            graph = graph.skill(&name, skill.frontmatter());
+            paths.insert(name, dir.path);
+        }
+        Ok(graph)
+    }
+
+    /// Add a skill and the edges declared in its frontmatter.
+    pub fn skill(mut self, name: impl Into<String>, frontmatter: &Frontmatter) -> Self {
+        let mut depends_on = Vec::new();
+        for dep in &frontmatter.depends_on {
+            if !depends_on.contains(dep) {
+                depends_on.push(dep.clone());
+            }
+        }
+        let links = Links {
+            extends: frontmatter.extends.clone(),
+            depends_on,
+        };
+        self.nodes.insert(name.into(), links);
+        self
+    }
+
+    pub fn contains(&self, name: &str) -> bool {
+        self.nodes.contains_key(name)
+    }
+
+    /// Skill names, sorted.
+    pub fn names(&self) -> impl Iterator<Item = &str> {
+        self.nodes.keys().map(String::as_str)
+    }
+
+    /// Every edge, grouped by the skill declaring it.
+    pub fn edges(&self) -> Vec<Edge> {
+        self.nodes
+            .iter()
+            .flat_map(|(from, links)| {
+                links.targets().map(|(to, kind)| Edge {
+                    from: from.clone(),
+                    to: to.to_string(),
+                    kind,
+                })
+            })
+            .collect()
+    }
+
+    /// Edges to skills that are not in the graph.
+    pub fn unknown(&self) -> Vec<Edge> {
+        self.edges()
+            .into_iter()
+            .filter(|e| !self.contains(&e.to))
+            .collect()
+    }
+
+    /// At least one cycle through every group of skills that reach each
+    /// other, each as `[a, b, .., a]` starting from its first skill by name.
+    pub fn cycles(&self) -> Vec<Vec<String>> {
+        let mut done = BTreeSet::new();
+        let mut found = Vec::new();
+        for name in self.nodes.keys() {
+            let mut stack = Vec::new();
+            self.find_cycles(name, &mut stack, &mut done, &mut found);
+        }
+        found
+    }
+
+    fn find_cycles<'a>(
+        &'a self,
+        name: &'a str,
+        stack: &mut Vec<&'a str>,
+        done: &mut BTreeSet<&'a str>,
+        found: &mut Vec<Vec<String>>,
+    ) {
+        if let Some(start) = stack.iter().position(|n| *n == name) {
+            let mut cycle: Vec<String> = stack[start..].iter().map(|n| n.to_string()).collect();
+            let first = (0..cycle.len()).min_by_key(|&i| &cycle[i]).unwrap_or(0);
+            cycle.rotate_left(first);
+            cycle.push(cycle[0].clone());
+            if !found.contains(&cycle) {
+                found.push(cycle);
+            }
+            return;
+        }
+        if done.contains(name) {
+            return;
+        }
+        let Some(links) = self.nodes.get(name) else {
+            return;
+        };
+        stack.push(name);
+        for (to, _) in links.targets() {
+            self.find_cycles(to, stack, done, found);
+        }
+        stack.pop();
+        done.insert(name);
+    }
+
+    /// `name` and the skills it extends, the root base first.
+    pub fn bases(&self, name: &str) -> Result<Vec<String>> {
+        let mut chain = vec![name.to_string()];
+        let mut current = name;
+        while let Some(base) = self.nodes.get(current).and_then(|l| l.extends.as_deref()) {
+            if let Some(start) = chain.iter().position(|n| n == base) {
+                let mut cycle = chain[start..].to_vec();
+                cycle.push(base.to_string());
+                return Err(Error::Cycle(cycle));
+            }
+            chain.push(base.to_string());
+            current = base;
+        }
+        chain.reverse();
+        Ok(chain)
+    }
+
+    /// Skills to load before `name`, each after its own prerequisites and
+    /// each once. Its bases are not listed, since [`compose`] merges them
+    /// into its instructions, but what they depend on is. Unknown skills
+    /// are skipped.
+    pub fn prerequisites(&self, name: &str) -> Result<Vec<String>> {
+        let bases = self.bases(name)?;
+        let mut order = Vec::new();
+        let mut stack = Vec::new();
+        for base in &bases {
+            let Some(links) = self.nodes.get(base) else {
+                continue;
+            };
+            for dep in &links.depends_on {
+                self.visit(dep, &mut stack, &mut order)?;
+            }
+        }
+        order.retain(|n| !bases.contains(n));
+        Ok(order)
+    }
+
+    /// Depth-first, appending `name` after everything it reaches.
+    fn visit(&self, name: &str, stack: &mut Vec<String>, order: &mut Vec<String>) -> Result<()> {
+        if let Some(start) = stack.iter().position(|n| n == name) {
+            let mut cycle = stack[start..].to_vec();
+            cycle.push(name.to_string());
+            return Err(Error::Cycle(cycle));
+        }
+        if order.iter().any(|n| n == name) {
+            return Ok(());
+        }
+        let Some(links) = self.nodes.get(name) else {
+            return Ok(());
+        };
+        stack.push(name.to_string());
+        for (to, _) in links.targets() {
+            self.visit(to, stack, order)?;
+        }
+        stack.pop();
+        order.push(name.to_string());
+        Ok(())
+    }
+
+    /// Graphviz source; `extends` edges are dashed.
+    pub fn to_dot(&self) -> String {
+        let mut out = String::from("digraph skills {\n    rankdir=LR;\n    node [shape=box];\n");
+        for name in self.all_names() {
+            let style = match self.contains(name) {
+                true => "",
+                false => " [style=dashed]",
+            };
+            let _ = writeln!(out, "    {}{style};", dot_id(name));
+        }
+        for edge in self.edges() {
+            let (from, to) = (dot_id(&edge.from), dot_id(&edge.to));
+            let _ = match edge.kind {
+                EdgeKind::DependsOn => writeln!(out, "    {from} -> {to};"),
+                EdgeKind::Extends => {
+                    writeln!(out, "    {from} -> {to} [style=dashed, label=\"extends\"];")
+                }
+            };
+        }
+        out.push_str("}\n");
+        out
+    }
+
+    /// A Mermaid flowchart; `extends` edges are dotted.
+    pub fn to_mermaid(&self) -> String {
+        let names: Vec<&str> = self.all_names().collect();
+        let id = |name: &str| names.iter().position(|n| *n == name).unwrap_or(0);
+        let mut out = String::from("flowchart LR\n");
+        for (i, name) in names.iter().enumerate() {
+            let _ = writeln!(out, "    s{i}[\"{}\"]", mermaid_label(name));
+        }
+        for edge in self.edges() {
+            let (from, to) = (id(&edge.from), id(&edge.to));
+            let _ = match edge.kind {
+                EdgeKind::DependsOn => writeln!(out, "    s{from} --> s{to}"),
+                EdgeKind::Extends => writeln!(out, "    s{from} -. extends .-> s{to}"),
+            };
+        }
+        out
+    }
+
+    /// Known skills and the unknown ones edges point at, sorted.
+    fn all_names(&self) -> impl Iterator<Item = &str> {
+        let mut names: BTreeSet<&str> = self.names().collect();
+        for links in self.nodes.values() {
+            names.extend(links.targets().map(|(to, _)| to));
+        }
+        names.into_iter()
+    }
+}
+
+/// `name` as a quoted Graphviz ID. Names come from frontmatter, so they may
+/// hold quotes or line breaks.
+fn dot_id(name: &str) -> String {
+    let mut id = String::from("\"");
+    for c in name.chars() {
+        match c {
+            '"' => id.push_str("\\\""),
+            '\\' => id.push_str("\\\\"),
+            '\n' => id.push_str("\\n"),
+            '\r' => {}
+            c => id.push(c),
+        }
+    }
+    id.push('"');
+    id
+}
+
+/// `name` for a quoted Mermaid label, with the characters Mermaid reads as
+/// markup written as entity codes.
+fn mermaid_label(name: &str) -> String {
+    let mut label = String::new();
+    for c in name.chars() {
+        match c {
+            '"' => label.push_str("#quot;"),
+            '#' => label.push_str("#35;"),
+            '<' => label.push_str("#lt;"),
+            '>' => label.push_str("#gt;"),
+            '\n' | '\r' => label.push(' '),
+            c => label.push(c),
+        }
+    }
+    label
+}
+
+/// A part of composed instructions and the skill it was taken from.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Block {
+    pub skill: String,
+    /// The `##` heading, or `None` for the text before the first one.
+    pub title: Option<String>,
+    pub text: String,
+}
+
+/// Merge the bodies of an `extends` chain, root base first. Each skill's
+/// `##` sections replace those of its bases with the same title, ignoring
+/// case, and its other sections are appended. The text before the first
+/// `##` comes from the last skill that has any.
+pub fn compose(chain: &[(&str, &Skill)]) -> Vec<Block> {
+    let mut blocks: Vec<Block> = Vec::new();
+    for (name, skill) in chain {
+        let parts = split(skill).into_iter().map(|(title, text)| Block {
+            skill: name.to_string(),
+            title: title.map(str::to_string),
+            text: text.to_string(),
+        });
+        merge(&mut blocks, parts, |b| b.title.as_deref());
+    }
+    blocks
+}
+
+/// A body cut at its `##` headings: the text before the first one, when
+/// there is any, with no title, then each section with its title.
+pub(crate) fn split(skill: &Skill) -> Vec<(Option<&str>, &str)> {
+    let body = skill.body();
+    let offset = skill.body_span().start.offset;
+    let top: Vec<_> = skill.sections().iter().filter(|s| s.level == 2).collect();
+    let intro_end = top
+        .first()
+        .map_or(body.len(), |s| s.span.start.offset - offset);
+    let intro = body[..intro_end].trim();
+    let mut parts = Vec::new();
+    if !intro.is_empty() {
+        parts.push((None, intro));
+    }
+    for section in top {
+        parts.push((
+            Some(section.title.as_str()),
+            section.span.text(skill.source()).trim(),
+        ));
+    }
+    parts
+}
+
+/// Lay `patch` over `parts`: an untitled part replaces the untitled one,
+/// a titled part replaces the part with the same title, ignoring case, and
+/// anything new is added, untitled parts first.
+pub(crate) fn merge<T>(
+    parts: &mut Vec<T>,
+    patch: impl IntoIterator<Item = T>,
+    title: impl Fn(&T) -> Option<&str>,
+) {
+    for part in patch {
+        let same = parts.iter().position(|p| match (title(p), title(&part)) {
+            (Some(a), Some(b)) => a.eq_ignore_ascii_case(b),
+            (a, b) => a.is_none() && b.is_none(),
+        });
+        match (same, title(&part)) {
+            (Some(i), _) => parts[i] = part,
+            (None, None) => parts.insert(0, part),
+            (None, Some(_)) => parts.push(part),
+        }
+    }
+}
+
+/// Blocks joined back into markdown.
+pub fn render(blocks: &[Block]) -> String {
+    let texts: Vec<&str> = blocks.iter().map(|b| b.text.as_str()).collect();
+    texts.join("\n\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::TempDir;
+
+    fn frontmatter(yaml: &str) -> Frontmatter {
+        Skill::parse(format!("---\ndescription: x\n{yaml}---\n"))
+            .unwrap()
+            .frontmatter()
+            .clone()
+    }
+
+    /// `react` extends `typescript` and depends on `pnpm`, which depends on
+    /// `node`.
+    fn graph() -> Graph {
+        Graph::new()
+            .skill(
+                "react",
+                &frontmatter("extends: typescript\ndepends_on: [pnpm, pnpm]\n"),
+            )
+            .skill("typescript", &frontmatter("depends_on: [node]\n"))
+            .skill("pnpm", &frontmatter("depends_on: [node]\n"))
+            .skill("node", &frontmatter(""))
+    }
+
+    #[test]
+    fn edges_list_the_base_first_without_repeats() {
+        let edges: Vec<String> = graph()
+            .edges()
+            .iter()
+            .map(|e| format!("{} {} {}", e.from, e.kind, e.to))
+            .collect();
+        assert_eq!(
+            edges,
+            [
+                "pnpm depends on node",
+                "react extends typescript",
+                "react depends on pnpm",
+                "typescript depends on node",
+            ]
+        );
+        assert_eq!(graph().unknown(), []);
+    }
+
+    #[test]
+    fn unknown_targets_are_reported() {
+        let graph = Graph::new().skill("react", &frontmatter("depends_on: [vite]\n"));
+        assert_eq!(
+            graph.unknown(),
+            [Edge {
+                from: "react".into(),
+                to: "vite".into(),
+                kind: EdgeKind::DependsOn
+            }]
+        );
+    }
+
+    #[test]
+    fn prerequisites_come_before_what_needs_them() {
+        let graph = graph();
+        assert_eq!(graph.bases("react").unwrap(), ["typescript", "react"]);
+        // The base is merged in, but what it depends on is loaded.
+        assert_eq!(graph.prerequisites("react").unwrap(), ["node", "pnpm"]);
+        assert_eq!(graph.prerequisites("node").unwrap(), Vec::<String>::new());
+        assert_eq!(
+            graph.prerequisites("missing").unwrap(),
+            Vec::<String>::new()
+        );
+    }
+
+    #[test]
+    fn cycles_are_found_once_from_their_first_skill() {
+        let graph = Graph::new()
+            .skill("b", &frontmatter("depends_on: [c]\n"))
+            .skill("c", &frontmatter("extends: a\n"))
+            .skill("a", &frontmatter("depends_on: [b]\n"));
+        assert_eq!(graph.cycles(), [vec!["a", "b", "c", "a"]]);
+        assert!(matches!(
+            graph.prerequisites("a"),
+            Err(Error::Cycle(cycle)) if cycle == ["b", "c", "a", "b"]
+        ));
+
+        let graph = Graph::new()
+            .skill("a", &frontmatter("extends: b\n"))
+            .skill("b", &frontmatter("extends: a\n"));
+        assert!(matches!(graph.bases("a"), Err(Error::Cycle(_))));
+    }
+
+    #[test]
+    fn from_project_keys_skills_by_their_name() {
+        let dir = TempDir::new();
+        dir.write(
+            ".claude/skills/react-dir/SKILL.md",
+            "---\nname: react\ndescription: x\ndepends_on: [pnpm]\n---\n",
+        );
+        dir.write(".claude/skills/pnpm/SKILL.md", "---\ndescription: x\n---\n");
+        dir.write(".claude/skills/broken/SKILL.md", "no frontmatter");
+        let graph = Graph::from_project(&Project::new(dir.path())).unwrap();
+        assert_eq!(graph.names().collect::<Vec<_>>(), ["pnpm", "react"]);
+        assert_eq!(graph.unknown(), []);
+    }
+
+    #[test]
+    fn two_skills_with_one_name_are_an_error() {
+        let dir = TempDir::new();
+        dir.write(
+            ".claude/skills/a/SKILL.md",
+            "---\nname: react\ndescription: x\n---\n",
+        );
+        dir.write(
+            ".claude/skills/b/SKILL.md",
+            "---\nname: react\ndescription: x\n---\n",
+        );
+        match Graph::from_project(&Project::new(dir.path())) {
+            Err(Error::DuplicateSkill {
+                name,
+                first,
+                second,
+            }) => {
+                assert_eq!(name, "react");
+                assert!(first.ends_with("a") && second.ends_with("b"));
+            }
+            other => panic!("expected a duplicate, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn dot_output_quotes_and_escapes_names() {
+        let graph = Graph::new()
+            .skill("react", &frontmatter("extends: typescript\n"))
+            .skill("a\"b\\c\nd", &frontmatter("depends_on: [react]\n"));
+        assert_eq!(
+            graph.to_dot(),
+            "digraph skills {
+    rankdir=LR;
+    node [shape=box];
+    \"a\\\"b\\\\c\\nd\";
+    \"react\";
+    \"typescript\" [style=dashed];
+    \"a\\\"b\\\\c\\nd\" -> \"react\";
+    \"react\" -> \"typescript\" [style=dashed, label=\"extends\"];
+}
+"
+        );
+    }
+
+    #[test]
+    fn mermaid_output_escapes_labels() {
+        let graph = Graph::new()
+            .skill("react", &frontmatter("extends: typescript\n"))
+            .skill("x\"]-->y<b>#", &frontmatter("depends_on: [react]\n"));
+        assert_eq!(
+            graph.to_mermaid(),
+            "flowchart LR
+    s0[\"react\"]
+    s1[\"typescript\"]
+    s2[\"x#quot;]--#gt;y#lt;b#gt;#35;\"]
+    s0 -. extends .-> s1
+    s2 --> s0
+"
+        );
+    }
+
+    #[test]
+    fn compose_replaces_sections_by_title_and_appends_new_ones() {
+        let base = Skill::parse(
+            "---\nname: ts\ndescription: x\n---\nIntro\n\n## Setup\n\nnpm i\n\n## Style\n\nstrict\n",
+        )
+        .unwrap();
+        let child = Skill::parse(
+            "---\nname: react\ndescription: x\n---\n## setup\n\npnpm i\n\n## JSX\n\nuse tsx\n",
+        )
+        .unwrap();
+        let blocks = compose(&[("ts", &base), ("react", &child)]);
+        let origin: Vec<(&str, Option<&str>)> = blocks
+            .iter()
+            .map(|b| (b.skill.as_str(), b.title.as_deref()))
+            .collect();
+        assert_eq!(
+            origin,
+            [
+                ("ts", None),
+                ("react", Some("setup")),
+                ("ts", Some("Style")),
+                ("react", Some("JSX"))
+            ]
+        );
+        assert_eq!(
+            render(&blocks),
+            "Intro\n\n## setup\n\npnpm i\n\n## Style\n\nstrict\n\n## JSX\n\nuse tsx"
+        );
+    }
+}
@@ -0,0 +1,693 @@
+//! `skills import`: turning other tools' rule files into skills.
+//!
+//! - `.cursor/rules/*.mdc` and `.github/instructions/*.instructions.md`
+//!   hold one rule per file and become one skill each, keeping their
+//!   description and globs.
+//! - `.cursorrules`, `AGENTS.md`, `CLAUDE.md` and
+//!   `.github/copilot-instructions.md` are monolithic. They are split at the
+//!   shallowest heading level that occurs more than once, one skill per
+//!   heading; text before the first split goes to the global
+//!   `.claude/SKILL.md`. An `AGENTS.md` written by `skills export` is read
+//!   back skill by skill instead.
+//!
+//! Every imported skill gets a kebab-case `name`, a `description` taken
+//! from its first sentence and a "When to Use This Skill" section. While a
+//! `SKILL.md` is longer than `lint.max-lines`, its largest `##` section is
+//! moved into `references/`.
+
+use std::collections::BTreeSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::config::{Config, LintConfig};
+use crate::export::{self, combined, copilot, cursor, ExportedFile, Rule};
+use crate::lint::is_when_to_use;
+use crate::parse::{self, markdown_lines};
+use crate::project::{Project, CLAUDE_DIR, REFERENCES_DIR, SKILLS_DIR};
+use crate::relevance::fold;
+use crate::skill::{self, is_kebab_case, Frontmatter, SKILL_FILE};
+use crate::span::LineIndex;
+use crate::{Error, Result};
+
+/// Monolithic instruction files looked for at the project root.
+pub const SOURCES: &[&str] = &[
+    ".cursorrules",
+    "AGENTS.md",
+    "CLAUDE.md",
+    ".github/copilot-instructions.md",
+];
+/// Directories of one-rule-per-file formats, with the file suffix.
+const RULE_DIRS: &[(&str, &str)] = &[
+    (cursor::RULES_DIR, ".mdc"),
+    (copilot::INSTRUCTIONS_DIR, ".instructions.md"),
+];
+/// Generated descriptions are cut to this many characters.
+const DESCRIPTION_CHARS: usize = 200;
+/// The lint rule's lower bound for a useful description.
+const MIN_DESCRIPTION_CHARS: usize = 20;
+
+/// A skill built from imported rules.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ImportedSkill {
+    pub name: String,
+    /// The file it came from.
+    pub source: PathBuf,
+    /// `SKILL.md` first, then any references, relative to the project root.
+    pub files: Vec<ExportedFile>,
+    /// Titles of sections moved into `references/`.
+    pub moved: Vec<String>,
+}
+
+/// Everything an import would write.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Import {
+    pub skills: Vec<ImportedSkill>,
+    /// The global `.claude/SKILL.md`, built from text before the first split
+    /// of each monolithic file.
+    pub global: Option<ExportedFile>,
+}
+
+impl Import {
+    /// Write the import. Existing skill directories and an existing global
+    /// file are only replaced when `force` is set.
+    pub fn write(&self, project: &Project, force: bool) -> Result<Vec<PathBuf>> {
+        if !force {
+            for skill in &self.skills {
+                let dir = project.skill(&skill.name).path;
+                if dir.exists() {
+                    return Err(Error::AlreadyExists(dir));
+                }
+            }
+        }
+        let mut files: Vec<ExportedFile> = Vec::new();
+        if let Some(global) = &self.global {
+            if force || !project.global_skill_file().exists() {
+                files.push(global.clone());
+            }
+        }
+        for skill in &self.skills {
+            let dir = project.skill(&skill.name).path;
+            if dir.exists() {
+                fs::remove_dir_all(&dir).map_err(|e| Error::io(&dir, e))?;
+            }
+            files.extend(skill.files.iter().cloned());
+        }
+        export::write(project, &files)
+    }
+}
+
+/// Builds skills out of other tools' rule files.
+#[derive(Debug, Clone)]
+pub struct Importer {
+    max_lines: usize,
+}
+
+impl Default for Importer {
+    fn default() -> Self {
+        Self {
+            max_lines: LintConfig::default().max_lines,
+        }
+    }
+}
+
+impl Importer {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn from_config(config: &Config) -> Self {
+        Self::new().max_lines(config.lint.max_lines)
+    }
+
+    /// Longest `SKILL.md` to produce before moving sections out.
+    pub fn max_lines(mut self, max_lines: usize) -> Self {
+        self.max_lines = max_lines;
+        self
+    }
+
+    /// Known rule files present in `project`, monolithic ones first.
+    pub fn discover(project: &Project) -> Result<Vec<PathBuf>> {
+        let mut found: Vec<PathBuf> = SOURCES
+            .iter()
+            .map(|source| project.join(source))
+            .filter(|path| path.is_file())
+            .collect();
+        for (dir, suffix) in RULE_DIRS {
+            let dir = project.join(dir);
+            let entries = match fs::read_dir(&dir) {
+                Ok(entries) => entries,
+                Err(e) if e.kind() == std::io::ErrorKind::NotFound => continue,
+                Err(e) => return Err(Error::io(&dir, e)),
+            };
+            let mut files = Vec::new();
+            for entry in entries {
+                let path = entry.map_err(|e| Error::io(&dir, e))?.path();
+                let name = path.file_name().unwrap_or_default().to_string_lossy();
+                if path.is_file() && name.ends_with(suffix) {
+                    files.push(path);
+                }
+            }
+            files.sort();
+            found.extend(files);
+        }
+        Ok(found)
+    }
+
+    /// Build skills from `sources`. Names are made unique across the whole
+    /// import.
+    pub fn import(&self, sources: &[PathBuf]) -> Result<Import> {
+        let mut import = Import::default();
+        let mut taken = BTreeSet::new();
+        let mut preambles: Vec<(String, String)> = Vec::new();
+        for source in sources {
+            let text = fs::read_to_string(source).map_err(|e| Error::io(source, e))?;
+            let file_name = source
+                .file_name()
+                .map(|n| n.to_string_lossy().into_owned())
+                .unwrap_or_default();
+            let (preamble, rules) = rules(&file_name, &text);
+            if let Some(preamble) = preamble {
+                preambles.push((file_name.clone(), preamble));
+            }
+            for rule in rules {
+                let skill = self.skill(rule, source, &file_name, &mut taken);
+                import.skills.push(skill);
+            }
+        }
+        import.global = global(&preambles);
+        Ok(import)
+    }
+
+    fn skill(
+        &self,
+        rule: Rule,
+        source: &Path,
+        file_name: &str,
+        taken: &mut BTreeSet<String>,
+    ) -> ImportedSkill {
+        let name = unique(
+            match is_kebab_case(&rule.name) {
+                true => rule.name.clone(),
+                false => slug(&rule.name),
+            },
+            taken,
+        );
+        let mut body = rule.body.trim().to_string();
+        let title = match body.lines().next().and_then(parse::heading) {
+            Some((1, title)) => title,
+            _ => {
+                let title = match is_kebab_case(&rule.name) {
+                    true => title_case(&name),
+                    false => rule.name.trim().to_string(),
+                };
+                body = format!("# {title}\n\n{body}");
+                title
+            }
+        };
+        let description = rule
+            .description
+            .filter(|d| !d.trim().is_empty())
+            .unwrap_or_else(|| describe(&body, &title, file_name));
+        if !headings(&body).iter().any(|(_, t, _)| is_when_to_use(t)) {
+            body = insert_when_to_use(&body, &description);
+        }
+        let frontmatter = Frontmatter {
+            name: Some(name.clone()),
+            description: Some(description),
+            globs: rule.globs,
+            ..Frontmatter::default()
+        };
+
+        let dir = Path::new(CLAUDE_DIR).join(SKILLS_DIR).join(&name);
+        let mut references = Vec::new();
+        let mut moved = Vec::new();
+        let mut slugs = BTreeSet::new();
+        loop {
+            let text = skill::render(&frontmatter, &format!("\n{body}\n"));
+            if text.lines().count() <= self.max_lines {
+                break;
+            }
+            // With nothing left to move, lint reports the length.
+            let Some((rest, title, slug, content)) =
+                move_largest_section(&body, &moved, &mut slugs)
+            else {
+                break;
+            };
+            body = rest;
+            references.push(ExportedFile::new(
+                dir.join(REFERENCES_DIR).join(format!("{slug}.md")),
+                content,
+            ));
+            moved.push(title);
+        }
+        let text = skill::render(&frontmatter, &format!("\n{body}\n"));
+        let mut files = vec![ExportedFile::new(dir.join(SKILL_FILE), text)];
+        files.extend(references);
+        ImportedSkill {
+            name,
+            source: source.to_path_buf(),
+            files,
+            moved,
+        }
+    }
+}
+
+/// The rules in one file and any text meant for the global file.
+fn rules(file_name: &str, text: &str) -> (Option<String>, Vec<Rule>) {
+    if let Some(name) = file_name.strip_suffix(".mdc") {
+        return (None, vec![cursor::parse_rule(name, text)]);
+    }
+    if let Some(name) = file_name.strip_suffix(".instructions.md") {
+        return (None, vec![copilot::parse_instructions(name, text)]);
+    }
+    if text.lines().any(|l| l.starts_with("<!-- skill: ")) {
+        let (global, skills): (Vec<Rule>, Vec<Rule>) =
+            combined::parse(text).into_iter().partition(|r| r.always);
+        let preamble = global.into_iter().next().map(|r| r.body);
+        return (preamble, skills);
+    }
+    split(text)
+}
+
+/// Split a monolithic file at the shallowest heading level that repeats.
+fn split(text: &str) -> (Option<String>, Vec<Rule>) {
+    let headings = headings(text);
+    let level = (1..=6).find(|l| headings.iter().filter(|(level, ..)| level == l).count() >= 2);
+    let splits: Vec<&(u8, String, usize)> = match level {
+        Some(level) => headings
+            .iter()
+            .skip_while(|(l, ..)| *l != level)
+            .filter(|(l, ..)| *l <= level)
+            .collect(),
+        // No repeated level: the first heading starts a single skill.
+        None => headings.iter().take(1).collect(),
+    };
+    let Some((_, _, first)) = splits.first() else {
+        return (non_empty(text), Vec::new());
+    };
+    let preamble = non_empty(&text[..*first]);
+
+    let rules = splits
+        .iter()
+        .enumerate()
+        .map(|(i, (level, title, start))| {
+            let end = splits.get(i + 1).map_or(text.len(), |(_, _, next)| *next);
+            let content_start = text[*start..].find('\n').map_or(end, |n| start + n + 1);
+            let content = promote(&text[content_start.min(end)..end], level - 1);
+            Rule {
+                name: title.clone(),
+                body: format!("# {title}\n\n{}\n", content.trim()),
+                ..Rule::default()
+            }
+        })
+        .collect();
+    (preamble, rules)
+}
+
+/// `(level, title, offset)` of every heading outside code blocks.
+fn headings(text: &str) -> Vec<(u8, String, usize)> {
+    parse::sections(text, 0, &LineIndex::new(text))
+        .into_iter()
+        .map(|s| (s.level, s.title, s.span.start.offset))
+        .collect()
+}
+
+/// Raise every heading outside code blocks by `by` levels, stopping at `#`.
+fn promote(text: &str, by: u8) -> String {
+    let mut out = String::with_capacity(text.len());
+    for (line, code) in markdown_lines(text, 0..text.len()) {
+        let ending = &text[line.start + line.text.len()..line.next];
+        match parse::heading(line.text).filter(|_| !code && by > 0) {
+            Some((level, title)) => {
+                let level = level.saturating_sub(by).max(1) as usize;
+                out.push_str(&format!("{} {title}{ending}", "#".repeat(level)));
+            }
+            None => out.push_str(&text[line.start..line.next]),
+        }
+    }
+    out
+}
+
+/// Move the longest `##` section that was not moved yet and is not "When to
+/// Use" into a reference, leaving a link. Returns the new body, the section
+/// title, its slug, unique among `slugs`, and the reference contents.
+fn move_largest_section(
+    body: &str,
+    moved: &[String],
+    slugs: &mut BTreeSet<String>,
+) -> Option<(String, String, String, String)> {
+    let sections = parse::sections(body, 0, &LineIndex::new(body));
+    let section = sections
+        .iter()
+        .filter(|s| s.level == 2 && !is_when_to_use(&s.title) && !moved.contains(&s.title))
+        .max_by_key(|s| s.span.text(body).lines().count())?;
+    let slug = unique(slug(&section.title), slugs);
+    let reference = format!(
+        "# {}\n\n{}\n",
+        section.title,
+        promote(section.content.text(body), 1).trim()
+    );
+    let link = format!("{REFERENCES_DIR}/{slug}.md");
+    let pointer = format!("## {}\n\nSee [{link}]({link}).\n\n", section.title);
+    let range = section.span.range();
+    let body = format!("{}{pointer}{}", &body[..range.start], &body[range.end..]);
+    Some((
+        body.trim_end().to_string(),
+        section.title.clone(),
+        slug,
+        reference,
+    ))
+}
+
+/// Add a "When to Use This Skill" section after the title.
+fn insert_when_to_use(body: &str, description: &str) -> String {
+    let (title, rest) = body.split_once('\n').unwrap_or((body, ""));
+    format!(
+        "{title}\n\n## When to Use This Skill\n\n- {description}\n\n{}",
+        rest.trim_start()
+    )
+    .trim_end()
+    .to_string()
+}
+
+/// The first sentence of the first paragraph, stripped of markdown, or a
+/// generic description when that is too short to be useful.
+fn describe(body: &str, title: &str, file_name: &str) -> String {
+    let sentence = markdown_lines(body, 0..body.len())
+        .filter(|(_, code)| !code)
+        .map(|(line, _)| line.text.trim())
+        .find(|line| {
+            !line.is_empty() && !line.starts_with(['#', '>', '|', '<', '-', '*']) && !is_step(line)
+        })
+        .map(|line| {
+            let plain: String = line
+                .chars()
+                .filter(|c| !matches!(c, '*' | '`' | '_'))
+                .collect();
+            let end = plain.find(". ").map_or(plain.len(), |i| i + 1);
+            truncate(plain[..end].trim(), DESCRIPTION_CHARS)
+        })
+        .unwrap_or_default();
+    match sentence.chars().count() >= MIN_DESCRIPTION_CHARS {
+        true => sentence,
+        false => format!("{title}: instrucciones importadas de {file_name}"),
+    }
+}
+
+fn is_step(line: &str) -> bool {
+    let digits = line.len() - line.trim_start_matches(|c: char| c.is_ascii_digit()).len();
+    digits > 0 && line[digits..].starts_with(['.', ')'])
+}
+
+/// `text` cut to at most `max` characters at a word boundary.
+fn truncate(text: &str, max: usize) -> String {
+    if text.chars().count() <= max {
+        return text.to_string();
+    }
+    let cut: String = text.chars().take(max).collect();
+    match cut.rfind(' ') {
+        Some(space) => cut[..space].trim_end_matches([',', ';', ':']).to_string(),
+        None => cut,
+    }
+}
+
+/// `Convenciones de código` → `convenciones-de-codigo`.
+fn slug(title: &str) -> String {
+    let folded = fold(&title.to_lowercase());
+    let slug = folded
+        .split(|c: char| !c.is_ascii_alphanumeric())
+        .filter(|part| !part.is_empty())
+        .collect::<Vec<_>>()
+        .join("-");
+    match slug.is_empty() {
+        true => "imported".to_string(),
+        false => slug,
+    }
+}
+
+/// `name`, or `name-2`, `name-3`... when already taken.
+fn unique(name: String, taken: &mut BTreeSet<String>) -> String {
+    let mut candidate = name.clone();
+    let mut n = 2;
+    while taken.contains(&candidate) {
+        candidate = format!("{name}-{n}");
+        n += 1;
+    }
+    taken.insert(candidate.clone());
+    candidate
+}
+
+fn title_case(name: &str) -> String {
+    let mut chars = name.chars();
+    let first = chars.next().map(|c| c.to_uppercase().to_string());
+    format!("{}{}", first.unwrap_or_default(), chars.as_str()).replace('-', " ")
+}
+
+fn non_empty(text: &str) -> Option<String> {
+    let text = text.trim();
+    (!text.is_empty()).then(|| format!("{text}\n"))
+}
+
+/// The global `SKILL.md` for the collected preambles.
+fn global(preambles: &[(String, String)]) -> Option<ExportedFile> {
+    if preambles.is_empty() {
+        return None;
+    }
+    let sources: Vec<&str> = preambles
+        .iter()
+        .map(|(source, _)| source.as_str())
+        .collect();
+    let frontmatter = Frontmatter {
+        description: Some(format!(
+            "Configuración del proyecto importada de {}",
+            sources.join(", ")
+        )),
+        ..Frontmatter::default()
+    };
+    let body: Vec<&str> = preambles.iter().map(|(_, text)| text.trim()).collect();
+    Some(ExportedFile::new(
+        Path::new(CLAUDE_DIR).join(SKILL_FILE),
+        skill::render(&frontmatter, &format!("\n{}\n", body.join("\n\n"))),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::TempDir;
+    use crate::Skill;
+
+    const CLAUDE_MD: &str = "# Proyecto\n\nUsa pnpm siempre.\n\n## Convenciones de código\n\nEscribe funciones pequeñas y con nombres claros.\n\n### Detalles\n\nNada más.\n\n## Testing\n\nEjecuta `pnpm test` antes de cada commit.\n";
+
+    fn import(dir: &TempDir, files: &[(&str, &str)]) -> Import {
+        let sources: Vec<PathBuf> = files
+            .iter()
+            .map(|(path, text)| dir.write(path, text))
+            .collect();
+        Importer::new().import(&sources).unwrap()
+    }
+
+    fn parsed(skill: &ImportedSkill) -> Skill {
+        Skill::parse(skill.files[0].contents.clone()).unwrap()
+    }
+
+    #[test]
+    fn monolithic_files_split_at_the_repeated_heading_level() {
+        let dir = TempDir::new();
+        let import = import(&dir, &[("CLAUDE.md", CLAUDE_MD)]);
+        let names: Vec<_> = import.skills.iter().map(|s| s.name.as_str()).collect();
+        assert_eq!(names, ["convenciones-de-codigo", "testing"]);
+
+        let conventions = parsed(&import.skills[0]);
+        assert_eq!(
+            conventions.description(),
+            Some("Escribe funciones pequeñas y con nombres claros.")
+        );
+        let titles: Vec<_> = conventions
+            .sections()
+            .iter()
+            .map(|s| s.title.as_str())
+            .collect();
+        assert_eq!(
+            titles,
+            [
+                "Convenciones de código",
+                "When to Use This Skill",
+                "Detalles"
+            ]
+        );
+        assert_eq!(conventions.sections()[2].level, 2);
+
+        let global = import.global.unwrap();
+        assert_eq!(global.path, Path::new(".claude/SKILL.md"));
+        let global = Skill::parse(global.contents).unwrap();
+        assert_eq!(global.name(), None);
+        assert!(global.body().contains("Usa pnpm siempre."));
+    }
+
+    #[test]
+    fn one_rule_files_keep_their_description_and_globs() {
+        let dir = TempDir::new();
+        let import = import(
+            &dir,
+            &[(
+                ".cursor/rules/api-testing.mdc",
+                "---\ndescription: Test APIs with Jest and Postman\nglobs: *.test.ts\nalwaysApply: false\n---\nRun the suite.\n",
+            )],
+        );
+        assert_eq!(import.global, None);
+        let skill = parsed(&import.skills[0]);
+        assert_eq!(skill.name(), Some("api-testing"));
+        assert_eq!(skill.description(), Some("Test APIs with Jest and Postman"));
+        assert_eq!(skill.frontmatter().globs, ["*.test.ts"]);
+        assert_eq!(skill.sections()[0].title, "Api testing");
+    }
+
+    #[test]
+    fn names_are_unique_across_the_import() {
+        let dir = TempDir::new();
+        let import = import(
+            &dir,
+            &[
+                ("AGENTS.md", "## Testing\n\nA.\n\n## Testing\n\nB.\n"),
+                (".github/instructions/testing.instructions.md", "C.\n"),
+            ],
+        );
+        let names: Vec<_> = import.skills.iter().map(|s| s.name.as_str()).collect();
+        assert_eq!(names, ["testing", "testing-2", "testing-3"]);
+    }
+
+    #[test]
+    fn short_descriptions_fall_back_to_the_source() {
+        let dir = TempDir::new();
+        let import = import(
+            &dir,
+            &[("AGENTS.md", "## Lint\n\nRun it.\n\n## Test\n\n1. Run it.\n")],
+        );
+        assert_eq!(
+            parsed(&import.skills[0]).description(),
+            Some("Lint: instrucciones importadas de AGENTS.md")
+        );
+    }
+
+    #[test]
+    fn long_skills_move_their_largest_section_to_references() {
+        let dir = TempDir::new();
+        let long = format!(
+            "---\ndescription: A guide long enough to split\n---\n# Guide\n\n## Small\n\nOne.\n\n## Large\n\n{}",
+            "- line\n".repeat(40)
+        );
+        let sources = [dir.write(".cursor/rules/guide.mdc", long)];
+        let import = Importer::new().max_lines(30).import(&sources).unwrap();
+        let skill = &import.skills[0];
+        assert_eq!(skill.moved, ["Large"]);
+        assert_eq!(
+            skill.files[1].path,
+            Path::new(".claude/skills/guide/references/large.md")
+        );
+        assert!(skill.files[1].contents.starts_with("# Large\n\n- line\n"));
+        assert!(skill.files[0]
+            .contents
+            .contains("See [references/large.md](references/large.md)."));
+        assert!(skill.files[0].contents.lines().count() <= 30);
+    }
+
+    #[test]
+    fn sections_whose_titles_share_a_slug_get_their_own_reference() {
+        let dir = TempDir::new();
+        let long = format!(
+            "---\ndescription: A guide long enough to split\n---\n# Guide\n\n## Setup\n\n{}\n## Setup!\n\n{}",
+            "- first\n".repeat(30),
+            "- second\n".repeat(20)
+        );
+        let sources = [dir.write(".cursor/rules/guide.mdc", long)];
+        let import = Importer::new().max_lines(20).import(&sources).unwrap();
+        let skill = &import.skills[0];
+        assert_eq!(skill.moved, ["Setup", "Setup!"]);
+        let paths: Vec<&Path> = skill.files.iter().map(|f| f.path.as_path()).collect();
+        assert_eq!(
+            paths[1..],
+            [
+                Path::new(".claude/skills/guide/references/setup.md"),
+                Path::new(".claude/skills/guide/references/setup-2.md"),
+            ]
+        );
+        assert!(skill.files[1].contents.contains("- first"));
+        assert!(skill.files[2].contents.contains("- second"));
+        let body = &skill.files[0].contents;
+        assert!(body.contains("See [references/setup.md](references/setup.md)."));
+        assert!(body.contains("See [references/setup-2.md](references/setup-2.md)."));
+    }
+
+    #[test]
+    fn exported_agents_md_is_read_back_skill_by_skill() {
+        let rules = [
+            Rule {
+                name: "project".into(),
+                body: "Use pnpm.\n".into(),
+                always: true,
+                ..Rule::default()
+            },
+            Rule {
+                name: "zod".into(),
+                description: Some("Validate input with Zod schemas".into()),
+                body: "# Zod\n".into(),
+                ..Rule::default()
+            },
+        ];
+        let dir = TempDir::new();
+        let import = import(&dir, &[("AGENTS.md", &combined::render(&rules))]);
+        assert_eq!(import.skills.len(), 1);
+        assert_eq!(
+            parsed(&import.skills[0]).description(),
+            Some("Validate input with Zod schemas")
+        );
+        assert!(import.global.unwrap().contents.contains("Use pnpm."));
+    }
+
+    #[test]
+    fn discover_lists_monolithic_files_first() {
+        let dir = TempDir::new();
+        dir.write(".cursor/rules/b.mdc", "");
+        dir.write(".cursor/rules/a.mdc", "");
+        dir.write(".cursor/rules/notes.txt", "");
+        dir.write("CLAUDE.md", "");
+        let found = Importer::discover(&Project::new(dir.path())).unwrap();
+        let relative: Vec<_> = found
+            .iter()
+            .map(|p| p.strip_prefix(dir.path()).unwrap().to_path_buf())
+            .collect();
+        assert_eq!(
+            relative,
+            [
+                PathBuf::from("CLAUDE.md"),
+                PathBuf::from(".cursor/rules/a.mdc"),
+                PathBuf::from(".cursor/rules/b.mdc"),
+            ]
+        );
+    }
+
+    #[test]
+    fn write_only_replaces_existing_skills_when_forced() {
+        let dir = TempDir::new();
+        let import = import(&dir, &[("CLAUDE.md", CLAUDE_MD)]);
+        let project = Project::new(dir.path().join("project"));
+        let written = import.write(&project, false).unwrap();
+        assert_eq!(written.len(), 3);
+        assert!(matches!(
+            import.write(&project, false),
+            Err(Error::AlreadyExists(_))
+        ));
+        dir.write("project/.claude/skills/testing/stale.md", "");
+        import.write(&project, true).unwrap();
+        assert!(!project.skill("testing").path.join("stale.md").exists());
+    }
+
+    #[test]
+    fn helpers_normalize_titles() {
+        assert_eq!(slug("Convenciones de código"), "convenciones-de-codigo");
+        assert_eq!(slug("¿?"), "imported");
+        assert_eq!(truncate("one two three", 8), "one two");
+        assert_eq!(title_case("api-testing"), "Api testing");
+    }
+}
@@ -0,0 +1,470 @@
+//! Stacked `.claude/` trees and the instructions they add up to.
+//!
+//! Skills and the global `SKILL.md` can come from three kinds of layer,
+//! from lowest to highest precedence:
+//!
+//! 1. user: `~/.claude/`, or the directory named by `SKILLS_USER_DIR`;
+//! 2. repo: `.claude/` at the project root;
+//! 3. directory: `.claude/` in each subdirectory between the root and the
+//!    directory being worked in, the nearest last.
+//!
+//! Global `SKILL.md` files are configuration, so they are merged: a `##`
+//! section replaces the one with the same title from lower layers, and
+//! `override: { disable: [<title>] }` drops inherited sections. A skill,
+//! on the other hand, replaces the same-named skill of lower layers unless
+//! its frontmatter has an [`Override`]:
+//!
+//! ```yaml
+//! name: pnpm-extension
+//! override:
+//!   disable: [Troubleshooting]
+//! ```
+//!
+//! When skill instructions and the effective global configuration
+//! disagree, the global configuration wins.
+
+use std::collections::BTreeSet;
+use std::env;
+use std::fmt;
+use std::fs;
+use std::path::{Component, Path, PathBuf};
+
+use serde::Serialize;
+
+use crate::graph::{merge, split};
+use crate::project::{skill_dirs, Project, CLAUDE_DIR, SKILLS_DIR};
+use crate::skill::{Override, Skill, SKILL_FILE};
+use crate::{Error, Result};
+
+/// Environment variable naming the user-level `.claude` directory, for when
+/// it is not `~/.claude`.
+pub const USER_DIR_ENV: &str = "SKILLS_USER_DIR";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Scope {
+    User,
+    Repo,
+    Directory,
+}
+
+impl fmt::Display for Scope {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.pad(match self {
+            Scope::User => "user",
+            Scope::Repo => "repo",
+            Scope::Directory => "directory",
+        })
+    }
+}
+
+/// One `.claude` directory.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Layer {
+    pub scope: Scope,
+    pub dir: PathBuf,
+}
+
+impl Layer {
+    pub fn global_skill_file(&self) -> PathBuf {
+        self.dir.join(SKILL_FILE)
+    }
+
+    pub fn skills_dir(&self) -> PathBuf {
+        self.dir.join(SKILLS_DIR)
+    }
+
+    fn skill_file(&self, name: &str) -> PathBuf {
+        self.skills_dir().join(name).join(SKILL_FILE)
+    }
+}
+
+/// A file that contributed to the effective instructions.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Source {
+    pub scope: Scope,
+    pub path: PathBuf,
+}
+
+/// A `##` section, or the text before the first one, and where it is from.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Part {
+    pub from: Source,
+    pub title: Option<String>,
+    pub text: String,
+}
+
+/// An inherited section an override dropped.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Disabled {
+    pub title: String,
+    pub by: Source,
+}
+
+/// The instructions left after every layer has been applied.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct Effective {
+    /// Contributing files, lowest precedence first.
+    pub files: Vec<Source>,
+    pub parts: Vec<Part>,
+    pub disabled: Vec<Disabled>,
+    /// The file whose `override: disable` removed the skill.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub removed_by: Option<Source>,
+}
+
+impl Effective {
+    /// The merged markdown.
+    pub fn text(&self) -> String {
+        let texts: Vec<&str> = self.parts.iter().map(|p| p.text.as_str()).collect();
+        texts.join("\n\n")
+    }
+
+    fn apply(&mut self, from: Source, skill: &Skill, merge_by_default: bool) {
+        let parts = split(skill).into_iter().map(|(title, text)| Part {
+            from: from.clone(),
+            title: title.map(str::to_string),
+            text: text.to_string(),
+        });
+        let overrides = skill.frontmatter().overrides.as_ref();
+        let disable = match overrides {
+            Some(Override::Merge { disable }) => disable.as_slice(),
+            _ => &[],
+        };
+        let merges = match overrides {
+            Some(Override::Merge { .. }) => true,
+            Some(Override::Disable) => false,
+            None => merge_by_default,
+        };
+        if !merges {
+            self.files.clear();
+            self.parts.clear();
+            self.disabled.clear();
+        }
+        self.removed_by = None;
+        self.files.push(from.clone());
+        merge(&mut self.parts, parts, |p| p.title.as_deref());
+        for title in disable {
+            let inherited = |p: &Part| {
+                p.from != from
+                    && p.title
+                        .as_deref()
+                        .is_some_and(|t| t.eq_ignore_ascii_case(title))
+            };
+            if self.parts.iter().any(inherited) {
+                self.parts.retain(|p| !inherited(p));
+                self.disabled.push(Disabled {
+                    title: title.clone(),
+                    by: from.clone(),
+                });
+            }
+        }
+    }
+}
+
+/// The layers that apply in one directory, lowest precedence first.
+#[derive(Debug, Clone, Default)]
+pub struct Layers {
+    layers: Vec<Layer>,
+}
+
+impl Layers {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a layer above those added so far.
+    pub fn layer(mut self, scope: Scope, dir: impl Into<PathBuf>) -> Self {
+        self.layers.push(Layer {
+            scope,
+            dir: dir.into(),
+        });
+        self
+    }
+
+    /// The user layer, the project's `.claude/` and every `.claude/` from
+    /// the project root down to `dir`, which must be inside the project.
+    pub fn discover(project: &Project, dir: &Path) -> Result<Self> {
+        let mut layers = Self::new();
+        let repo = project.claude_dir();
+        if let Some(user) = user_dir() {
+            if user.is_dir() && !same_dir(&user, &repo) {
+                layers = layers.layer(Scope::User, user);
+            }
+        }
+        layers = layers.layer(Scope::Repo, repo);
+        let root = canonical(project.root())?;
+        let target = canonical(dir)?;
+        let Ok(relative) = target.strip_prefix(&root) else {
+            return Err(Error::Config {
+                path: dir.to_path_buf(),
+                message: format!("is outside the project at {}", root.display()),
+            });
+        };
+        let mut current = PathBuf::new();
+        for component in relative.components() {
+            let Component::Normal(name) = component else {
+                continue;
+            };
+            current.push(name);
+            let claude = project.join(current.join(CLAUDE_DIR));
+            if claude.is_dir() {
+                layers = layers.layer(Scope::Directory, claude);
+            }
+        }
+        Ok(layers)
+    }
+
+    pub fn layers(&self) -> &[Layer] {
+        &self.layers
+    }
+
+    /// The merged global `SKILL.md`, if any layer has one.
+    pub fn global(&self) -> Result<Option<Effective>> {
+        self.effective(Layer::global_skill_file, true)
+    }
+
+    /// The effective `name` skill, if any layer has it.
+    pub fn skill(&self, name: &str) -> Result<Option<Effective>> {
+        self.effective(|layer| layer.skill_file(name), false)
+    }
+
+    /// Names of the skills in any layer, sorted.
+    pub fn skill_names(&self) -> Result<Vec<String>> {
+        let mut names = BTreeSet::new();
+        for layer in &self.layers {
+            for dir in skill_dirs(&layer.skills_dir())? {
+                if dir.skill_file().is_file() {
+                    names.insert(dir.name);
+                }
+            }
+        }
+        Ok(names.into_iter().collect())
+    }
+
+    fn effective(
+        &self,
+        file: impl Fn(&Layer) -> PathBuf,
+        merge_by_default: bool,
+    ) -> Result<Option<Effective>> {
+        let mut effective: Option<Effective> = None;
+        for layer in &self.layers {
+            let path = file(layer);
+            if !path.is_file() {
+                continue;
+            }
+            let skill = Skill::from_path(&path)?;
+            let from = Source {
+                scope: layer.scope,
+                path,
+            };
+            let current = effective.get_or_insert_with(Effective::default);
+            if !merge_by_default && skill.frontmatter().overrides == Some(Override::Disable) {
+                *current = Effective {
+                    removed_by: Some(from),
+                    ..Effective::default()
+                };
+                continue;
+            }
+            current.apply(from, &skill, merge_by_default);
+        }
+        Ok(effective)
+    }
+}
+
+/// `SKILLS_USER_DIR`, or `.claude` in the home directory.
+fn user_dir() -> Option<PathBuf> {
+    if let Some(dir) = env::var_os(USER_DIR_ENV).filter(|d| !d.is_empty()) {
+        return Some(PathBuf::from(dir));
+    }
+    let home = env::var_os("HOME").or_else(|| env::var_os("USERPROFILE"))?;
+    Some(PathBuf::from(home).join(CLAUDE_DIR))
+}
+
+fn canonical(path: &Path) -> Result<PathBuf> {
+    fs::canonicalize(path).map_err(|e| Error::io(path, e))
+}
+
+fn same_dir(a: &Path, b: &Path) -> bool {
+    matches!((fs::canonicalize(a), fs::canonicalize(b)), (Ok(a), Ok(b)) if a == b)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::TempDir;
+
+    fn skill(extra: &str, body: &str) -> String {
+        format!("---\ndescription: x\n{extra}---\n{body}")
+    }
+
+    /// A user and a repo layer under `dir`.
+    fn layers(dir: &TempDir) -> Layers {
+        Layers::new()
+            .layer(Scope::User, dir.path().join("user"))
+            .layer(Scope::Repo, dir.path().join("repo"))
+    }
+
+    fn titles(effective: &Effective) -> Vec<(Scope, Option<&str>)> {
+        effective
+            .parts
+            .iter()
+            .map(|p| (p.from.scope, p.title.as_deref()))
+            .collect()
+    }
+
+    #[test]
+    fn global_files_merge_by_section() {
+        let dir = TempDir::new();
+        dir.write(
+            "user/SKILL.md",
+            skill("", "Be terse.\n\n## Style\n\ntabs\n\n## Tests\n\nalways\n"),
+        );
+        dir.write(
+            "repo/SKILL.md",
+            skill("", "## style\n\nspaces\n\n## Git\n\nrebase\n"),
+        );
+        let global = layers(&dir).global().unwrap().unwrap();
+        assert_eq!(
+            titles(&global),
+            [
+                (Scope::User, None),
+                (Scope::Repo, Some("style")),
+                (Scope::User, Some("Tests")),
+                (Scope::Repo, Some("Git")),
+            ]
+        );
+        assert_eq!(
+            global.text(),
+            "Be terse.\n\n## style\n\nspaces\n\n## Tests\n\nalways\n\n## Git\n\nrebase"
+        );
+        assert_eq!(global.files.len(), 2);
+    }
+
+    #[test]
+    fn global_overrides_disable_inherited_sections_only() {
+        let dir = TempDir::new();
+        dir.write(
+            "user/SKILL.md",
+            skill("", "## Tests\n\nalways\n\n## Git\n\nmerge\n"),
+        );
+        dir.write(
+            "repo/SKILL.md",
+            skill(
+                "override: { disable: [tests, Git, Missing] }\n",
+                "## Git\n\nrebase\n",
+            ),
+        );
+        let global = layers(&dir).global().unwrap().unwrap();
+        assert_eq!(global.text(), "## Git\n\nrebase");
+        let disabled: Vec<_> = global.disabled.iter().map(|d| d.title.as_str()).collect();
+        // Its own `Git` replaced the inherited one, so only `Tests` was dropped.
+        assert_eq!(disabled, ["tests"]);
+        assert_eq!(global.disabled[0].by.scope, Scope::Repo);
+    }
+
+    #[test]
+    fn a_skill_replaces_the_one_below_unless_it_merges() {
+        let dir = TempDir::new();
+        dir.write(
+            "user/skills/pnpm/SKILL.md",
+            skill("", "## Install\n\nnpm i\n"),
+        );
+        dir.write(
+            "repo/skills/pnpm/SKILL.md",
+            skill("", "## Run\n\npnpm run\n"),
+        );
+        let pnpm = layers(&dir).skill("pnpm").unwrap().unwrap();
+        assert_eq!(titles(&pnpm), [(Scope::Repo, Some("Run"))]);
+        assert_eq!(pnpm.files.len(), 1);
+
+        dir.write(
+            "repo/skills/pnpm/SKILL.md",
+            skill("override: true\n", "## Run\n\npnpm run\n"),
+        );
+        let pnpm = layers(&dir).skill("pnpm").unwrap().unwrap();
+        assert_eq!(
+            titles(&pnpm),
+            [(Scope::User, Some("Install")), (Scope::Repo, Some("Run"))]
+        );
+        assert!(layers(&dir).skill("missing").unwrap().is_none());
+    }
+
+    #[test]
+    fn override_disable_removes_the_skill_until_a_higher_layer_adds_it() {
+        let dir = TempDir::new();
+        dir.write("user/skills/pnpm/SKILL.md", skill("", "npm i\n"));
+        dir.write(
+            "repo/skills/pnpm/SKILL.md",
+            skill("override: disable\n", "ignored\n"),
+        );
+        let pnpm = layers(&dir).skill("pnpm").unwrap().unwrap();
+        assert!(pnpm.parts.is_empty());
+        assert_eq!(pnpm.removed_by.as_ref().unwrap().scope, Scope::Repo);
+
+        dir.write("sub/skills/pnpm/SKILL.md", skill("", "pnpm i\n"));
+        let pnpm = layers(&dir)
+            .layer(Scope::Directory, dir.path().join("sub"))
+            .skill("pnpm")
+            .unwrap()
+            .unwrap();
+        assert_eq!(pnpm.removed_by, None);
+        assert_eq!(pnpm.text(), "pnpm i");
+    }
+
+    #[test]
+    fn skill_names_are_collected_from_every_layer() {
+        let dir = TempDir::new();
+        dir.write("user/skills/zod/SKILL.md", skill("", ""));
+        dir.write("repo/skills/astro/SKILL.md", skill("", ""));
+        dir.write("repo/skills/zod/SKILL.md", skill("", ""));
+        dir.write("repo/skills/empty/notes.md", "");
+        assert_eq!(layers(&dir).skill_names().unwrap(), ["astro", "zod"]);
+    }
+
+    #[test]
+    fn discover_stacks_directory_layers_from_the_root_down() {
+        let dir = TempDir::new();
+        dir.write(".claude/SKILL.md", skill("", ""));
+        dir.write("web/.claude/SKILL.md", skill("", ""));
+        dir.write("web/src/app/main.ts", "");
+        dir.write("web/src/.claude/SKILL.md", skill("", ""));
+        let project = Project::new(dir.path());
+        let layers = Layers::discover(&project, &dir.path().join("web/src/app")).unwrap();
+        let found: Vec<_> = layers
+            .layers()
+            .iter()
+            .filter(|l| l.scope != Scope::User)
+            .map(|l| {
+                (
+                    l.scope,
+                    l.dir.strip_prefix(dir.path()).unwrap().to_path_buf(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            found,
+            [
+                (Scope::Repo, PathBuf::from(".claude")),
+                (Scope::Directory, PathBuf::from("web/.claude")),
+                (Scope::Directory, PathBuf::from("web/src/.claude")),
+            ]
+        );
+    }
+
+    #[test]
+    fn discover_rejects_directories_outside_the_project() {
+        let dir = TempDir::new();
+        dir.write("project/README.md", "");
+        dir.write("elsewhere/README.md", "");
+        let project = Project::new(dir.path().join("project"));
+        assert!(matches!(
+            Layers::discover(&project, &dir.path().join("elsewhere")),
+            Err(Error::Config { .. })
+        ));
+        assert!(matches!(
+            Layers::discover(&project, &dir.path().join("project/missing")),
+            Err(Error::Io { .. })
+        ));
+    }
+}
@@ -0,0 +1,53 @@
+//! Tooling for [Agent Skills](https://agentskills.io): `SKILL.md` files made
+//! of YAML frontmatter (`name`, `description`, optional `globs`) followed by
+//! markdown instructions.
+//!
+//! ```
+//! use agent_skills::Skill;
+//!
+//! let skill = Skill::parse("---\nname: pnpm-workflow\ndescription: Use pnpm\n---\n# pnpm\n")?;
+//! assert_eq!(skill.name(), Some("pnpm-workflow"));
+//! assert_eq!(skill.sections()[0].title, "pnpm");
+//! # Ok::<(), agent_skills::ParseError>(())
+//! ```
+
+pub mod activation;
+pub mod config;
+pub mod conflicts;
+pub mod error;
+pub mod export;
+pub mod graph;
+pub mod import;
+pub mod injection;
+pub mod install;
+pub mod layers;
+pub mod lint;
+pub mod loader;
+pub mod lock;
+pub mod lsp;
+pub mod mcp;
+pub mod package;
+mod parse;
+pub mod project;
+pub mod registry;
+pub mod relevance;
+pub mod resolve;
+mod sandbox;
+pub mod scaffold;
+pub mod script;
+pub mod secrets;
+pub mod shell;
+pub mod signing;
+pub mod skill;
+pub mod span;
+#[cfg(test)]
+mod test_support;
+pub mod tokens;
+
+pub use config::Config;
+pub use error::{Error, Result};
+pub use loader::SkillLoader;
+pub use parse::{ParseError, ParseErrorKind};
+pub use project::{Project, SkillDir};
+pub use skill::{Frontmatter, Override, Section, Skill, SKILL_FILE};
+pub use span::{LineIndex, Position, Span};
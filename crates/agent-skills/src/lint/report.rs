@@ -0,0 +1,190 @@
+//! Rendering lint results for people and for CI.
+
+use std::fmt::Write as _;
+
+use serde::Serialize;
+use serde_json::{json, Value};
+
+use super::{Diagnostic, Severity, RULES};
+
+const SARIF_SCHEMA: &str = "https://json.schemastore.org/sarif-2.1.0.json";
+const INFORMATION_URI: &str = "https://github.com/carloss765/agent-skills";
+
+/// The outcome of linting a set of skills.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct Report {
+    /// Number of skill directories checked.
+    pub skills: usize,
+    pub diagnostics: Vec<Diagnostic>,
+}
+
+impl Report {
+    pub fn count(&self, severity: Severity) -> usize {
+        self.diagnostics
+            .iter()
+            .filter(|d| d.severity == severity)
+            .count()
+    }
+
+    pub fn has_errors(&self) -> bool {
+        self.count(Severity::Error) > 0
+    }
+
+    /// Sort by file, then position, so output is stable across platforms.
+    pub fn sort(&mut self) {
+        self.diagnostics.sort_by(|a, b| {
+            (&a.path, a.span.map(|s| s.start.offset), a.rule).cmp(&(
+                &b.path,
+                b.span.map(|s| s.start.offset),
+                b.rule,
+            ))
+        });
+    }
+
+    /// `path:line:col: severity[rule]: message` lines and a summary.
+    pub fn to_human(&self) -> String {
+        let mut out = String::new();
+        for d in &self.diagnostics {
+            let _ = writeln!(out, "{d}");
+        }
+        let _ = writeln!(
+            out,
+            "{} checked: {}, {}, {}",
+            plural(self.skills, "skill"),
+            plural(self.count(Severity::Error), "error"),
+            plural(self.count(Severity::Warning), "warning"),
+            plural(self.count(Severity::Info), "note"),
+        );
+        out
+    }
+
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).expect("report serializes")
+    }
+
+    /// A SARIF 2.1.0 log, the format GitHub code scanning ingests.
+    pub fn to_sarif(&self) -> String {
+        let rules: Vec<Value> = RULES
+            .iter()
+            .map(|rule| {
+                json!({
+                    "id": rule.id,
+                    "shortDescription": { "text": rule.summary },
+                    "defaultConfiguration": { "level": sarif_level(rule.severity) },
+                })
+            })
+            .collect();
+        let results: Vec<Value> = self
+            .diagnostics
+            .iter()
+            .map(|d| {
+                let mut location = json!({
+                    "artifactLocation": {
+                        "uri": d.path.to_string_lossy().replace('\\', "/"),
+                    },
+                });
+                if let Some(span) = d.span {
+                    location["region"] = json!({
+                        "startLine": span.start.line,
+                        "startColumn": span.start.column,
+                        "endLine": span.end.line,
+                        "endColumn": span.end.column,
+                    });
+                }
+                json!({
+                    "ruleId": d.rule,
+                    "level": sarif_level(d.severity),
+                    "message": { "text": d.message },
+                    "locations": [{ "physicalLocation": location }],
+                })
+            })
+            .collect();
+        let log = json!({
+            "$schema": SARIF_SCHEMA,
+            "version": "2.1.0",
+            "runs": [{
+                "tool": {
+                    "driver": {
+                        "name": "skills",
+                        "version": env!("CARGO_PKG_VERSION"),
+                        "informationUri": INFORMATION_URI,
+                        "rules": rules,
+                    },
+                },
+                "results": results,
+            }],
+        });
+        serde_json::to_string_pretty(&log).expect("SARIF log serializes")
+    }
+}
+
+fn sarif_level(severity: Severity) -> &'static str {
+    match severity {
+        Severity::Error => "error",
+        Severity::Warning => "warning",
+        Severity::Info => "note",
+    }
+}
+
+fn plural(n: usize, noun: &str) -> String {
+    match n {
+        1 => format!("1 {noun}"),
+        n => format!("{n} {noun}s"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::lint::rules::{EXAMPLES, NAME_KEBAB_CASE};
+    use crate::span::LineIndex;
+
+    fn report() -> Report {
+        let span = LineIndex::new("---\nname: Bad\n").span(4..13);
+        Report {
+            skills: 1,
+            diagnostics: vec![
+                EXAMPLES.diagnostic("b/SKILL.md", None, "no examples"),
+                NAME_KEBAB_CASE.diagnostic("a/SKILL.md", Some(span), "not kebab-case"),
+            ],
+        }
+    }
+
+    #[test]
+    fn human_output_lists_diagnostics_and_a_summary() {
+        let mut report = report();
+        report.sort();
+        assert_eq!(
+            report.to_human(),
+            "a/SKILL.md:2:1: error[name-kebab-case]: not kebab-case\n\
+             b/SKILL.md: info[examples]: no examples\n\
+             1 skill checked: 1 error, 0 warnings, 1 note\n"
+        );
+        assert!(report.has_errors());
+    }
+
+    #[test]
+    fn sarif_output_carries_rules_and_regions() {
+        let log: Value = serde_json::from_str(&report().to_sarif()).unwrap();
+        let run = &log["runs"][0];
+        assert_eq!(
+            run["tool"]["driver"]["rules"].as_array().unwrap().len(),
+            RULES.len()
+        );
+        let results = run["results"].as_array().unwrap();
+        assert_eq!(results[0]["level"], "note");
+        assert!(results[0]["locations"][0]["physicalLocation"]
+            .get("region")
+            .is_none());
+        let region = &results[1]["locations"][0]["physicalLocation"]["region"];
+        assert_eq!(region["startLine"], 2);
+        assert_eq!(region["endColumn"], 10);
+    }
+
+    #[test]
+    fn json_output_uses_lowercase_severities() {
+        let json: Value = serde_json::from_str(&report().to_json()).unwrap();
+        assert_eq!(json["skills"], 1);
+        assert_eq!(json["diagnostics"][1]["severity"], "error");
+    }
+}
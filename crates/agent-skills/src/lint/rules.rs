@@ -0,0 +1,709 @@
+//! The checklist rules themselves.
+
+use std::path::{Path, PathBuf};
+
+use std::fs;
+
+use serde_yaml::Value;
+
+use crate::activation::Patterns;
+use crate::config::Budgets;
+use crate::injection;
+use crate::parse::markdown_lines;
+use crate::project::{SkillDir, REFERENCES_DIR, SCRIPTS_DIR};
+use crate::shell;
+use crate::skill::{is_kebab_case, Skill};
+use crate::span::{LineIndex, Span};
+use crate::tokens::TokenCounter;
+
+use super::{Diagnostic, Severity};
+
+pub(super) const DEFAULT_MAX_LINES: usize = 500;
+
+/// Descriptions shorter than this rarely tell an agent when to load a skill.
+const MIN_DESCRIPTION_CHARS: usize = 20;
+/// Upper bound from the Agent Skills specification.
+const MAX_DESCRIPTION_CHARS: usize = 1024;
+
+/// Headings accepted for the "When to Use This Skill" section. Skills here
+/// are written in English or Spanish.
+const WHEN_TO_USE_TITLES: &[&str] = &["when to use", "cuándo usar", "cuando usar"];
+const INSTRUCTIONS_TITLES: &[&str] = &["instructions", "instrucciones"];
+
+/// Metadata for a lint rule.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Rule {
+    pub id: &'static str,
+    pub severity: Severity,
+    pub summary: &'static str,
+}
+
+impl Rule {
+    pub(crate) fn diagnostic(
+        &self,
+        path: impl Into<PathBuf>,
+        span: Option<Span>,
+        message: impl Into<String>,
+    ) -> Diagnostic {
+        Diagnostic {
+            rule: self.id,
+            severity: self.severity,
+            message: message.into(),
+            path: path.into(),
+            span,
+        }
+    }
+}
+
+pub const PARSE_ERROR: Rule = Rule {
+    id: "parse-error",
+    severity: Severity::Error,
+    summary: "SKILL.md must start with valid YAML frontmatter",
+};
+pub const MISSING_SKILL_FILE: Rule = Rule {
+    id: "missing-skill-file",
+    severity: Severity::Error,
+    summary: "Every skill directory contains a SKILL.md",
+};
+pub const INVALID_GLOB: Rule = Rule {
+    id: "invalid-glob",
+    severity: Severity::Error,
+    summary: "Every `globs` pattern is a valid glob",
+};
+pub const INVALID_VERSION: Rule = Rule {
+    id: "invalid-version",
+    severity: Severity::Error,
+    summary: "A `version` is a semver version like `1.2.0`",
+};
+pub const INVALID_REQUIRES: Rule = Rule {
+    id: "invalid-requires",
+    severity: Severity::Error,
+    summary: "`requires` maps skill names to semver ranges",
+};
+pub const NAME_MISSING: Rule = Rule {
+    id: "name-missing",
+    severity: Severity::Error,
+    summary: "Frontmatter declares a `name`",
+};
+pub const NAME_KEBAB_CASE: Rule = Rule {
+    id: "name-kebab-case",
+    severity: Severity::Error,
+    summary: "Skill names are kebab-case",
+};
+pub const NAME_MATCHES_DIRECTORY: Rule = Rule {
+    id: "name-matches-directory",
+    severity: Severity::Warning,
+    summary: "The skill `name` matches its directory name",
+};
+pub const DESCRIPTION_MISSING: Rule = Rule {
+    id: "description-missing",
+    severity: Severity::Error,
+    summary: "Frontmatter declares a `description`",
+};
+pub const DESCRIPTION_LENGTH: Rule = Rule {
+    id: "description-length",
+    severity: Severity::Warning,
+    summary: "Description is specific enough to decide when to load the skill",
+};
+pub const MAX_LINES: Rule = Rule {
+    id: "max-lines",
+    severity: Severity::Error,
+    summary: "SKILL.md is under 500 lines",
+};
+pub const WHEN_TO_USE_SECTION: Rule = Rule {
+    id: "when-to-use-section",
+    severity: Severity::Error,
+    summary: "Includes a \"When to Use This Skill\" section",
+};
+pub const NUMBERED_INSTRUCTIONS: Rule = Rule {
+    id: "numbered-instructions",
+    severity: Severity::Warning,
+    summary: "Instructions are actionable and numbered",
+};
+pub const EXAMPLES: Rule = Rule {
+    id: "examples",
+    severity: Severity::Info,
+    summary: "Includes examples when relevant",
+};
+pub const SCRIPT_EXECUTABLE: Rule = Rule {
+    id: "script-executable",
+    severity: Severity::Warning,
+    summary: "Scripts in scripts/ are executable",
+};
+pub const SHELL_RECURSIVE_REMOVE: Rule = Rule {
+    id: "shell-rm-variable",
+    severity: Severity::Error,
+    summary: "Scripts do not `rm -r` paths built from variables that may be empty",
+};
+pub const SHELL_PIPE_TO_SHELL: Rule = Rule {
+    id: "shell-pipe-to-shell",
+    severity: Severity::Error,
+    summary: "Scripts do not pipe downloads into an interpreter",
+};
+pub const SHELL_PRIVILEGE: Rule = Rule {
+    id: "shell-sudo",
+    severity: Severity::Warning,
+    summary: "Scripts do not use sudo or other privilege escalation",
+};
+pub const SHELL_WRITE_OUTSIDE: Rule = Rule {
+    id: "shell-write-outside-project",
+    severity: Severity::Warning,
+    summary: "Scripts only write inside the project and the temp directory",
+};
+pub const SHELL_NETWORK: Rule = Rule {
+    id: "shell-network",
+    severity: Severity::Info,
+    summary: "Scripts that use the network say so",
+};
+pub const SHELL_EVAL: Rule = Rule {
+    id: "shell-eval",
+    severity: Severity::Warning,
+    summary: "Scripts do not `eval` expanded text",
+};
+pub const HIDDEN_MARKUP: Rule = Rule {
+    id: "hidden-markup",
+    severity: Severity::Warning,
+    summary: "Skill text does not hide content in HTML comments or hidden elements",
+};
+pub const INVISIBLE_CHARACTERS: Rule = Rule {
+    id: "invisible-characters",
+    severity: Severity::Error,
+    summary: "Skill text has no zero-width, filler or Unicode tag characters",
+};
+pub const BIDI_OVERRIDE: Rule = Rule {
+    id: "bidi-override",
+    severity: Severity::Error,
+    summary: "Skill text has no bidirectional override characters",
+};
+pub const ENCODED_PAYLOAD: Rule = Rule {
+    id: "encoded-payload",
+    severity: Severity::Warning,
+    summary: "Skill text does not embed base64-encoded text",
+};
+pub const PROMPT_INJECTION: Rule = Rule {
+    id: "prompt-injection",
+    severity: Severity::Error,
+    summary: "Skill text does not tell the agent to ignore its instructions or deceive the user",
+};
+pub const UNKNOWN_RULE: Rule = Rule {
+    id: "unknown-rule",
+    severity: Severity::Warning,
+    summary: "Rules named in `lint.allow` exist",
+};
+pub const PROTECTED_RULE: Rule = Rule {
+    id: "protected-rule",
+    severity: Severity::Warning,
+    summary: "`lint.allow` in a skill does not name security rules",
+};
+pub const METADATA_BUDGET: Rule = Rule {
+    id: "metadata-budget",
+    severity: Severity::Error,
+    summary: "Level 1 metadata fits the per-skill token budget",
+};
+pub const INSTRUCTIONS_BUDGET: Rule = Rule {
+    id: "instructions-budget",
+    severity: Severity::Error,
+    summary: "Level 2 instructions fit the per-skill token budget",
+};
+pub const REFERENCE_BUDGET: Rule = Rule {
+    id: "reference-budget",
+    severity: Severity::Error,
+    summary: "Each Level 3 reference fits the per-file token budget",
+};
+pub const STARTUP_BUDGET: Rule = Rule {
+    id: "startup-budget",
+    severity: Severity::Error,
+    summary: "Level 1 metadata of all skills fits the startup token budget",
+};
+pub const DUPLICATE_NAME: Rule = Rule {
+    id: "duplicate-name",
+    severity: Severity::Error,
+    summary: "No two skills share a `name`",
+};
+pub const UNMET_REQUIRES: Rule = Rule {
+    id: "unmet-requires",
+    severity: Severity::Error,
+    summary: "Installed skills satisfy every `requires` range",
+};
+pub const UNKNOWN_DEPENDENCY: Rule = Rule {
+    id: "unknown-dependency",
+    severity: Severity::Error,
+    summary: "`depends_on` and `extends` name installed skills",
+};
+pub const DEPENDENCY_CYCLE: Rule = Rule {
+    id: "dependency-cycle",
+    severity: Severity::Error,
+    summary: "Skills do not depend on or extend each other in a cycle",
+};
+
+/// Every rule, in the order they are checked.
+pub const RULES: &[Rule] = &[
+    PARSE_ERROR,
+    MISSING_SKILL_FILE,
+    NAME_MISSING,
+    NAME_KEBAB_CASE,
+    NAME_MATCHES_DIRECTORY,
+    DESCRIPTION_MISSING,
+    DESCRIPTION_LENGTH,
+    INVALID_GLOB,
+    INVALID_VERSION,
+    INVALID_REQUIRES,
+    MAX_LINES,
+    WHEN_TO_USE_SECTION,
+    NUMBERED_INSTRUCTIONS,
+    EXAMPLES,
+    SCRIPT_EXECUTABLE,
+    SHELL_RECURSIVE_REMOVE,
+    SHELL_PIPE_TO_SHELL,
+    SHELL_PRIVILEGE,
+    SHELL_WRITE_OUTSIDE,
+    SHELL_NETWORK,
+    SHELL_EVAL,
+    HIDDEN_MARKUP,
+    INVISIBLE_CHARACTERS,
+    BIDI_OVERRIDE,
+    ENCODED_PAYLOAD,
+    PROMPT_INJECTION,
+    UNKNOWN_RULE,
+    PROTECTED_RULE,
+    METADATA_BUDGET,
+    INSTRUCTIONS_BUDGET,
+    REFERENCE_BUDGET,
+    STARTUP_BUDGET,
+    DUPLICATE_NAME,
+    UNMET_REQUIRES,
+    UNKNOWN_DEPENDENCY,
+    DEPENDENCY_CYCLE,
+];
+
+/// Rules that catch a skill hiding text from review or instructing the
+/// agent behind the user's back. Whoever writes such a skill would also fill
+/// in its `lint.allow`, so only the project's `skills.toml` can allow them.
+pub(super) const SECURITY_RULES: &[Rule] = &[
+    HIDDEN_MARKUP,
+    INVISIBLE_CHARACTERS,
+    BIDI_OVERRIDE,
+    ENCODED_PAYLOAD,
+    PROMPT_INJECTION,
+];
+
+pub(super) struct Context<'a> {
+    pub dir: &'a SkillDir,
+    pub path: &'a Path,
+    pub skill: &'a Skill,
+    pub max_lines: usize,
+    pub budgets: &'a Budgets,
+    pub counter: &'a TokenCounter,
+}
+
+impl Context<'_> {
+    fn report(&self, rule: &Rule, span: Option<Span>, message: String) -> Diagnostic {
+        rule.diagnostic(self.path, span, message)
+    }
+
+    /// Span of a frontmatter key, or of the whole frontmatter when absent.
+    fn key_span(&self, key: &str) -> Span {
+        self.skill
+            .frontmatter()
+            .span_of(key)
+            .unwrap_or_else(|| self.skill.frontmatter_span())
+    }
+}
+
+pub(super) fn check(cx: &Context, out: &mut Vec<Diagnostic>) {
+    // An `override` patches a skill from another layer, which has the
+    // description and sections the patch leaves out.
+    let patch = cx.skill.frontmatter().overrides.is_some();
+    name(cx, out);
+    if !patch {
+        description(cx, out);
+    }
+    globs(cx, out);
+    version(cx, out);
+    requires(cx, out);
+    max_lines(cx, out);
+    if !patch {
+        when_to_use(cx, out);
+    }
+    numbered_instructions(cx, out);
+    examples(cx, out);
+    scripts(cx, out);
+    shell_scripts(cx, out);
+    hidden_text(cx.path, cx.skill.source(), out);
+    hidden_references(cx.dir, out);
+    budgets(cx, out);
+}
+
+/// Drop diagnostics of the rules a skill allows in its frontmatter:
+///
+/// ```yaml
+/// lint:
+///   allow: [shell-network, examples]
+/// ```
+///
+/// [`SECURITY_RULES`] are reported rather than allowed.
+pub(super) fn suppress(cx: &Context, out: &mut Vec<Diagnostic>) {
+    let Some(allow) = cx
+        .skill
+        .frontmatter()
+        .get("lint")
+        .and_then(|lint| lint.get("allow"))
+    else {
+        return;
+    };
+    let entries: Vec<Option<&str>> = match allow {
+        Value::Sequence(items) => items.iter().map(Value::as_str).collect(),
+        other => vec![other.as_str()],
+    };
+    let mut allowed = Vec::new();
+    for entry in entries {
+        match entry {
+            Some(id) if SECURITY_RULES.iter().any(|r| r.id == id) => out.push(cx.report(
+                &PROTECTED_RULE,
+                Some(cx.key_span("lint")),
+                format!(
+                    "`lint.allow` cannot silence security rule `{id}`; allow it in skills.toml instead"
+                ),
+            )),
+            Some(id) if RULES.iter().any(|r| r.id == id) => allowed.push(id),
+            Some(id) => out.push(cx.report(
+                &UNKNOWN_RULE,
+                Some(cx.key_span("lint")),
+                format!("`lint.allow` names unknown rule `{id}`"),
+            )),
+            None => out.push(cx.report(
+                &UNKNOWN_RULE,
+                Some(cx.key_span("lint")),
+                "`lint.allow` entries must be rule ids".into(),
+            )),
+        }
+    }
+    out.retain(|d| !allowed.contains(&d.rule));
+}
+
+fn name(cx: &Context, out: &mut Vec<Diagnostic>) {
+    let Some(name) = cx.skill.name() else {
+        out.push(cx.report(
+            &NAME_MISSING,
+            Some(cx.skill.frontmatter_span()),
+            "frontmatter has no `name`".into(),
+        ));
+        return;
+    };
+    let span = Some(cx.key_span("name"));
+    if !is_kebab_case(name) {
+        out.push(cx.report(
+            &NAME_KEBAB_CASE,
+            span,
+            format!(
+                "name `{name}` is not kebab-case (lowercase letters, digits and single hyphens)"
+            ),
+        ));
+    }
+    if name != cx.dir.name {
+        out.push(cx.report(
+            &NAME_MATCHES_DIRECTORY,
+            span,
+            format!("name `{name}` does not match directory `{}`", cx.dir.name),
+        ));
+    }
+}
+
+fn description(cx: &Context, out: &mut Vec<Diagnostic>) {
+    let Some(description) = cx.skill.description() else {
+        out.push(
+            cx.report(
+                &DESCRIPTION_MISSING,
+                Some(cx.skill.frontmatter_span()),
+                "frontmatter has no `description`; agents use it to decide when to load the skill"
+                    .into(),
+            ),
+        );
+        return;
+    };
+    let chars = description.trim().chars().count();
+    let message = if chars < MIN_DESCRIPTION_CHARS {
+        format!("description is {chars} characters; say what the skill does and when to use it")
+    } else if chars > MAX_DESCRIPTION_CHARS {
+        format!("description is {chars} characters, over the {MAX_DESCRIPTION_CHARS} allowed")
+    } else {
+        return;
+    };
+    out.push(cx.report(
+        &DESCRIPTION_LENGTH,
+        Some(cx.key_span("description")),
+        message,
+    ));
+}
+
+fn globs(cx: &Context, out: &mut Vec<Diagnostic>) {
+    if let Err(e) = Patterns::new(&cx.skill.frontmatter().globs) {
+        out.push(cx.report(&INVALID_GLOB, Some(cx.key_span("globs")), e.to_string()));
+    }
+}
+
+fn version(cx: &Context, out: &mut Vec<Diagnostic>) {
+    let Some(version) = &cx.skill.frontmatter().version else {
+        return;
+    };
+    if let Err(e) = semver::Version::parse(version) {
+        out.push(cx.report(
+            &INVALID_VERSION,
+            Some(cx.key_span("version")),
+            format!("version `{version}` is not semver: {e}"),
+        ));
+    }
+}
+
+fn requires(cx: &Context, out: &mut Vec<Diagnostic>) {
+    for (name, range) in &cx.skill.frontmatter().requires {
+        let message = if !is_kebab_case(name) {
+            format!("`requires` names `{name}`, which is not a skill name")
+        } else if Some(name.as_str()) == cx.skill.name() {
+            format!("skill `{name}` requires itself")
+        } else if let Err(e) = semver::VersionReq::parse(range) {
+            format!("`{range}` required of `{name}` is not a semver range: {e}")
+        } else {
+            continue;
+        };
+        out.push(cx.report(&INVALID_REQUIRES, Some(cx.key_span("requires")), message));
+    }
+}
+
+fn max_lines(cx: &Context, out: &mut Vec<Diagnostic>) {
+    let lines = cx.skill.line_count();
+    if lines <= cx.max_lines {
+        return;
+    }
+    let index = LineIndex::new(cx.skill.source());
+    let start = index.line_start(cx.max_lines + 1);
+    out.push(cx.report(
+        &MAX_LINES,
+        Some(index.span(start..start)),
+        format!(
+            "SKILL.md has {lines} lines, over the limit of {}; move long content to references/",
+            cx.max_lines
+        ),
+    ));
+}
+
+fn title_matches(title: &str, prefixes: &[&str]) -> bool {
+    let title = title.to_lowercase();
+    prefixes.iter().any(|prefix| title.starts_with(prefix))
+}
+
+/// Whether a heading introduces the "When to Use This Skill" section.
+pub(crate) fn is_when_to_use(title: &str) -> bool {
+    title_matches(title, WHEN_TO_USE_TITLES)
+}
+
+fn when_to_use(cx: &Context, out: &mut Vec<Diagnostic>) {
+    let found = cx.skill.sections().iter().any(|s| is_when_to_use(&s.title));
+    if !found {
+        out.push(cx.report(
+            &WHEN_TO_USE_SECTION,
+            Some(body_start(cx.skill)),
+            "missing a \"## When to Use This Skill\" section".into(),
+        ));
+    }
+}
+
+fn body_start(skill: &Skill) -> Span {
+    let start = skill.body_span().start;
+    Span { start, end: start }
+}
+
+/// `1. step` or `1) step`, returning the step text.
+fn ordered_item(line: &str) -> Option<&str> {
+    let line = line.trim_start();
+    let digits = line.len() - line.trim_start_matches(|c: char| c.is_ascii_digit()).len();
+    if digits == 0 || digits > 9 {
+        return None;
+    }
+    let rest = line[digits..].strip_prefix(['.', ')'])?;
+    rest.starts_with([' ', '\t']).then(|| rest.trim())
+}
+
+/// Template text such as `[Condición 1]` or `[ACCIÓN]` left in place.
+fn is_placeholder(step: &str) -> bool {
+    let step = step.trim_matches('*').trim();
+    step.is_empty() || (step.starts_with('[') && step.ends_with(']') && !step.contains("]("))
+}
+
+fn numbered_instructions(cx: &Context, out: &mut Vec<Diagnostic>) {
+    let skill = cx.skill;
+    let section = skill
+        .sections()
+        .iter()
+        .find(|s| title_matches(&s.title, INSTRUCTIONS_TITLES));
+    let (range, anchor) = match section {
+        Some(section) => (section.content.range(), section.heading),
+        None => (skill.body_span().range(), body_start(skill)),
+    };
+    let index = LineIndex::new(skill.source());
+    let mut steps = 0;
+    for (line, code) in markdown_lines(skill.source(), range) {
+        let Some(step) = ordered_item(line.text).filter(|_| !code) else {
+            continue;
+        };
+        steps += 1;
+        if is_placeholder(step) {
+            let start = line.start + (line.text.len() - line.text.trim_start().len());
+            out.push(cx.report(
+                &NUMBERED_INSTRUCTIONS,
+                Some(index.span(start..line.start + line.text.len())),
+                format!("step `{step}` is a template placeholder, not an instruction"),
+            ));
+        }
+    }
+    if steps == 0 {
+        let message = match section {
+            Some(section) => format!("section \"{}\" has no numbered steps", section.title),
+            None => {
+                "no numbered instructions; add an \"## Instructions\" section with steps".into()
+            }
+        };
+        out.push(cx.report(&NUMBERED_INSTRUCTIONS, Some(anchor), message));
+    }
+}
+
+fn examples(cx: &Context, out: &mut Vec<Diagnostic>) {
+    let skill = cx.skill;
+    let has_code = markdown_lines(skill.source(), skill.body_span().range()).any(|(_, code)| code);
+    if has_code || cx.dir.references_dir().is_dir() {
+        return;
+    }
+    out.push(cx.report(
+        &EXAMPLES,
+        Some(body_start(skill)),
+        "no examples; add a fenced code block or a references/ file".into(),
+    ));
+}
+
+fn scripts(cx: &Context, out: &mut Vec<Diagnostic>) {
+    let Ok(files) = cx.dir.files_in(SCRIPTS_DIR) else {
+        return;
+    };
+    for file in files {
+        if !is_executable(&file) {
+            out.push(SCRIPT_EXECUTABLE.diagnostic(
+                &file,
+                None,
+                format!(
+                    "script is not executable; run `chmod +x {}`",
+                    file.display()
+                ),
+            ));
+        }
+    }
+}
+
+fn shell_scripts(cx: &Context, out: &mut Vec<Diagnostic>) {
+    let Ok(files) = cx.dir.files_in(SCRIPTS_DIR) else {
+        return;
+    };
+    for file in files {
+        let Ok(source) = fs::read_to_string(&file) else {
+            continue;
+        };
+        if !shell::is_shell_script(&file, &source) {
+            continue;
+        }
+        let index = LineIndex::new(&source);
+        for finding in shell::analyze(&source) {
+            let rule = match finding.concern {
+                shell::Concern::RecursiveRemove => &SHELL_RECURSIVE_REMOVE,
+                shell::Concern::PipeToShell => &SHELL_PIPE_TO_SHELL,
+                shell::Concern::Privilege => &SHELL_PRIVILEGE,
+                shell::Concern::WriteOutsideProject => &SHELL_WRITE_OUTSIDE,
+                shell::Concern::Network => &SHELL_NETWORK,
+                shell::Concern::Eval => &SHELL_EVAL,
+            };
+            out.push(rule.diagnostic(&file, Some(index.span(finding.range)), finding.message));
+        }
+    }
+}
+
+pub(super) fn hidden_text(path: &Path, source: &str, out: &mut Vec<Diagnostic>) {
+    let index = LineIndex::new(source);
+    for finding in injection::scan(source) {
+        let rule = match finding.concern {
+            injection::Concern::HiddenMarkup => &HIDDEN_MARKUP,
+            injection::Concern::InvisibleCharacters => &INVISIBLE_CHARACTERS,
+            injection::Concern::BidiOverride => &BIDI_OVERRIDE,
+            injection::Concern::EncodedPayload => &ENCODED_PAYLOAD,
+            injection::Concern::Instruction => &PROMPT_INJECTION,
+        };
+        out.push(rule.diagnostic(path, Some(index.span(finding.range)), finding.message));
+    }
+}
+
+/// References are read into context on demand, so they are held to the
+/// same standard as `SKILL.md`. Binary files cannot hide text from review.
+pub(super) fn hidden_references(dir: &SkillDir, out: &mut Vec<Diagnostic>) {
+    let Ok(files) = dir.files_in(REFERENCES_DIR) else {
+        return;
+    };
+    for file in files {
+        if let Ok(source) = fs::read_to_string(&file) {
+            hidden_text(&file, &source, out);
+        }
+    }
+}
+
+fn budgets(cx: &Context, out: &mut Vec<Diagnostic>) {
+    let Budgets {
+        metadata,
+        instructions,
+        reference,
+        ..
+    } = *cx.budgets;
+    let tokenizer = cx.counter.tokenizer().name();
+    if metadata > 0 {
+        let tokens = cx.counter.metadata(cx.skill);
+        if tokens > metadata {
+            out.push(cx.report(
+                &METADATA_BUDGET,
+                Some(cx.key_span("description")),
+                format!("Level 1 metadata costs {tokens} tokens ({tokenizer}), over the budget of {metadata}"),
+            ));
+        }
+    }
+    if instructions > 0 {
+        let tokens = cx.counter.instructions(cx.skill);
+        if tokens > instructions {
+            out.push(cx.report(
+                &INSTRUCTIONS_BUDGET,
+                Some(body_start(cx.skill)),
+                format!("SKILL.md costs {tokens} tokens ({tokenizer}), over the Level 2 budget of {instructions}; move content to references/"),
+            ));
+        }
+    }
+    if reference > 0 {
+        for path in cx.dir.files_in(REFERENCES_DIR).unwrap_or_default() {
+            let Ok(bytes) = fs::read(&path) else {
+                continue;
+            };
+            let tokens = cx.counter.count(&String::from_utf8_lossy(&bytes));
+            if tokens > reference {
+                out.push(REFERENCE_BUDGET.diagnostic(
+                    &path,
+                    None,
+                    format!("reference costs {tokens} tokens ({tokenizer}), over the Level 3 budget of {reference}"),
+                ));
+            }
+        }
+    }
+}
+
+#[cfg(unix)]
+pub(crate) fn is_executable(path: &Path) -> bool {
+    use std::os::unix::fs::PermissionsExt;
+    path.metadata()
+        .is_ok_and(|m| m.permissions().mode() & 0o111 != 0)
+}
+
+#[cfg(not(unix))]
+pub(crate) fn is_executable(_path: &Path) -> bool {
+    true
+}
@@ -0,0 +1,553 @@
+//! Progressive-disclosure runtime for agent hosts.
+//!
+//! A [`SkillLoader`] indexes a skills directory once and then serves the
+//! three levels on request, keeping a [`Ledger`] of everything it has handed
+//! to the agent so the host knows what its context window holds.
+//!
+//! ```no_run
+//! use agent_skills::{Project, SkillLoader};
+//!
+//! let mut loader = SkillLoader::from_project(&Project::new("."))?;
+//! let catalog = loader.metadata_catalog();
+//! let instructions = loader.load_instructions(&catalog[0].name)?;
+//! let examples = loader.load_reference(&catalog[0].name, "references/examples.md")?;
+//! println!("{} tokens in context", loader.ledger().total());
+//! # Ok::<(), agent_skills::Error>(())
+//! ```
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Component, Path, PathBuf};
+use std::sync::Arc;
+use std::time::Duration;
+
+use serde::Serialize;
+
+use crate::graph::{self, Graph};
+use crate::project::{skill_dirs, Project, SkillDir, SCRIPTS_DIR};
+use crate::script::{Runner, ScriptOutput};
+use crate::skill::Skill;
+use crate::tokens::{self, TokenCounter, Tokenizer};
+use crate::{Error, Result};
+
+/// Progressive-disclosure level of a piece of context.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Level {
+    /// `name` and `description`, always loaded.
+    Metadata = 1,
+    /// The full `SKILL.md`, loaded when relevant.
+    Instructions = 2,
+    /// References and script output, loaded on demand.
+    Resources = 3,
+}
+
+/// Level 1 entry for one skill.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct SkillMetadata {
+    pub name: String,
+    pub description: String,
+    pub globs: Vec<String>,
+    /// The skill directory.
+    pub path: PathBuf,
+    /// Tokens of [`Skill::metadata`].
+    pub tokens: usize,
+}
+
+/// Level 2 text of one skill, with its bases merged in.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Instructions {
+    pub skill: String,
+    /// Markdown, without frontmatter.
+    pub text: String,
+}
+
+/// One item pulled into context.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct LoadEvent {
+    pub level: Level,
+    pub skill: String,
+    /// Reference or script path relative to the skill directory.
+    pub item: Option<PathBuf>,
+    pub tokens: usize,
+}
+
+/// Everything the loader has handed out since it was created or last
+/// [reset](SkillLoader::reset).
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct Ledger {
+    pub events: Vec<LoadEvent>,
+}
+
+impl Ledger {
+    pub fn tokens(&self, level: Level) -> usize {
+        self.events
+            .iter()
+            .filter(|e| e.level == level)
+            .map(|e| e.tokens)
+            .sum()
+    }
+
+    pub fn total(&self) -> usize {
+        self.events.iter().map(|e| e.tokens).sum()
+    }
+
+    fn contains(&self, level: Level, skill: &str, item: Option<&Path>) -> bool {
+        self.events
+            .iter()
+            .any(|e| e.level == level && e.skill == skill && e.item.as_deref() == item)
+    }
+}
+
+struct Entry {
+    dir: SkillDir,
+    metadata: SkillMetadata,
+    /// Level 1 text, see [`Skill::metadata`].
+    summary: String,
+}
+
+/// Serves skills level by level with caching and context accounting.
+pub struct SkillLoader {
+    skills_dir: PathBuf,
+    counter: TokenCounter,
+    runner: Runner,
+    entries: BTreeMap<String, Entry>,
+    graph: Graph,
+    invalid: Vec<Error>,
+    cache: BTreeMap<PathBuf, Arc<str>>,
+    ledger: Ledger,
+    catalog_recorded: bool,
+}
+
+impl SkillLoader {
+    /// Index every `<skills_dir>/*/SKILL.md`. Skills that fail to parse are
+    /// left out of the catalog and reported by [`SkillLoader::invalid`].
+    pub fn open(skills_dir: impl Into<PathBuf>) -> Result<Self> {
+        let counter = TokenCounter::new(tokens::tokenizer(None)?);
+        let mut loader = Self {
+            skills_dir: skills_dir.into(),
+            runner: Runner::new(counter.clone()),
+            counter,
+            entries: BTreeMap::new(),
+            graph: Graph::new(),
+            invalid: Vec::new(),
+            cache: BTreeMap::new(),
+            ledger: Ledger::default(),
+            catalog_recorded: false,
+        };
+        loader.reindex()?;
+        Ok(loader)
+    }
+
+    /// Index a project's `.claude/skills`, running scripts from the project
+    /// root with the limits of its `skills.toml`.
+    pub fn from_project(project: &Project) -> Result<Self> {
+        let config = project.config()?;
+        let tokenizer = tokens::tokenizer(config.tokens.tokenizer.as_deref())?;
+        let loader = Self::open(project.skills_dir())?.tokenizer(tokenizer);
+        let runner = loader
+            .runner
+            .clone()
+            .config(&config.scripts)
+            .working_dir(project.root());
+        Ok(loader.runner(runner))
+    }
+
+    /// Count tokens with `tokenizer` instead of the default.
+    pub fn tokenizer(mut self, tokenizer: Arc<dyn Tokenizer>) -> Self {
+        self.counter = TokenCounter::new(tokenizer);
+        self.runner = self.runner.counter(self.counter.clone());
+        for entry in self.entries.values_mut() {
+            entry.metadata.tokens = self.counter.count(&entry.summary);
+        }
+        self
+    }
+
+    /// Working directory for [`SkillLoader::run_script`].
+    pub fn working_dir(mut self, dir: impl Into<PathBuf>) -> Self {
+        self.runner = self.runner.working_dir(dir);
+        self
+    }
+
+    /// Time limit for a single script run.
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.runner = self.runner.timeout(timeout);
+        self
+    }
+
+    /// Run scripts with `runner`, replacing the limits set so far. Its token
+    /// counter is replaced by the loader's.
+    pub fn runner(mut self, runner: Runner) -> Self {
+        self.runner = runner.counter(self.counter.clone());
+        self
+    }
+
+    /// Re-read the skills directory and drop every cached file. The ledger is
+    /// kept, since reindexing does not change what the agent has already seen.
+    pub fn reindex(&mut self) -> Result<()> {
+        self.entries.clear();
+        self.graph = Graph::new();
+        self.invalid.clear();
+        self.cache.clear();
+        for dir in skill_dirs(&self.skills_dir)? {
+            let skill = match Skill::from_path(dir.skill_file()) {
+                Ok(skill) => skill,
+                Err(e) => {
+                    self.invalid.push(e);
+                    continue;
+                }
+            };
+            let name = dir.skill_name(&skill).to_string();
+            // Keep the first, so which skill a name loads does not change
+            // with each reindex, and report the second.
+            if let Some(first) = self.entries.get(&name) {
+                self.invalid.push(Error::DuplicateSkill {
+                    name,
+                    first: first.dir.path.clone(),
+                    second: dir.path,
+                });
+                continue;
+            }
+            self.graph = std::mem::take(&mut self.graph).skill(&name, skill.frontmatter());
+            let metadata = SkillMetadata {
+                name: name.clone(),
+                description: skill.description().unwrap_or_default().trim().to_string(),
+                globs: skill.frontmatter().globs.clone(),
+                path: dir.path.clone(),
+                tokens: self.counter.metadata(&skill),
+            };
+            self.entries.insert(
+                name,
+                Entry {
+                    dir,
+                    metadata,
+                    summary: skill.metadata(),
+                },
+            );
+        }
+        Ok(())
+    }
+
+    /// Skills left out of the index because their `SKILL.md` did not parse.
+    pub fn invalid(&self) -> &[Error] {
+        &self.invalid
+    }
+
+    pub fn skills_dir(&self) -> &Path {
+        &self.skills_dir
+    }
+
+    /// The `depends_on` and `extends` edges between indexed skills.
+    pub fn graph(&self) -> &Graph {
+        &self.graph
+    }
+
+    pub fn ledger(&self) -> &Ledger {
+        &self.ledger
+    }
+
+    /// Forget what has been loaded, as at the start of a new session.
+    /// Cached file contents are kept.
+    pub fn reset(&mut self) {
+        self.ledger = Ledger::default();
+        self.catalog_recorded = false;
+    }
+
+    /// Level 1: name and description of every skill, sorted by name. The
+    /// first call records the catalog's cost in the ledger.
+    pub fn metadata_catalog(&mut self) -> Vec<SkillMetadata> {
+        let catalog: Vec<SkillMetadata> =
+            self.entries.values().map(|e| e.metadata.clone()).collect();
+        if !self.catalog_recorded {
+            self.catalog_recorded = true;
+            self.ledger.events.extend(catalog.iter().map(|m| LoadEvent {
+                level: Level::Metadata,
+                skill: m.name.clone(),
+                item: None,
+                tokens: m.tokens,
+            }));
+        }
+        catalog
+    }
+
+    /// Level 1 entry for one skill, without touching the ledger.
+    pub fn metadata(&self, name: &str) -> Option<&SkillMetadata> {
+        self.entries.get(name).map(|e| &e.metadata)
+    }
+
+    /// Level 2: the full `SKILL.md` of `name`.
+    pub fn load_instructions(&mut self, name: &str) -> Result<Arc<str>> {
+        let path = self.entry(name)?.dir.skill_file();
+        let text = self.read_cached(&path)?;
+        self.record(Level::Instructions, name, None, &text);
+        Ok(text)
+    }
+
+    /// Level 2 following the skill graph: the `depends_on` prerequisites of
+    /// `name` that are not in context yet, in load order, then `name` itself
+    /// merged with the skills it `extends`. Sections of bases that are
+    /// already in context are left out rather than repeated.
+    pub fn load_composed(&mut self, name: &str) -> Result<Vec<Instructions>> {
+        self.entry(name)?;
+        let mut loaded = Vec::new();
+        for skill in self.graph.prerequisites(name)? {
+            let known = self.entries.contains_key(&skill);
+            if !known || self.ledger.contains(Level::Instructions, &skill, None) {
+                continue;
+            }
+            loaded.push(self.compose(&skill)?);
+        }
+        loaded.push(self.compose(name)?);
+        Ok(loaded)
+    }
+
+    /// Level 3: a file inside the skill directory, usually under
+    /// `references/`. `path` is relative to the skill directory and may not
+    /// escape it.
+    pub fn load_reference(&mut self, name: &str, path: impl AsRef<Path>) -> Result<Arc<str>> {
+        let relative = path.as_ref();
+        let full = self.resolve(name, relative)?;
+        let text = self.read_cached(&full)?;
+        self.record(Level::Resources, name, Some(relative), &text);
+        Ok(text)
+    }
+
+    /// Level 3: run a script from the skill's `scripts/` directory. Only its
+    /// output is recorded, since only the output reaches the agent. Scripts
+    /// are never cached.
+    pub fn run_script(
+        &mut self,
+        name: &str,
+        script: &str,
+        args: &[String],
+    ) -> Result<ScriptOutput> {
+        let relative = match Path::new(script).starts_with(SCRIPTS_DIR) {
+            true => PathBuf::from(script),
+            false => Path::new(SCRIPTS_DIR).join(script),
+        };
+        let full = self.resolve(name, &relative)?;
+        let output = self.runner.run(&full, args)?;
+        self.ledger.events.push(LoadEvent {
+            level: Level::Resources,
+            skill: name.to_string(),
+            item: Some(relative),
+            tokens: output.tokens,
+        });
+        Ok(output)
+    }
+
+    /// Merge `name` with its bases and record it as loaded.
+    fn compose(&mut self, name: &str) -> Result<Instructions> {
+        let mut chain = Vec::new();
+        for skill in self.graph.bases(name)? {
+            let Some(entry) = self.entries.get(&skill) else {
+                continue;
+            };
+            let path = entry.dir.skill_file();
+            let source = self.read_cached(&path)?;
+            let parsed =
+                Skill::parse(source.to_string()).map_err(|source| Error::Parse { path, source })?;
+            chain.push((skill, parsed));
+        }
+        let chain: Vec<(&str, &Skill)> = chain.iter().map(|(n, s)| (n.as_str(), s)).collect();
+        let mut blocks = graph::compose(&chain);
+        blocks.retain(|b| {
+            b.skill == name || !self.ledger.contains(Level::Instructions, &b.skill, None)
+        });
+        let text = graph::render(&blocks);
+        self.record(Level::Instructions, name, None, &text);
+        Ok(Instructions {
+            skill: name.to_string(),
+            text,
+        })
+    }
+
+    fn entry(&self, name: &str) -> Result<&Entry> {
+        self.entries
+            .get(name)
+            .ok_or_else(|| Error::UnknownSkill(name.to_string()))
+    }
+
+    /// Join `relative` onto the skill directory, rejecting absolute paths,
+    /// `..` and symlinks that lead outside it.
+    fn resolve(&self, name: &str, relative: &Path) -> Result<PathBuf> {
+        let dir = &self.entry(name)?.dir.path;
+        let escapes = relative
+            .components()
+            .any(|c| !matches!(c, Component::Normal(_) | Component::CurDir));
+        let outside = || Error::OutsideSkill {
+            skill: name.to_string(),
+            path: relative.to_path_buf(),
+        };
+        if escapes {
+            return Err(outside());
+        }
+        let full = dir.join(relative);
+        let real = fs::canonicalize(&full).map_err(|e| Error::io(&full, e))?;
+        let root = fs::canonicalize(dir).map_err(|e| Error::io(dir, e))?;
+        match real.starts_with(&root) {
+            true => Ok(full),
+            false => Err(outside()),
+        }
+    }
+
+    fn read_cached(&mut self, path: &Path) -> Result<Arc<str>> {
+        if let Some(text) = self.cache.get(path) {
+            return Ok(text.clone());
+        }
+        let text: Arc<str> = fs::read_to_string(path)
+            .map_err(|e| Error::io(path, e))?
+            .into();
+        self.cache.insert(path.to_path_buf(), text.clone());
+        Ok(text)
+    }
+
+    /// Add an event unless the same item is already in context.
+    fn record(&mut self, level: Level, skill: &str, item: Option<&Path>, text: &str) {
+        if self.ledger.contains(level, skill, item) {
+            return;
+        }
+        self.ledger.events.push(LoadEvent {
+            level,
+            skill: skill.to_string(),
+            item: item.map(Path::to_path_buf),
+            tokens: self.counter.count(text),
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::TempDir;
+
+    fn skill(name: &str, extra: &str, body: &str) -> String {
+        format!("---\nname: {name}\ndescription: The {name} skill\n{extra}---\n{body}")
+    }
+
+    fn loader(dir: &TempDir) -> SkillLoader {
+        SkillLoader::open(dir.path())
+            .unwrap()
+            .tokenizer(tokens::tokenizer(Some("chars")).unwrap())
+    }
+
+    #[test]
+    fn catalog_is_sorted_and_recorded_once() {
+        let dir = TempDir::new();
+        dir.write("zod/SKILL.md", skill("zod", "", "# Zod\n"));
+        dir.write("astro/SKILL.md", skill("astro", "globs: ['*.astro']\n", ""));
+        dir.write("broken/SKILL.md", "no frontmatter");
+        let mut loader = loader(&dir);
+        assert_eq!(loader.invalid().len(), 1);
+        let catalog = loader.metadata_catalog();
+        let names: Vec<_> = catalog.iter().map(|m| m.name.as_str()).collect();
+        assert_eq!(names, ["astro", "zod"]);
+        assert_eq!(catalog[0].globs, ["*.astro"]);
+        // "astro: The astro skill" is 22 characters.
+        assert_eq!(catalog[0].tokens, 6);
+        loader.metadata_catalog();
+        assert_eq!(loader.ledger().events.len(), 2);
+        assert_eq!(
+            loader.ledger().tokens(Level::Metadata),
+            loader.ledger().total()
+        );
+    }
+
+    #[test]
+    fn a_second_skill_with_the_same_name_is_invalid() {
+        let dir = TempDir::new();
+        dir.write("a/SKILL.md", skill("zod", "", "first\n"));
+        dir.write("b/SKILL.md", skill("zod", "", "second\n"));
+        let mut loader = loader(&dir);
+        assert!(matches!(
+            loader.invalid(),
+            [Error::DuplicateSkill { name, second, .. }] if name == "zod" && second.ends_with("b")
+        ));
+        assert!(loader
+            .load_instructions("zod")
+            .unwrap()
+            .ends_with("first\n"));
+    }
+
+    #[test]
+    fn instructions_and_references_are_recorded_once_per_session() {
+        let dir = TempDir::new();
+        dir.write("zod/SKILL.md", skill("zod", "", "# Zod\n"));
+        dir.write("zod/references/api.md", "x".repeat(8));
+        let mut loader = loader(&dir);
+        let text = loader.load_instructions("zod").unwrap();
+        assert!(text.ends_with("# Zod\n"));
+        loader.load_instructions("zod").unwrap();
+        assert_eq!(
+            &*loader.load_reference("zod", "references/api.md").unwrap(),
+            "xxxxxxxx"
+        );
+        loader.load_reference("zod", "references/api.md").unwrap();
+        let levels: Vec<_> = loader.ledger().events.iter().map(|e| e.level).collect();
+        assert_eq!(levels, [Level::Instructions, Level::Resources]);
+        assert_eq!(loader.ledger().tokens(Level::Resources), 2);
+        loader.reset();
+        assert_eq!(loader.ledger().total(), 0);
+        loader.load_instructions("zod").unwrap();
+        assert_eq!(loader.ledger().events.len(), 1);
+    }
+
+    #[test]
+    fn unknown_skills_are_an_error() {
+        let dir = TempDir::new();
+        let mut loader = loader(&dir);
+        assert!(matches!(
+            loader.load_instructions("zod"),
+            Err(Error::UnknownSkill(name)) if name == "zod"
+        ));
+    }
+
+    #[test]
+    fn references_may_not_escape_the_skill_directory() {
+        let dir = TempDir::new();
+        dir.write("zod/SKILL.md", skill("zod", "", ""));
+        dir.write("secret.txt", "secret");
+        let mut loader = loader(&dir);
+        for path in ["../secret.txt", "/etc/passwd"] {
+            assert!(
+                matches!(
+                    loader.load_reference("zod", path),
+                    Err(Error::OutsideSkill { .. })
+                ),
+                "{path}"
+            );
+        }
+        assert!(matches!(
+            loader.run_script("zod", "../../secret.txt", &[]),
+            Err(Error::OutsideSkill { .. })
+        ));
+        assert!(loader.ledger().events.is_empty());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn symlinks_out_of_the_skill_directory_are_rejected() {
+        let dir = TempDir::new();
+        dir.write("zod/SKILL.md", skill("zod", "", ""));
+        let secret = dir.write("secret.txt", "secret");
+        fs::create_dir_all(dir.path().join("zod/references")).unwrap();
+        std::os::unix::fs::symlink(&secret, dir.path().join("zod/references/link.md")).unwrap();
+        let mut loader = loader(&dir);
+        assert!(matches!(
+            loader.load_reference("zod", "references/link.md"),
+            Err(Error::OutsideSkill { .. })
+        ));
+    }
+
+    #[test]
+    fn reindex_picks_up_edits() {
+        let dir = TempDir::new();
+        dir.write("zod/SKILL.md", skill("zod", "", "old\n"));
+        let mut loader = loader(&dir);
+        assert!(loader.load_instructions("zod").unwrap().ends_with("old\n"));
+        dir.write("zod/SKILL.md", skill("zod", "", "new\n"));
+        assert!(loader.load_instructions("zod").unwrap().ends_with("old\n"));
+        loader.reindex().unwrap();
+        assert!(loader.load_instructions("zod").unwrap().ends_with("new\n"));
+    }
+}
@@ -0,0 +1,314 @@
+//! `skills.lock`: where each installed skill came from.
+//!
+//! ```toml
+//! version = 1
+//!
+//! [skills.typescript-extension]
+//! source = "https://github.com/carloss765/agent-skills.git"
+//! path = ".claude/skills/typescript-extension"
+//! version = "1.4.0"
+//! commit = "3f2c1e0..."
+//! hash = "sha256:9b1d..."
+//! ```
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::io::{self, Read};
+use std::path::{Component, Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use crate::lint::is_executable;
+use crate::skill::is_kebab_case;
+use crate::{Error, Result};
+
+pub const LOCK_FILE: &str = "skills.lock";
+
+/// Current lockfile format.
+const VERSION: u32 = 1;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Lockfile {
+    pub version: u32,
+    /// Installed skills by directory name.
+    #[serde(default)]
+    pub skills: BTreeMap<String, LockedSkill>,
+}
+
+impl Default for Lockfile {
+    fn default() -> Self {
+        Self {
+            version: VERSION,
+            skills: BTreeMap::new(),
+        }
+    }
+}
+
+/// Provenance of one installed skill.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct LockedSkill {
+    /// Git URL or absolute local path, as accepted by `skills add`.
+    pub source: String,
+    /// Skill directory relative to the source root, `/`-separated.
+    pub path: String,
+    /// The skill's frontmatter `version`, when it declares one.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub version: Option<String>,
+    /// Commit the skill was copied from, when the source is a git checkout.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub commit: Option<String>,
+    /// [`content_hash`] of the installed directory.
+    pub hash: String,
+}
+
+impl Lockfile {
+    /// Read a `skills.lock`. A missing file is an empty lockfile.
+    pub fn load(path: &Path) -> Result<Self> {
+        let text = match fs::read_to_string(path) {
+            Ok(text) => text,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Self::default()),
+            Err(e) => return Err(Error::io(path, e)),
+        };
+        let lock: Self = toml::from_str(&text).map_err(|e| Error::Config {
+            path: path.to_path_buf(),
+            message: e.message().to_string(),
+        })?;
+        let invalid = |message: String| Error::Config {
+            path: path.to_path_buf(),
+            message,
+        };
+        if lock.version != VERSION {
+            return Err(invalid(format!(
+                "unsupported lockfile version {}",
+                lock.version
+            )));
+        }
+        // Names become directories under `.claude/skills` and paths are
+        // joined onto a source checkout, so neither may climb out.
+        for (name, skill) in &lock.skills {
+            if !is_kebab_case(name) {
+                return Err(invalid(format!("`{name}` is not a skill name")));
+            }
+            if !is_relative_path(&skill.path) {
+                return Err(invalid(format!(
+                    "`{}` locked for `{name}` is not a path inside its source",
+                    skill.path
+                )));
+            }
+        }
+        Ok(lock)
+    }
+
+    /// Write the lockfile, or delete it once no skills are left.
+    pub fn save(&self, path: &Path) -> Result<()> {
+        if self.skills.is_empty() {
+            return match fs::remove_file(path) {
+                Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(Error::io(path, e)),
+                _ => Ok(()),
+            };
+        }
+        let text = toml::to_string_pretty(self).map_err(|e| Error::Config {
+            path: path.to_path_buf(),
+            message: e.to_string(),
+        })?;
+        let text = format!("# Generated by `skills add`; do not edit by hand.\n{text}");
+        fs::write(path, text).map_err(|e| Error::io(path, e))
+    }
+}
+
+/// `.` for the source root, or plain directory names joined by `/`.
+fn is_relative_path(path: &str) -> bool {
+    path == "."
+        || (!path.is_empty()
+            && Path::new(path)
+                .components()
+                .all(|c| matches!(c, Component::Normal(_))))
+}
+
+/// `sha256:<hex>` over every file in `dir`: relative path, executable bit
+/// and contents, in path order. `.git` is ignored.
+pub fn content_hash(dir: &Path) -> Result<String> {
+    let mut relatives = Vec::new();
+    walk(dir, Path::new(""), &mut relatives)?;
+    let mut files = Vec::new();
+    for relative in relatives {
+        let path = dir.join(&relative);
+        let mut contents = Vec::new();
+        fs::File::open(&path)
+            .and_then(|mut f| f.read_to_end(&mut contents))
+            .map_err(|e| Error::io(&path, e))?;
+        files.push((relative, is_executable(&path), contents));
+    }
+    Ok(hash_files(files.iter().map(
+        |(relative, executable, contents)| (relative.as_path(), *executable, &contents[..]),
+    )))
+}
+
+/// [`content_hash`] of files held in memory, given as relative path,
+/// executable bit and contents in any order.
+pub(crate) fn hash_files<'a>(
+    files: impl IntoIterator<Item = (&'a Path, bool, &'a [u8])>,
+) -> String {
+    let mut files: Vec<_> = files.into_iter().collect();
+    files.sort_by_key(|(relative, _, _)| *relative);
+    let mut hasher = Sha256::new();
+    for (relative, executable, contents) in files {
+        hasher.update(slash_path(relative).as_bytes());
+        hasher.update([0, executable as u8]);
+        hasher.update((contents.len() as u64).to_le_bytes());
+        hasher.update(contents);
+    }
+    let digest = hasher.finalize();
+    let hex: String = digest.iter().map(|b| format!("{b:02x}")).collect();
+    format!("sha256:{hex}")
+}
+
+/// Files under `dir`, relative to it. Symbolic links are refused rather
+/// than followed, so a skill cannot pull in files from elsewhere on the
+/// host.
+pub(crate) fn walk(root: &Path, relative: &Path, out: &mut Vec<PathBuf>) -> Result<()> {
+    let dir = root.join(relative);
+    let entries = fs::read_dir(&dir).map_err(|e| Error::io(&dir, e))?;
+    for entry in entries {
+        let entry = entry.map_err(|e| Error::io(&dir, e))?;
+        if entry.file_name() == ".git" {
+            continue;
+        }
+        let relative = relative.join(entry.file_name());
+        let file_type = entry.file_type().map_err(|e| Error::io(entry.path(), e))?;
+        if file_type.is_symlink() {
+            let link = io::Error::new(io::ErrorKind::InvalidInput, "is a symbolic link");
+            return Err(Error::io(entry.path(), link));
+        }
+        if file_type.is_dir() {
+            walk(root, &relative, out)?;
+        } else if file_type.is_file() {
+            out.push(relative);
+        }
+    }
+    Ok(())
+}
+
+/// `path` with `/` separators on every platform.
+pub(crate) fn slash_path(path: &Path) -> String {
+    path.components()
+        .map(|c| c.as_os_str().to_string_lossy())
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::TempDir;
+
+    fn locked(path: &str) -> LockedSkill {
+        LockedSkill {
+            source: "https://example.com/skills.git".into(),
+            path: path.into(),
+            version: Some("1.0.0".into()),
+            commit: None,
+            hash: "sha256:00".into(),
+        }
+    }
+
+    fn write_lock(dir: &TempDir, name: &str, path: &str) -> PathBuf {
+        dir.write(
+            LOCK_FILE,
+            format!(
+                "version = 1\n\n[skills.{name:?}]\nsource = \"/src\"\npath = {path:?}\nhash = \"sha256:00\"\n"
+            ),
+        )
+    }
+
+    #[test]
+    fn lockfiles_round_trip_and_empty_ones_are_deleted() {
+        let dir = TempDir::new();
+        let path = dir.path().join(LOCK_FILE);
+        assert_eq!(Lockfile::load(&path).unwrap(), Lockfile::default());
+        let mut lock = Lockfile::default();
+        lock.skills
+            .insert("zod".into(), locked(".claude/skills/zod"));
+        lock.save(&path).unwrap();
+        assert_eq!(Lockfile::load(&path).unwrap(), lock);
+        Lockfile::default().save(&path).unwrap();
+        assert!(!path.exists());
+    }
+
+    #[test]
+    fn other_versions_are_rejected() {
+        let dir = TempDir::new();
+        let path = dir.write(LOCK_FILE, "version = 2\n");
+        assert!(matches!(Lockfile::load(&path), Err(Error::Config { .. })));
+    }
+
+    #[test]
+    fn names_that_are_not_skill_names_are_rejected() {
+        let dir = TempDir::new();
+        for name in ["../../victim", "a/b", "Zod", ""] {
+            let path = write_lock(&dir, name, ".");
+            assert!(
+                matches!(Lockfile::load(&path), Err(Error::Config { .. })),
+                "{name}"
+            );
+        }
+    }
+
+    #[test]
+    fn paths_must_stay_inside_the_source() {
+        let dir = TempDir::new();
+        for bad in ["..", "../x", "skills/../../x", "/etc", "./skills/zod", ""] {
+            let path = write_lock(&dir, "zod", bad);
+            assert!(
+                matches!(Lockfile::load(&path), Err(Error::Config { .. })),
+                "{bad}"
+            );
+        }
+        for good in [".", "zod", ".claude/skills/zod"] {
+            let path = write_lock(&dir, "zod", good);
+            assert!(Lockfile::load(&path).is_ok(), "{good}");
+        }
+    }
+
+    #[test]
+    fn content_hash_covers_paths_and_contents_but_not_git() {
+        let dir = TempDir::new();
+        dir.write("SKILL.md", "a");
+        let hash = content_hash(dir.path()).unwrap();
+        assert!(hash.starts_with("sha256:") && hash.len() == 7 + 64);
+        dir.write(".git/HEAD", "ref");
+        assert_eq!(content_hash(dir.path()).unwrap(), hash);
+        dir.write("SKILL.md", "b");
+        assert_ne!(content_hash(dir.path()).unwrap(), hash);
+        dir.write("SKILL.md", "a");
+        dir.write("references/x.md", "");
+        assert_ne!(content_hash(dir.path()).unwrap(), hash);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn content_hash_covers_the_executable_bit() {
+        use std::os::unix::fs::PermissionsExt;
+        let dir = TempDir::new();
+        let script = dir.write("scripts/run.sh", "echo hi\n");
+        let before = content_hash(dir.path()).unwrap();
+        fs::set_permissions(&script, fs::Permissions::from_mode(0o755)).unwrap();
+        assert_ne!(content_hash(dir.path()).unwrap(), before);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn content_hash_refuses_symbolic_links() {
+        let dir = TempDir::new();
+        let secret = dir.write("secret", "key");
+        dir.write("skill/SKILL.md", "a");
+        std::os::unix::fs::symlink(&secret, dir.path().join("skill/references")).unwrap();
+        assert!(matches!(
+            content_hash(&dir.path().join("skill")),
+            Err(Error::Io { path, .. }) if path.ends_with("skill/references")
+        ));
+    }
+}
@@ -0,0 +1,1063 @@
+//! A [Language Server](https://microsoft.github.io/language-server-protocol/)
+//! for writing `SKILL.md` files, so authors see what `skills lint` and
+//! `skills tokens` would say while they type:
+//!
+//! - completion of frontmatter keys;
+//! - diagnostics from the [lint rules](crate::lint) on open, edit and save;
+//! - token counts as inlay hints: Levels 1 and 2 after the frontmatter and
+//!   one per heading;
+//! - go to definition and hover previews on `references/...` links;
+//! - a code action that moves a long `##` section into `references/` and
+//!   leaves a link in its place.
+//!
+//! Messages are JSON-RPC 2.0 framed by `Content-Length` headers, and
+//! positions count UTF-16 code units, as the protocol specifies by default.
+//! Only documents named `SKILL.md` are tracked.
+
+use std::collections::BTreeMap;
+use std::fmt::Write as _;
+use std::fs;
+use std::io::{self, BufRead, Read, Write};
+use std::ops::Range;
+use std::path::{Component, Path, PathBuf};
+
+use serde_json::{json, Value};
+
+use crate::lint::{is_when_to_use, Diagnostic, Linter, Severity};
+use crate::lock::slash_path;
+use crate::mcp::{failure, INVALID_PARAMS, INVALID_REQUEST, METHOD_NOT_FOUND, PARSE_ERROR};
+use crate::parse::lines;
+use crate::project::{Project, REFERENCES_DIR};
+use crate::relevance::fold;
+use crate::skill::{Section, Skill, SKILL_FILE};
+use crate::tokens::{self, TokenCounter};
+use crate::{Error, Result};
+
+/// Frontmatter keys offered by completion, with what each is for.
+const FRONTMATTER_KEYS: &[(&str, &str)] = &[
+    (
+        "name",
+        "Skill name in kebab-case, the same as its directory.",
+    ),
+    (
+        "description",
+        "What the skill does and when to use it. Always in context (Level 1).",
+    ),
+    (
+        "globs",
+        "File patterns that make the skill relevant, gitignore style.",
+    ),
+    ("version", "Semantic version of the skill, e.g. `1.0.0`."),
+    (
+        "requires",
+        "Skills this one needs, with the version range it works with.",
+    ),
+    (
+        "depends_on",
+        "Skills whose instructions are loaded before this one's.",
+    ),
+    (
+        "extends",
+        "A base skill whose `##` sections this one replaces or adds to.",
+    ),
+    (
+        "override",
+        "What to do with the same-named skill of a lower `.claude/` layer: \
+         `true`, `disable` or `{ disable: [<section>] }`.",
+    ),
+    ("lint", "`allow:` the lint rules this skill silences."),
+];
+
+/// Sections with at least this many lines are offered the move to
+/// `references/`.
+const LONG_SECTION_LINES: usize = 40;
+
+/// Lines of a referenced file shown on hover.
+const PREVIEW_LINES: usize = 20;
+
+/// Largest message body read into memory. Larger ones are skipped and
+/// answered with an error.
+const MAX_MESSAGE: usize = 16 * 1024 * 1024;
+
+/// An open `SKILL.md` as the editor has it.
+struct Document {
+    path: PathBuf,
+    text: String,
+    version: Option<i64>,
+}
+
+/// Answers LSP requests from one editor.
+pub struct Server {
+    linter: Linter,
+    counter: TokenCounter,
+    documents: BTreeMap<String, Document>,
+    exited: bool,
+}
+
+impl Server {
+    pub fn new(linter: Linter, counter: TokenCounter) -> Self {
+        Self {
+            linter,
+            counter,
+            documents: BTreeMap::new(),
+            exited: false,
+        }
+    }
+
+    /// A server linting and counting tokens as `skills.toml` configures.
+    pub fn from_project(project: &Project) -> Result<Self> {
+        let config = project.config()?;
+        let tokenizer = tokens::tokenizer(config.tokens.tokenizer.as_deref())?;
+        Ok(Self::new(
+            Linter::from_config(&config)?,
+            TokenCounter::new(tokenizer),
+        ))
+    }
+
+    /// Read messages from `input` until it closes or the client sends
+    /// `exit`, writing replies and notifications to `output`.
+    pub fn serve(&mut self, mut input: impl BufRead, mut output: impl Write) -> Result<()> {
+        while let Some(message) = read_message(&mut input)? {
+            let replies = match message {
+                Ok(message) => self.handle(&message),
+                Err(length) => vec![failure(
+                    Value::Null,
+                    INVALID_REQUEST,
+                    &format!("message of {length} bytes is over the {MAX_MESSAGE} byte limit"),
+                )],
+            };
+            for reply in replies {
+                let body = reply.to_string();
+                write!(output, "Content-Length: {}\r\n\r\n{body}", body.len())
+                    .and_then(|()| output.flush())
+                    .map_err(|e| Error::io("stdout", e))?;
+            }
+            if self.exited {
+                break;
+            }
+        }
+        Ok(())
+    }
+
+    /// What to send back for one JSON-RPC message: the reply to a request,
+    /// or diagnostics after a document changes.
+    pub fn handle(&mut self, message: &str) -> Vec<Value> {
+        let message: Value = match serde_json::from_str(message) {
+            Ok(message) => message,
+            Err(e) => return vec![failure(Value::Null, PARSE_ERROR, &e.to_string())],
+        };
+        let id = message.get("id").cloned();
+        let Some(method) = message.get("method").and_then(Value::as_str) else {
+            let is_response = message.get("result").is_some() || message.get("error").is_some();
+            return match is_response {
+                true => Vec::new(),
+                false => vec![failure(
+                    id.unwrap_or(Value::Null),
+                    INVALID_REQUEST,
+                    "not a JSON-RPC request",
+                )],
+            };
+        };
+        let params = message.get("params").cloned().unwrap_or(json!({}));
+        let Some(id) = id else {
+            return self.notification(method, &params);
+        };
+        let result = match method {
+            "initialize" => Ok(initialize()),
+            "shutdown" => Ok(Value::Null),
+            "textDocument/completion" => self.completion(&params),
+            "textDocument/hover" => self.hover(&params),
+            "textDocument/definition" => self.definition(&params),
+            "textDocument/inlayHint" => self.inlay_hints(&params),
+            "textDocument/codeAction" => self.code_actions(&params),
+            other => Err((METHOD_NOT_FOUND, format!("unknown method `{other}`"))),
+        };
+        vec![match result {
+            Ok(result) => json!({ "jsonrpc": "2.0", "id": id, "result": result }),
+            Err((code, message)) => failure(id, code, &message),
+        }]
+    }
+
+    fn notification(&mut self, method: &str, params: &Value) -> Vec<Value> {
+        let Some(uri) = params["textDocument"]["uri"].as_str() else {
+            self.exited |= method == "exit";
+            return Vec::new();
+        };
+        let version = params["textDocument"]["version"].as_i64();
+        match method {
+            "textDocument/didOpen" => {
+                let Some(path) = uri_to_path(uri).filter(|p| p.ends_with(SKILL_FILE)) else {
+                    return Vec::new();
+                };
+                let text = params["textDocument"]["text"].as_str().unwrap_or_default();
+                self.documents.insert(
+                    uri.to_string(),
+                    Document {
+                        path,
+                        text: text.to_string(),
+                        version,
+                    },
+                );
+            }
+            // Changes are always whole documents, as `initialize` asks.
+            "textDocument/didChange" => {
+                let Some(document) = self.documents.get_mut(uri) else {
+                    return Vec::new();
+                };
+                let changes = params["contentChanges"].as_array();
+                if let Some(text) = changes.and_then(|c| c.last()?["text"].as_str()) {
+                    document.text = text.to_string();
+                }
+                document.version = version;
+            }
+            // Scripts and references may have changed on disk too.
+            "textDocument/didSave" => {
+                if !self.documents.contains_key(uri) {
+                    return Vec::new();
+                }
+            }
+            "textDocument/didClose" => {
+                if self.documents.remove(uri).is_none() {
+                    return Vec::new();
+                }
+            }
+            _ => return Vec::new(),
+        }
+        vec![self.publish(uri)]
+    }
+
+    /// `textDocument/publishDiagnostics` with what the linter says about
+    /// `uri` now; nothing once it is closed.
+    fn publish(&self, uri: &str) -> Value {
+        let diagnostics: Vec<Value> = match self.documents.get(uri) {
+            Some(document) => self
+                .linter
+                .lint_source(&document.path, &document.text)
+                .iter()
+                .map(|d| diagnostic(document, d))
+                .collect(),
+            None => Vec::new(),
+        };
+        json!({
+            "jsonrpc": "2.0",
+            "method": "textDocument/publishDiagnostics",
+            "params": { "uri": uri, "diagnostics": diagnostics },
+        })
+    }
+
+    /// The open document a request is about, if it is tracked.
+    fn document(&self, params: &Value) -> Rpc<Option<(&str, &Document)>> {
+        let uri = params["textDocument"]["uri"]
+            .as_str()
+            .ok_or((INVALID_PARAMS, "missing `textDocument.uri`".to_string()))?;
+        Ok(self
+            .documents
+            .get_key_value(uri)
+            .map(|(uri, document)| (uri.as_str(), document)))
+    }
+
+    /// Top-level frontmatter keys not in the document yet, when the cursor
+    /// is where a key goes.
+    fn completion(&self, params: &Value) -> Rpc<Value> {
+        let Some((_, document)) = self.document(params)? else {
+            return Ok(Value::Null);
+        };
+        let text = &document.text;
+        let at = offset(text, &params["position"]);
+        let Some(yaml) = frontmatter(text).filter(|yaml| yaml.contains(&at) || yaml.end == at)
+        else {
+            return Ok(Value::Null);
+        };
+        let line_start = text[..at].rfind('\n').map_or(0, |i| i + 1);
+        let typed = &text[line_start..at];
+        if typed.starts_with([' ', '\t', '-', '#']) || typed.contains(':') {
+            return Ok(Value::Null);
+        }
+        let present: Vec<&str> = lines(text, yaml.start)
+            .take_while(|line| line.start < yaml.end)
+            .filter(|line| line.start != line_start && !line.text.starts_with([' ', '\t', '-']))
+            .filter_map(|line| line.text.split_once(':'))
+            .map(|(key, _)| key.trim())
+            .collect();
+        let items: Vec<Value> = FRONTMATTER_KEYS
+            .iter()
+            .filter(|(key, _)| !present.contains(key))
+            .map(|(key, doc)| {
+                json!({
+                    "label": key,
+                    "kind": 10,
+                    "documentation": { "kind": "markdown", "value": doc },
+                    "insertText": format!("{key}: "),
+                })
+            })
+            .collect();
+        Ok(Value::Array(items))
+    }
+
+    fn definition(&self, params: &Value) -> Rpc<Value> {
+        let Some((_, document)) = self.document(params)? else {
+            return Ok(Value::Null);
+        };
+        let at = offset(&document.text, &params["position"]);
+        let target = reference_at(&document.text, at).and_then(|(_, link)| resolve(document, link));
+        Ok(match target {
+            Some(path) => json!({
+                "uri": path_to_uri(&path),
+                "range": { "start": { "line": 0, "character": 0 }, "end": { "line": 0, "character": 0 } },
+            }),
+            None => Value::Null,
+        })
+    }
+
+    /// The start of a referenced file and what it costs to load.
+    fn hover(&self, params: &Value) -> Rpc<Value> {
+        let Some((_, document)) = self.document(params)? else {
+            return Ok(Value::Null);
+        };
+        let text = &document.text;
+        let at = offset(text, &params["position"]);
+        let Some((range, link)) = reference_at(text, at) else {
+            return Ok(Value::Null);
+        };
+        let value = match resolve(document, link) {
+            Some(path) => self.preview(&path, link),
+            None => format!("`{link}` does not exist"),
+        };
+        Ok(json!({
+            "contents": { "kind": "markdown", "value": value },
+            "range": lsp_range(text, range),
+        }))
+    }
+
+    fn preview(&self, path: &Path, link: &str) -> String {
+        let text = match fs::read(path) {
+            Ok(bytes) => String::from_utf8_lossy(&bytes).into_owned(),
+            Err(e) => return format!("`{link}`: {e}"),
+        };
+        let mut out = format!(
+            "**{link}**: {} tokens when loaded (Level 3)\n\n---\n\n",
+            self.counter.count(&text)
+        );
+        let shown: Vec<&str> = text.lines().take(PREVIEW_LINES).collect();
+        match path.extension().and_then(|e| e.to_str()) {
+            Some("md") => out.push_str(&shown.join("\n")),
+            extension => {
+                let _ = write!(
+                    out,
+                    "```{}\n{}\n```",
+                    extension.unwrap_or_default(),
+                    shown.join("\n")
+                );
+            }
+        }
+        if text.lines().count() > PREVIEW_LINES {
+            out.push_str("\n\n…");
+        }
+        out
+    }
+
+    /// Level 1 and Level 2 costs after the closing `---`, and the cost of
+    /// each section after its heading.
+    fn inlay_hints(&self, params: &Value) -> Rpc<Value> {
+        let Some((_, document)) = self.document(params)? else {
+            return Ok(Value::Null);
+        };
+        let Ok(skill) = Skill::parse(document.text.as_str()) else {
+            return Ok(json!([]));
+        };
+        let text = skill.source();
+        let close = text[..skill.frontmatter_span().end.offset]
+            .trim_end_matches(['\n', '\r'])
+            .len();
+        let mut hints = vec![hint(
+            text,
+            close,
+            format!(
+                "Level 1: {} tokens, Level 2: {} tokens",
+                self.counter.metadata(&skill),
+                self.counter.instructions(&skill)
+            ),
+        )];
+        for section in skill.sections() {
+            let tokens = self.counter.count(section.span.text(text));
+            hints.push(hint(
+                text,
+                section.heading.end.offset,
+                format!("{tokens} tokens"),
+            ));
+        }
+        Ok(Value::Array(hints))
+    }
+
+    /// Moving the long `##` section under the cursor to `references/`.
+    fn code_actions(&self, params: &Value) -> Rpc<Value> {
+        let Some((uri, document)) = self.document(params)? else {
+            return Ok(Value::Null);
+        };
+        let Ok(skill) = Skill::parse(document.text.as_str()) else {
+            return Ok(json!([]));
+        };
+        let at = offset(&document.text, &params["range"]["start"]);
+        let actions: Vec<Value> = skill
+            .sections()
+            .iter()
+            .filter(|s| s.level == 2 && (s.span.range().contains(&at) || s.span.end.offset == at))
+            .filter(|s| s.span.end.line - s.span.start.line >= LONG_SECTION_LINES)
+            // Lint requires "When to Use" in SKILL.md itself.
+            .filter(|s| !is_when_to_use(&s.title))
+            .filter_map(|s| move_to_references(uri, document, s))
+            .collect();
+        Ok(Value::Array(actions))
+    }
+}
+
+/// A request's result, or a JSON-RPC error code and message.
+type Rpc<T> = std::result::Result<T, (i64, String)>;
+
+fn initialize() -> Value {
+    json!({
+        "capabilities": {
+            "positionEncoding": "utf-16",
+            "textDocumentSync": {
+                "openClose": true,
+                "change": 1,
+                "save": { "includeText": false },
+            },
+            "completionProvider": {},
+            "hoverProvider": true,
+            "definitionProvider": true,
+            "inlayHintProvider": true,
+            "codeActionProvider": { "codeActionKinds": ["refactor.extract"] },
+        },
+        "serverInfo": {
+            "name": env!("CARGO_PKG_NAME"),
+            "version": env!("CARGO_PKG_VERSION"),
+        },
+    })
+}
+
+/// One message body, or `None` once `input` closes. A body longer than
+/// [`MAX_MESSAGE`] is skipped and its length returned as the error.
+fn read_message(input: &mut impl BufRead) -> Result<Option<std::result::Result<String, usize>>> {
+    let mut length = None;
+    loop {
+        let mut header = String::new();
+        let read = input
+            .read_line(&mut header)
+            .map_err(|e| Error::io("stdin", e))?;
+        if read == 0 {
+            return Ok(None);
+        }
+        let header = header.trim_end();
+        if header.is_empty() {
+            match length {
+                Some(_) => break,
+                None => continue,
+            }
+        }
+        if let Some((name, value)) = header.split_once(':') {
+            if name.trim().eq_ignore_ascii_case("content-length") {
+                length = value.trim().parse::<usize>().ok();
+            }
+        }
+    }
+    let length = length.unwrap_or_default();
+    if length > MAX_MESSAGE {
+        let skipped = io::copy(&mut input.take(length as u64), &mut io::sink())
+            .map_err(|e| Error::io("stdin", e))?;
+        return match skipped == length as u64 {
+            true => Ok(Some(Err(length))),
+            false => Err(Error::io("stdin", io::ErrorKind::UnexpectedEof.into())),
+        };
+    }
+    let mut body = vec![0; length];
+    input
+        .read_exact(&mut body)
+        .map_err(|e| Error::io("stdin", e))?;
+    Ok(Some(Ok(String::from_utf8_lossy(&body).into_owned())))
+}
+
+/// A lint diagnostic on `document`. Those about other files of the skill,
+/// such as its scripts, are shown at the top and point at the file.
+fn diagnostic(document: &Document, d: &Diagnostic) -> Value {
+    let severity = match d.severity {
+        Severity::Error => 1,
+        Severity::Warning => 2,
+        Severity::Info => 3,
+    };
+    let text = &document.text;
+    if d.path == document.path {
+        let range = d.span.map_or(0..0, |s| s.range());
+        return json!({
+            "range": lsp_range(text, range),
+            "severity": severity,
+            "code": d.rule,
+            "source": "skills",
+            "message": d.message,
+        });
+    }
+    let dir = document.path.parent().unwrap_or(Path::new(""));
+    let relative = d.path.strip_prefix(dir).unwrap_or(&d.path);
+    let (line, character) = d
+        .span
+        .map_or((0, 0), |s| (s.start.line - 1, s.start.column - 1));
+    let at = json!({ "line": line, "character": character });
+    json!({
+        "range": lsp_range(text, 0..0),
+        "severity": severity,
+        "code": d.rule,
+        "source": "skills",
+        "message": format!("{}: {}", slash_path(relative), d.message),
+        "relatedInformation": [{
+            "location": { "uri": path_to_uri(&d.path), "range": { "start": at, "end": at } },
+            "message": d.message,
+        }],
+    })
+}
+
+fn hint(text: &str, offset: usize, label: String) -> Value {
+    json!({
+        "position": position(text, offset),
+        "label": label,
+        "paddingLeft": true,
+    })
+}
+
+/// The YAML between the `---` delimiters, up to the end of the text while
+/// the closing one is not typed yet.
+fn frontmatter(text: &str) -> Option<Range<usize>> {
+    let mut iter = lines(text, 0);
+    let open = iter.next().filter(|line| line.text.trim_end() == "---")?;
+    let end = iter
+        .find(|line| matches!(line.text.trim_end(), "---" | "..."))
+        .map_or(text.len(), |line| line.start);
+    Some(open.next..end)
+}
+
+/// The `references/...` path under `offset`, as written and without any
+/// `#fragment`, and where it is.
+fn reference_at(text: &str, offset: usize) -> Option<(Range<usize>, &str)> {
+    let line_start = text[..offset].rfind('\n').map_or(0, |i| i + 1);
+    let line_end = text[offset..].find('\n').map_or(text.len(), |i| offset + i);
+    let line = &text[line_start..line_end];
+    let prefix = format!("{REFERENCES_DIR}/");
+    for (start, _) in line.match_indices(&prefix) {
+        let start = match line[..start].ends_with("./") {
+            true => start - 2,
+            false => start,
+        };
+        let bounded = line[..start]
+            .chars()
+            .next_back()
+            .map_or(true, |c| c.is_whitespace() || "([<`\"'".contains(c));
+        if !bounded {
+            continue;
+        }
+        let len = line[start..]
+            .find(|c: char| c.is_whitespace() || ")]>`\"',;#".contains(c))
+            .unwrap_or(line.len() - start);
+        let link = line[start..start + len].trim_end_matches(['.', ':']);
+        let range = line_start + start..line_start + start + link.len();
+        if range.contains(&offset) || range.end == offset {
+            return Some((range, link));
+        }
+    }
+    None
+}
+
+/// The file a link points at, when it exists inside the skill directory.
+fn resolve(document: &Document, link: &str) -> Option<PathBuf> {
+    let relative = Path::new(link);
+    let escapes = relative
+        .components()
+        .any(|c| !matches!(c, Component::Normal(_) | Component::CurDir));
+    if escapes {
+        return None;
+    }
+    let path = document.path.parent()?.join(relative);
+    path.is_file().then_some(path)
+}
+
+/// Create `references/<title>.md` with the section's content and leave a
+/// link under its heading.
+fn move_to_references(uri: &str, document: &Document, section: &Section) -> Option<Value> {
+    let references = document.path.parent()?.join(REFERENCES_DIR);
+    let slug = slug(&section.title);
+    let mut name = format!("{slug}.md");
+    let mut n = 2;
+    while references.join(&name).exists() {
+        name = format!("{slug}-{n}.md");
+        n += 1;
+    }
+    let link = format!("{REFERENCES_DIR}/{name}");
+    let target = path_to_uri(&references.join(&name));
+    let text = &document.text;
+    let content = section.content.text(text).trim();
+    let pointer = match section.content.end.offset == text.len() {
+        true => format!("\nSee [{link}]({link}).\n"),
+        false => format!("\nSee [{link}]({link}).\n\n"),
+    };
+    let start = json!({ "line": 0, "character": 0 });
+    Some(json!({
+        "title": format!("Move \"{}\" to {link}", section.title),
+        "kind": "refactor.extract",
+        "edit": {
+            "documentChanges": [
+                { "kind": "create", "uri": target },
+                {
+                    "textDocument": { "uri": target, "version": null },
+                    "edits": [{
+                        "range": { "start": start, "end": start },
+                        "newText": format!("# {}\n\n{content}\n", section.title),
+                    }],
+                },
+                {
+                    "textDocument": { "uri": uri, "version": document.version },
+                    "edits": [{
+                        "range": lsp_range(text, section.content.range()),
+                        "newText": pointer,
+                    }],
+                },
+            ],
+        },
+    }))
+}
+
+/// `"Troubleshooting & FAQ"` as `troubleshooting-faq`.
+fn slug(title: &str) -> String {
+    let folded = fold(&title.to_lowercase());
+    let words: Vec<&str> = folded
+        .split(|c: char| !c.is_ascii_alphanumeric())
+        .filter(|w| !w.is_empty())
+        .collect();
+    match words.is_empty() {
+        true => "section".to_string(),
+        false => words.join("-"),
+    }
+}
+
+/// A byte offset as an LSP position.
+fn position(text: &str, offset: usize) -> Value {
+    let before = &text[..offset.min(text.len())];
+    let line_start = before.rfind('\n').map_or(0, |i| i + 1);
+    let character: usize = before[line_start..].chars().map(char::len_utf16).sum();
+    json!({ "line": before.matches('\n').count(), "character": character })
+}
+
+fn lsp_range(text: &str, range: Range<usize>) -> Value {
+    json!({ "start": position(text, range.start), "end": position(text, range.end) })
+}
+
+/// The byte offset of an LSP position, clamped to its line.
+fn offset(text: &str, position: &Value) -> usize {
+    let line = position["line"].as_u64().unwrap_or(0) as usize;
+    let character = position["character"].as_u64().unwrap_or(0) as usize;
+    let start = match line {
+        0 => 0,
+        _ => text
+            .match_indices('\n')
+            .nth(line - 1)
+            .map_or(text.len(), |(i, _)| i + 1),
+    };
+    let end = text[start..].find('\n').map_or(text.len(), |i| start + i);
+    let mut units = 0;
+    for (i, c) in text[start..end].char_indices() {
+        if units >= character {
+            return start + i;
+        }
+        units += c.len_utf16();
+    }
+    end
+}
+
+fn path_to_uri(path: &Path) -> String {
+    let path = path.to_string_lossy().replace('\\', "/");
+    let mut uri = String::from("file://");
+    if !path.starts_with('/') {
+        uri.push('/');
+    }
+    for byte in path.bytes() {
+        match byte {
+            b'a'..=b'z' | b'A'..=b'Z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' | b'/' => {
+                uri.push(byte as char)
+            }
+            _ => {
+                let _ = write!(uri, "%{byte:02X}");
+            }
+        }
+    }
+    uri
+}
+
+fn uri_to_path(uri: &str) -> Option<PathBuf> {
+    let rest = uri.strip_prefix("file://")?;
+    let rest = &rest[rest.find('/')?..];
+    let mut bytes = Vec::with_capacity(rest.len());
+    let mut iter = rest.bytes();
+    while let Some(byte) = iter.next() {
+        if byte != b'%' {
+            bytes.push(byte);
+            continue;
+        }
+        let hex = [iter.next()?, iter.next()?];
+        bytes.push(u8::from_str_radix(std::str::from_utf8(&hex).ok()?, 16).ok()?);
+    }
+    let path = String::from_utf8(bytes).ok()?;
+    // `/C:/Users/..` on Windows.
+    let path = match path.as_bytes() {
+        [b'/', drive, b':', ..] if drive.is_ascii_alphabetic() => path[1..].to_string(),
+        _ => path,
+    };
+    Some(PathBuf::from(path))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use super::*;
+    use crate::test_support::TempDir;
+    use crate::tokens::CharEstimate;
+
+    const SKILL: &str = "---
+name: zod
+description: Validate data with zod schemas
+---
+# Zod
+
+## When to Use This Skill
+
+- The project depends on zod
+
+## Instructions
+
+1. Read references/api.md first.
+
+```ts
+z.string().parse(input);
+```
+";
+
+    fn server() -> Server {
+        Server::new(Linter::new(), TokenCounter::new(Arc::new(CharEstimate)))
+    }
+
+    fn notify(server: &mut Server, method: &str, params: Value) -> Vec<Value> {
+        let message = json!({ "jsonrpc": "2.0", "method": method, "params": params });
+        server.handle(&message.to_string())
+    }
+
+    fn request(server: &mut Server, method: &str, params: Value) -> Value {
+        let message = json!({ "jsonrpc": "2.0", "id": 1, "method": method, "params": params });
+        let mut replies = server.handle(&message.to_string());
+        assert_eq!(replies.len(), 1);
+        replies.remove(0)
+    }
+
+    /// Open `text` as `zod/SKILL.md` in `dir` and return its uri.
+    fn open(server: &mut Server, dir: &TempDir, text: &str) -> String {
+        let uri = path_to_uri(&dir.path().join("zod").join(SKILL_FILE));
+        let document = json!({ "uri": uri, "version": 1, "text": text });
+        notify(
+            server,
+            "textDocument/didOpen",
+            json!({ "textDocument": document }),
+        );
+        uri
+    }
+
+    fn at(uri: &str, line: usize, character: usize) -> Value {
+        json!({
+            "textDocument": { "uri": uri },
+            "position": { "line": line, "character": character },
+        })
+    }
+
+    #[test]
+    fn positions_count_utf16_code_units() {
+        let text = "a\né😀b\nc";
+        let b = text.find('b').unwrap();
+        assert_eq!(position(text, b), json!({ "line": 1, "character": 3 }));
+        assert_eq!(offset(text, &json!({ "line": 1, "character": 3 })), b);
+        assert_eq!(
+            offset(text, &json!({ "line": 1, "character": 1 })),
+            text.find('😀').unwrap()
+        );
+        assert_eq!(
+            position(text, text.len()),
+            json!({ "line": 2, "character": 1 })
+        );
+        assert_eq!(position(text, 100), json!({ "line": 2, "character": 1 }));
+    }
+
+    #[test]
+    fn offsets_are_clamped_to_their_line() {
+        let text = "ab\ncd";
+        assert_eq!(offset(text, &json!({ "line": 0, "character": 9 })), 2);
+        assert_eq!(
+            offset(text, &json!({ "line": 7, "character": 0 })),
+            text.len()
+        );
+        assert_eq!(offset(text, &json!({})), 0);
+    }
+
+    #[test]
+    fn uris_round_trip_with_escaped_bytes() {
+        let path = Path::new("/tmp/my skills/ñandú/SKILL.md");
+        let uri = path_to_uri(path);
+        assert_eq!(uri, "file:///tmp/my%20skills/%C3%B1and%C3%BA/SKILL.md");
+        assert_eq!(uri_to_path(&uri).unwrap(), path);
+        assert_eq!(
+            uri_to_path("file://localhost/tmp/a%2fb").unwrap(),
+            Path::new("/tmp/a/b")
+        );
+        assert_eq!(
+            uri_to_path("file:///C:/Users/SKILL.md").unwrap(),
+            Path::new("C:/Users/SKILL.md")
+        );
+        assert_eq!(uri_to_path("untitled:Untitled-1"), None);
+        assert_eq!(uri_to_path("file:///tmp/%zz"), None);
+        assert_eq!(uri_to_path("file:///tmp/%C3"), None);
+        assert_eq!(uri_to_path("file:///tmp/%2"), None);
+    }
+
+    #[test]
+    fn messages_are_framed_by_content_length() {
+        let mut input =
+            "Content-Length: 2\r\n\r\n{}content-length:3\r\nX-Other: 1\r\n\r\n[1]".as_bytes();
+        assert_eq!(read_message(&mut input).unwrap(), Some(Ok("{}".into())));
+        assert_eq!(read_message(&mut input).unwrap(), Some(Ok("[1]".into())));
+        assert_eq!(read_message(&mut input).unwrap(), None);
+        let mut short = "Content-Length: 10\r\n\r\n{}".as_bytes();
+        assert!(read_message(&mut short).is_err());
+    }
+
+    #[test]
+    fn oversized_messages_are_skipped_and_answered_with_an_error() {
+        let huge = MAX_MESSAGE + 1;
+        let mut input = format!("Content-Length: {huge}\r\n\r\n").into_bytes();
+        input.resize(input.len() + huge, b' ');
+        let shutdown = r#"{"jsonrpc":"2.0","id":1,"method":"shutdown"}"#;
+        input.extend(format!("Content-Length: {}\r\n\r\n{shutdown}", shutdown.len()).bytes());
+        let mut output = Vec::new();
+        server().serve(&input[..], &mut output).unwrap();
+        let output = String::from_utf8(output).unwrap();
+        let replies: Vec<Value> = output
+            .split("Content-Length: ")
+            .skip(1)
+            .map(|frame| serde_json::from_str(frame.split_once("\r\n\r\n").unwrap().1).unwrap())
+            .collect();
+        assert_eq!(replies.len(), 2, "{output}");
+        assert_eq!(replies[0]["error"]["code"], INVALID_REQUEST);
+        assert!(replies[0]["id"].is_null());
+        assert_eq!(replies[1]["id"], 1);
+
+        let cut = format!("Content-Length: {huge}\r\n\r\n{{}}");
+        assert!(read_message(&mut cut.as_bytes()).is_err());
+    }
+
+    #[test]
+    fn references_are_found_under_the_cursor() {
+        let line = "See (./references/api.md#types), not xreferences/b.md.";
+        let start = line.find("./").unwrap();
+        let found = reference_at(line, start + 5).unwrap();
+        assert_eq!(found, (start..start + 19, "./references/api.md"));
+        assert_eq!(reference_at(line, line.len() - 3), None);
+        assert_eq!(
+            reference_at("references/a.md.", 15).unwrap().1,
+            "references/a.md"
+        );
+    }
+
+    #[test]
+    fn links_do_not_resolve_outside_the_skill() {
+        let dir = TempDir::new();
+        dir.write("zod/references/api.md", "# API\n");
+        dir.write("secret.md", "");
+        let document = Document {
+            path: dir.path().join("zod").join(SKILL_FILE),
+            text: String::new(),
+            version: None,
+        };
+        assert!(resolve(&document, "./references/api.md").is_some());
+        assert_eq!(resolve(&document, "references/missing.md"), None);
+        assert_eq!(resolve(&document, "references/../../secret.md"), None);
+        assert_eq!(resolve(&document, "/etc/passwd"), None);
+    }
+
+    #[test]
+    fn frontmatter_runs_to_the_end_until_it_is_closed() {
+        assert_eq!(frontmatter("---\nname: x\n---\nbody"), Some(4..12));
+        assert_eq!(frontmatter("---\nna"), Some(4..6));
+        assert_eq!(frontmatter("# Title\n"), None);
+        assert_eq!(slug("Troubleshooting & FAQ"), "troubleshooting-faq");
+        assert_eq!(slug("Configuración"), "configuracion");
+        assert_eq!(slug("¿?"), "section");
+    }
+
+    #[test]
+    fn documents_are_linted_while_open_and_cleared_on_close() {
+        let dir = TempDir::new();
+        let mut server = server();
+        let uri = path_to_uri(&dir.path().join("zod").join(SKILL_FILE));
+        let document = json!({ "uri": uri, "version": 1, "text": "---\nname: Zod\n---\n" });
+        let opened = notify(
+            &mut server,
+            "textDocument/didOpen",
+            json!({ "textDocument": document }),
+        );
+        let diagnostics = opened[0]["params"]["diagnostics"].as_array().unwrap();
+        assert!(!diagnostics.is_empty());
+        assert!(diagnostics.iter().all(|d| d["source"] == "skills"));
+
+        let changed = json!({
+            "textDocument": { "uri": uri, "version": 2 },
+            "contentChanges": [{ "text": SKILL }],
+        });
+        let replies = notify(&mut server, "textDocument/didChange", changed);
+        assert_eq!(replies[0]["params"]["diagnostics"], json!([]));
+        let closed = json!({ "textDocument": { "uri": uri } });
+        let replies = notify(&mut server, "textDocument/didClose", closed.clone());
+        assert_eq!(replies[0]["params"]["diagnostics"], json!([]));
+        assert!(notify(&mut server, "textDocument/didClose", closed).is_empty());
+
+        let other = json!({ "textDocument": {
+            "uri": path_to_uri(&dir.path().join("README.md")), "version": 1, "text": "",
+        }});
+        assert!(notify(&mut server, "textDocument/didOpen", other).is_empty());
+    }
+
+    #[test]
+    fn completion_offers_missing_keys_where_a_key_goes() {
+        let dir = TempDir::new();
+        let mut server = server();
+        let uri = open(&mut server, &dir, "---\nname: zod\nde\n---\nbody\n");
+        let reply = request(&mut server, "textDocument/completion", at(&uri, 2, 2));
+        let labels: Vec<_> = reply["result"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|item| item["label"].as_str().unwrap())
+            .collect();
+        assert!(labels.contains(&"description") && !labels.contains(&"name"));
+        for (line, character) in [(1, 6), (4, 0)] {
+            let reply = request(
+                &mut server,
+                "textDocument/completion",
+                at(&uri, line, character),
+            );
+            assert_eq!(reply["result"], Value::Null);
+        }
+    }
+
+    #[test]
+    fn hover_and_definition_follow_reference_links() {
+        let dir = TempDir::new();
+        let api = dir.write("zod/references/api.md", "# API\n\nz.object()\n");
+        let mut server = server();
+        let uri = open(&mut server, &dir, SKILL);
+        let reply = request(&mut server, "textDocument/hover", at(&uri, 12, 10));
+        let value = reply["result"]["contents"]["value"].as_str().unwrap();
+        assert!(value.starts_with("**references/api.md**: 5 tokens when loaded"));
+        assert!(value.ends_with("# API\n\nz.object()"));
+        assert_eq!(
+            reply["result"]["range"],
+            json!({ "start": { "line": 12, "character": 8 }, "end": { "line": 12, "character": 25 } })
+        );
+        let reply = request(&mut server, "textDocument/definition", at(&uri, 12, 10));
+        assert_eq!(reply["result"]["uri"], path_to_uri(&api));
+        let reply = request(&mut server, "textDocument/definition", at(&uri, 12, 0));
+        assert_eq!(reply["result"], Value::Null);
+    }
+
+    #[test]
+    fn inlay_hints_show_levels_and_section_costs() {
+        let dir = TempDir::new();
+        let mut server = server();
+        let uri = open(&mut server, &dir, SKILL);
+        let reply = request(
+            &mut server,
+            "textDocument/inlayHint",
+            json!({ "textDocument": { "uri": uri } }),
+        );
+        let hints = reply["result"].as_array().unwrap();
+        assert_eq!(hints.len(), 4);
+        assert_eq!(hints[0]["position"], json!({ "line": 3, "character": 3 }));
+        assert!(hints[0]["label"].as_str().unwrap().starts_with("Level 1: "));
+        assert_eq!(hints[2]["position"], json!({ "line": 6, "character": 25 }));
+    }
+
+    #[test]
+    fn long_sections_can_be_moved_to_references() {
+        let dir = TempDir::new();
+        dir.write("zod/references/setup.md", "");
+        let mut server = server();
+        let long: String = (0..LONG_SECTION_LINES)
+            .map(|i| format!("step {i}\n"))
+            .collect();
+        let text = format!("{SKILL}\n## Setup\n\n{long}");
+        let uri = open(&mut server, &dir, &text);
+        let range = |line: usize| {
+            json!({
+                "textDocument": { "uri": uri },
+                "range": {
+                    "start": { "line": line, "character": 0 },
+                    "end": { "line": line, "character": 0 },
+                },
+            })
+        };
+        let reply = request(&mut server, "textDocument/codeAction", range(20));
+        let actions = reply["result"].as_array().unwrap();
+        assert_eq!(actions.len(), 1);
+        assert_eq!(
+            actions[0]["title"],
+            "Move \"Setup\" to references/setup-2.md"
+        );
+        let changes = &actions[0]["edit"]["documentChanges"];
+        assert!(changes[1]["edits"][0]["newText"]
+            .as_str()
+            .unwrap()
+            .starts_with("# Setup\n\nstep 0\n"));
+        assert_eq!(
+            changes[2]["edits"][0]["newText"],
+            "\nSee [references/setup-2.md](references/setup-2.md).\n"
+        );
+        let reply = request(&mut server, "textDocument/codeAction", range(8));
+        assert_eq!(reply["result"], json!([]));
+    }
+
+    #[test]
+    fn requests_get_json_rpc_errors_and_exit_stops_serving() {
+        let mut server = server();
+        let code = |reply: &Value| reply["error"]["code"].as_i64();
+        assert_eq!(code(&server.handle("{")[0]), Some(PARSE_ERROR));
+        assert_eq!(
+            code(&server.handle(r#"{"id":1}"#)[0]),
+            Some(INVALID_REQUEST)
+        );
+        assert!(server.handle(r#"{"id":1,"result":null}"#).is_empty());
+        let reply = request(&mut server, "workspace/symbol", json!({}));
+        assert_eq!(code(&reply), Some(METHOD_NOT_FOUND));
+        let reply = request(&mut server, "textDocument/hover", json!({}));
+        assert_eq!(code(&reply), Some(INVALID_PARAMS));
+        let reply = request(
+            &mut server,
+            "textDocument/hover",
+            at("file:///x/SKILL.md", 0, 0),
+        );
+        assert_eq!(reply["result"], Value::Null);
+
+        let frame = |body: &str| format!("Content-Length: {}\r\n\r\n{body}", body.len());
+        let input = [
+            frame(r#"{"jsonrpc":"2.0","id":1,"method":"shutdown"}"#),
+            frame(r#"{"jsonrpc":"2.0","method":"exit"}"#),
+            frame(r#"{"jsonrpc":"2.0","id":2,"method":"shutdown"}"#),
+        ]
+        .concat();
+        let mut output = Vec::new();
+        server.serve(input.as_bytes(), &mut output).unwrap();
+        let output = String::from_utf8(output).unwrap();
+        assert_eq!(output.matches("Content-Length").count(), 1);
+        assert!(output.ends_with(r#""result":null}"#));
+    }
+}
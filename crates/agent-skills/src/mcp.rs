@@ -0,0 +1,472 @@
+//! A [Model Context Protocol](https://modelcontextprotocol.io) server over
+//! stdio, for hosts that only speak MCP.
+//!
+//! Instead of one tool per capability, which would put every schema in
+//! context up front, the server advertises four small tools that follow the
+//! progressive-disclosure levels of a [`SkillLoader`]:
+//!
+//! - `list_skills`: name and description of every skill (Level 1).
+//! - `load_skill`: the instructions of one skill (Level 2).
+//! - `read_reference` and `run_script`: files and script output from a
+//!   skill directory (Level 3).
+//!
+//! Messages are newline-delimited JSON-RPC 2.0, as the stdio transport
+//! specifies.
+
+use std::io::{BufRead, Write};
+use std::path::Path;
+
+use serde_json::{json, Value};
+
+use crate::loader::SkillLoader;
+use crate::project::{SkillDir, REFERENCES_DIR, SCRIPTS_DIR};
+use crate::{Error, Result};
+
+/// The newest protocol revision the server implements.
+pub const PROTOCOL_VERSION: &str = "2025-06-18";
+
+/// Revisions accepted from a client's `initialize`; anything else is
+/// answered with [`PROTOCOL_VERSION`].
+const SUPPORTED_VERSIONS: &[&str] = &[PROTOCOL_VERSION, "2025-03-26", "2024-11-05"];
+
+const INSTRUCTIONS: &str = "Project skills are packaged instructions for specific tasks. \
+Call list_skills to see what is available, load_skill before following one, and \
+read_reference or run_script only when its instructions point to them.";
+
+// JSON-RPC error codes.
+pub(crate) const PARSE_ERROR: i64 = -32700;
+pub(crate) const INVALID_REQUEST: i64 = -32600;
+pub(crate) const METHOD_NOT_FOUND: i64 = -32601;
+pub(crate) const INVALID_PARAMS: i64 = -32602;
+
+/// Answers MCP requests from one client.
+pub struct Server {
+    loader: SkillLoader,
+    scripts: bool,
+}
+
+impl Server {
+    pub fn new(loader: SkillLoader) -> Self {
+        Self {
+            loader,
+            scripts: true,
+        }
+    }
+
+    /// Offer the `run_script` tool. On by default; without it clients can
+    /// read skills but not execute their code.
+    pub fn scripts(mut self, scripts: bool) -> Self {
+        self.scripts = scripts;
+        self
+    }
+
+    /// The loader, whose ledger records what the client has been sent.
+    pub fn loader(&self) -> &SkillLoader {
+        &self.loader
+    }
+
+    /// Read messages from `input` until it closes, writing one line to
+    /// `output` per reply.
+    pub fn serve(&mut self, input: impl BufRead, mut output: impl Write) -> Result<()> {
+        for line in input.lines() {
+            let line = line.map_err(|e| Error::io("stdin", e))?;
+            if line.trim().is_empty() {
+                continue;
+            }
+            if let Some(reply) = self.handle(&line) {
+                writeln!(output, "{reply}")
+                    .and_then(|()| output.flush())
+                    .map_err(|e| Error::io("stdout", e))?;
+            }
+        }
+        Ok(())
+    }
+
+    /// The reply to one JSON-RPC message; notifications and responses get
+    /// none.
+    pub fn handle(&mut self, message: &str) -> Option<Value> {
+        let message: Value = match serde_json::from_str(message) {
+            Ok(message) => message,
+            Err(e) => return Some(failure(Value::Null, PARSE_ERROR, &e.to_string())),
+        };
+        let id = message.get("id").cloned();
+        let Some(method) = message.get("method").and_then(Value::as_str) else {
+            let is_response = message.get("result").is_some() || message.get("error").is_some();
+            return match is_response {
+                true => None,
+                false => Some(failure(
+                    id.unwrap_or(Value::Null),
+                    INVALID_REQUEST,
+                    "not a JSON-RPC request",
+                )),
+            };
+        };
+        // Notifications, `notifications/initialized` included, need no answer.
+        let id = id?;
+        let params = message.get("params").cloned().unwrap_or(json!({}));
+        let result = match method {
+            "initialize" => Ok(self.initialize(&params)),
+            "ping" => Ok(json!({})),
+            "tools/list" => Ok(json!({ "tools": self.tools() })),
+            "tools/call" => self.call(&params),
+            other => Err((METHOD_NOT_FOUND, format!("unknown method `{other}`"))),
+        };
+        Some(match result {
+            Ok(result) => json!({ "jsonrpc": "2.0", "id": id, "result": result }),
+            Err((code, message)) => failure(id, code, &message),
+        })
+    }
+
+    fn initialize(&self, params: &Value) -> Value {
+        let requested = params.get("protocolVersion").and_then(Value::as_str);
+        let version = requested
+            .filter(|v| SUPPORTED_VERSIONS.contains(v))
+            .unwrap_or(PROTOCOL_VERSION);
+        json!({
+            "protocolVersion": version,
+            "capabilities": { "tools": {} },
+            "serverInfo": {
+                "name": env!("CARGO_PKG_NAME"),
+                "version": env!("CARGO_PKG_VERSION"),
+            },
+            "instructions": INSTRUCTIONS,
+        })
+    }
+
+    fn tools(&self) -> Vec<Value> {
+        let mut tools = vec![
+            tool(
+                "list_skills",
+                "List available skills with a one-line description of when to use each.",
+                json!({}),
+                &[],
+            ),
+            tool(
+                "load_skill",
+                "Load the full instructions of a skill.",
+                json!({ "name": { "type": "string" } }),
+                &["name"],
+            ),
+            tool(
+                "read_reference",
+                "Read a file from a skill directory, such as references/examples.md.",
+                json!({ "skill": { "type": "string" }, "path": { "type": "string" } }),
+                &["skill", "path"],
+            ),
+        ];
+        if self.scripts {
+            tools.push(tool(
+                "run_script",
+                "Run a script from a skill's scripts/ directory and return its output.",
+                json!({
+                    "skill": { "type": "string" },
+                    "script": { "type": "string" },
+                    "args": { "type": "array", "items": { "type": "string" } },
+                }),
+                &["skill", "script"],
+            ));
+        }
+        tools
+    }
+
+    fn call(&mut self, params: &Value) -> std::result::Result<Value, (i64, String)> {
+        let name = params
+            .get("name")
+            .and_then(Value::as_str)
+            .ok_or((INVALID_PARAMS, "missing tool name".to_string()))?;
+        let args = params.get("arguments").cloned().unwrap_or(json!({}));
+        let outcome = match name {
+            "list_skills" => Ok(self.list_skills()),
+            "load_skill" => self.load_skill(&args),
+            "read_reference" => self.read_reference(&args),
+            "run_script" if self.scripts => self.run_script(&args),
+            other => return Err((INVALID_PARAMS, format!("unknown tool `{other}`"))),
+        };
+        // Failures of the tool itself go back to the model, not the host.
+        let (text, is_error) = match outcome {
+            Ok(output) => output,
+            Err(message) => (message, true),
+        };
+        Ok(json!({
+            "content": [{ "type": "text", "text": text }],
+            "isError": is_error,
+        }))
+    }
+
+    fn list_skills(&mut self) -> (String, bool) {
+        let catalog = self.loader.metadata_catalog();
+        if catalog.is_empty() {
+            return ("No skills are installed.".to_string(), false);
+        }
+        let mut text = String::new();
+        for skill in catalog {
+            text.push_str(&format!("- {}: {}", skill.name, skill.description));
+            if !skill.globs.is_empty() {
+                text.push_str(&format!(" (files: {})", skill.globs.join(", ")));
+            }
+            text.push('\n');
+        }
+        (text, false)
+    }
+
+    /// The `SKILL.md` body, after the prerequisites the client has not
+    /// been sent yet, followed by the files the Level 3 tools accept so the
+    /// model does not have to guess paths.
+    fn load_skill(&mut self, args: &Value) -> ToolOutcome {
+        let name = argument(args, "name")?;
+        let loaded = self.loader.load_composed(name).map_err(message)?;
+        let (own, prerequisites) = loaded
+            .split_last()
+            .expect("the requested skill is always loaded");
+        let mut text = String::new();
+        if !prerequisites.is_empty() {
+            let names: Vec<String> = prerequisites
+                .iter()
+                .map(|p| format!("`{}`", p.skill))
+                .collect();
+            text.push_str(&format!(
+                "`{name}` builds on {}, loaded first.\n\n",
+                names.join(", ")
+            ));
+            for prerequisite in prerequisites {
+                text.push_str(prerequisite.text.trim());
+                text.push_str("\n\n---\n\n");
+            }
+        }
+        text.push_str(own.text.trim());
+        let dir = match self.loader.metadata(name) {
+            Some(metadata) => SkillDir::new(&metadata.path),
+            None => return Ok((text, false)),
+        };
+        let mut listed = |subdir: &str, heading: &str| -> std::result::Result<(), String> {
+            let files = dir.files_in(subdir).map_err(message)?;
+            if files.is_empty() {
+                return Ok(());
+            }
+            text.truncate(text.trim_end().len());
+            text.push_str(&format!("\n\n{heading}:\n"));
+            for file in files {
+                let relative = file.strip_prefix(&dir.path).unwrap_or(&file);
+                text.push_str(&format!("- {}\n", crate::lock::slash_path(relative)));
+            }
+            Ok(())
+        };
+        listed(REFERENCES_DIR, "References (read_reference)")?;
+        if self.scripts {
+            listed(SCRIPTS_DIR, "Scripts (run_script)")?;
+        }
+        Ok((text.trim_end().to_string(), false))
+    }
+
+    fn read_reference(&mut self, args: &Value) -> ToolOutcome {
+        let skill = argument(args, "skill")?;
+        let path = argument(args, "path")?;
+        let text = self
+            .loader
+            .load_reference(skill, Path::new(path))
+            .map_err(message)?;
+        Ok((text.to_string(), false))
+    }
+
+    fn run_script(&mut self, args: &Value) -> ToolOutcome {
+        let skill = argument(args, "skill")?;
+        let script = argument(args, "script")?;
+        let script_args = match args.get("args") {
+            None | Some(Value::Null) => Vec::new(),
+            Some(value) => serde_json::from_value::<Vec<String>>(value.clone())
+                .map_err(|_| "`args` must be an array of strings".to_string())?,
+        };
+        let output = self
+            .loader
+            .run_script(skill, script, &script_args)
+            .map_err(message)?;
+        let mut text = output.text();
+        if output.timed_out {
+            text.push_str("\n[timed out]");
+        } else if let Some(code) = output.code.filter(|&c| c != 0) {
+            text.push_str(&format!("\n[exit code {code}]"));
+        }
+        Ok((text, !output.success()))
+    }
+}
+
+/// Tool output and whether it reports a failure, or an error message.
+type ToolOutcome = std::result::Result<(String, bool), String>;
+
+fn argument<'a>(args: &'a Value, key: &str) -> std::result::Result<&'a str, String> {
+    args.get(key)
+        .and_then(Value::as_str)
+        .ok_or_else(|| format!("missing string argument `{key}`"))
+}
+
+fn message(error: Error) -> String {
+    error.to_string()
+}
+
+fn tool(name: &str, description: &str, properties: Value, required: &[&str]) -> Value {
+    json!({
+        "name": name,
+        "description": description,
+        "inputSchema": {
+            "type": "object",
+            "properties": properties,
+            "required": required,
+        },
+    })
+}
+
+pub(crate) fn failure(id: Value, code: i64, message: &str) -> Value {
+    json!({
+        "jsonrpc": "2.0",
+        "id": id,
+        "error": { "code": code, "message": message },
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::TempDir;
+
+    fn server(dir: &TempDir) -> Server {
+        dir.write(
+            "zod/SKILL.md",
+            "---\nname: zod\ndescription: Validate input with Zod\nglobs: ['*.ts']\n---\n# Zod\n",
+        );
+        dir.write("zod/references/api.md", "The API.\n");
+        dir.write("zod/scripts/check.sh", "#!/bin/sh\n");
+        Server::new(SkillLoader::open(dir.path()).unwrap())
+    }
+
+    fn call(server: &mut Server, tool: &str, arguments: Value) -> Value {
+        let request = json!({
+            "jsonrpc": "2.0",
+            "id": 1,
+            "method": "tools/call",
+            "params": { "name": tool, "arguments": arguments },
+        });
+        server.handle(&request.to_string()).unwrap()
+    }
+
+    fn text(reply: &Value) -> &str {
+        reply["result"]["content"][0]["text"].as_str().unwrap()
+    }
+
+    #[test]
+    fn initialize_negotiates_a_supported_version() {
+        let dir = TempDir::new();
+        let mut server = server(&dir);
+        let reply = server
+            .handle(r#"{"jsonrpc":"2.0","id":1,"method":"initialize","params":{"protocolVersion":"2024-11-05"}}"#)
+            .unwrap();
+        assert_eq!(reply["result"]["protocolVersion"], "2024-11-05");
+        let reply = server
+            .handle(r#"{"jsonrpc":"2.0","id":2,"method":"initialize","params":{"protocolVersion":"1999-01-01"}}"#)
+            .unwrap();
+        assert_eq!(reply["result"]["protocolVersion"], PROTOCOL_VERSION);
+    }
+
+    #[test]
+    fn notifications_and_responses_get_no_reply() {
+        let dir = TempDir::new();
+        let mut server = server(&dir);
+        assert_eq!(
+            server.handle(r#"{"jsonrpc":"2.0","method":"notifications/initialized"}"#),
+            None
+        );
+        assert_eq!(
+            server.handle(r#"{"jsonrpc":"2.0","id":1,"result":{}}"#),
+            None
+        );
+    }
+
+    #[test]
+    fn malformed_messages_get_json_rpc_errors() {
+        let dir = TempDir::new();
+        let mut server = server(&dir);
+        let code = |reply: Option<Value>| reply.unwrap()["error"]["code"].as_i64();
+        assert_eq!(code(server.handle("{")), Some(PARSE_ERROR));
+        assert_eq!(code(server.handle(r#"{"id":1}"#)), Some(INVALID_REQUEST));
+        assert_eq!(
+            code(server.handle(r#"{"id":1,"method":"resources/list"}"#)),
+            Some(METHOD_NOT_FOUND)
+        );
+    }
+
+    #[test]
+    fn run_script_is_only_offered_when_enabled() {
+        let dir = TempDir::new();
+        let mut server = server(&dir).scripts(false);
+        let reply = server
+            .handle(r#"{"jsonrpc":"2.0","id":1,"method":"tools/list"}"#)
+            .unwrap();
+        let names: Vec<_> = reply["result"]["tools"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|t| t["name"].as_str().unwrap())
+            .collect();
+        assert_eq!(names, ["list_skills", "load_skill", "read_reference"]);
+        let reply = call(
+            &mut server,
+            "run_script",
+            json!({ "skill": "zod", "script": "check.sh" }),
+        );
+        assert_eq!(reply["error"]["code"], INVALID_PARAMS);
+    }
+
+    #[test]
+    fn tools_serve_the_three_levels() {
+        let dir = TempDir::new();
+        let mut server = server(&dir);
+        let reply = call(&mut server, "list_skills", json!({}));
+        assert_eq!(
+            text(&reply),
+            "- zod: Validate input with Zod (files: *.ts)\n"
+        );
+
+        let reply = call(&mut server, "load_skill", json!({ "name": "zod" }));
+        assert_eq!(reply["result"]["isError"], false);
+        assert_eq!(
+            text(&reply),
+            "# Zod\n\nReferences (read_reference):\n- references/api.md\n\nScripts (run_script):\n- scripts/check.sh"
+        );
+
+        let reply = call(
+            &mut server,
+            "read_reference",
+            json!({ "skill": "zod", "path": "references/api.md" }),
+        );
+        assert_eq!(text(&reply), "The API.\n");
+        assert_eq!(server.loader().ledger().events.len(), 3);
+    }
+
+    #[test]
+    fn tool_failures_go_back_to_the_model() {
+        let dir = TempDir::new();
+        let mut server = server(&dir);
+        let reply = call(
+            &mut server,
+            "read_reference",
+            json!({ "skill": "zod", "path": "../../etc/passwd" }),
+        );
+        assert_eq!(reply["result"]["isError"], true);
+        let reply = call(&mut server, "load_skill", json!({}));
+        assert_eq!(text(&reply), "missing string argument `name`");
+        let reply = call(&mut server, "load_skill", json!({ "name": "jest" }));
+        assert_eq!(reply["result"]["isError"], true);
+    }
+
+    #[test]
+    fn serve_answers_one_line_per_request() {
+        let dir = TempDir::new();
+        let mut server = server(&dir);
+        let input = "{\"jsonrpc\":\"2.0\",\"id\":1,\"method\":\"ping\"}\n\n{\"jsonrpc\":\"2.0\",\"method\":\"notifications/initialized\"}\n";
+        let mut output = Vec::new();
+        server.serve(input.as_bytes(), &mut output).unwrap();
+        assert_eq!(
+            String::from_utf8(output).unwrap(),
+            "{\"id\":1,\"jsonrpc\":\"2.0\",\"result\":{}}\n"
+        );
+    }
+}
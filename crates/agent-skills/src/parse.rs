@@ -0,0 +1,494 @@
+//! `SKILL.md` parsing.
+//!
+//! The file is split on its `---` delimiters by hand so that every error can
+//! be reported against the original file rather than the YAML fragment.
+
+use std::collections::BTreeMap;
+use std::ops::Range;
+
+use serde_yaml::{Mapping, Value};
+
+use crate::skill::{Frontmatter, Override, Section, Skill};
+use crate::span::{LineIndex, Span};
+
+/// Why a `SKILL.md` could not be parsed, and where.
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+#[error("{span}: {kind}")]
+pub struct ParseError {
+    pub kind: ParseErrorKind,
+    pub span: Span,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum ParseErrorKind {
+    #[error("missing YAML frontmatter, expected `---` on the first line")]
+    MissingFrontmatter,
+    #[error("frontmatter is never closed, expected a `---` line")]
+    UnterminatedFrontmatter,
+    #[error("invalid YAML: {0}")]
+    Yaml(String),
+    #[error("frontmatter must be a mapping of keys to values")]
+    NotAMapping,
+    #[error("frontmatter keys must be strings")]
+    NonStringKey,
+    #[error("`{key}` must be {expected}")]
+    InvalidField { key: String, expected: &'static str },
+}
+
+/// A line of the source without its terminator.
+pub(crate) struct Line<'a> {
+    pub start: usize,
+    /// Offset just past the line terminator.
+    pub next: usize,
+    pub text: &'a str,
+}
+
+pub(crate) fn lines(source: &str, from: usize) -> impl Iterator<Item = Line<'_>> {
+    let mut start = from;
+    std::iter::from_fn(move || {
+        if start >= source.len() {
+            return None;
+        }
+        let rest = &source[start..];
+        let (len, next) = match rest.find('\n') {
+            Some(i) => (i, start + i + 1),
+            None => (rest.len(), source.len()),
+        };
+        let text = rest[..len].strip_suffix('\r').unwrap_or(&rest[..len]);
+        let line = Line { start, next, text };
+        start = next;
+        Some(line)
+    })
+}
+
+pub(crate) fn parse(source: String) -> Result<Skill, ParseError> {
+    let Parts {
+        frontmatter,
+        frontmatter_span,
+        body_span,
+        sections,
+    } = parts(&source)?;
+    Ok(Skill {
+        source,
+        frontmatter,
+        frontmatter_span,
+        body_span,
+        sections,
+    })
+}
+
+struct Parts {
+    frontmatter: Frontmatter,
+    frontmatter_span: Span,
+    body_span: Span,
+    sections: Vec<Section>,
+}
+
+fn parts(source: &str) -> Result<Parts, ParseError> {
+    let index = LineIndex::new(source);
+    let error = |kind, range: Range<usize>| ParseError {
+        kind,
+        span: index.span(range),
+    };
+
+    let bom = if source.starts_with('\u{feff}') { 3 } else { 0 };
+    let mut iter = lines(source, bom);
+    let open = match iter.next() {
+        Some(line) if line.text.trim_end() == "---" => line,
+        Some(line) => {
+            return Err(error(
+                ParseErrorKind::MissingFrontmatter,
+                line.start..line.start + line.text.len(),
+            ))
+        }
+        None => return Err(error(ParseErrorKind::MissingFrontmatter, bom..bom)),
+    };
+    let Some(close) = iter.find(|line| matches!(line.text.trim_end(), "---" | "...")) else {
+        return Err(error(
+            ParseErrorKind::UnterminatedFrontmatter,
+            open.start..open.start + open.text.len(),
+        ));
+    };
+
+    let yaml_start = open.next;
+    let yaml = &source[yaml_start..close.start];
+    let spans = field_spans(yaml, yaml_start, &index);
+
+    let value: Value = serde_yaml::from_str(yaml).map_err(|e| {
+        let offset = e.location().map_or(yaml_start, |loc| {
+            let yaml_index = LineIndex::new(yaml);
+            yaml_start + yaml_index.offset(loc.line(), loc.column())
+        });
+        error(ParseErrorKind::Yaml(yaml_message(&e)), offset..offset)
+    })?;
+    let mapping = match value {
+        Value::Mapping(mapping) => mapping,
+        Value::Null => Mapping::new(),
+        _ => return Err(error(ParseErrorKind::NotAMapping, yaml_start..close.start)),
+    };
+
+    let mut frontmatter = Frontmatter {
+        spans,
+        ..Frontmatter::default()
+    };
+    for (key, value) in mapping {
+        let Value::String(key) = key else {
+            return Err(error(ParseErrorKind::NonStringKey, yaml_start..close.start));
+        };
+        let invalid = |expected| ParseError {
+            span: frontmatter
+                .span_of(&key)
+                .unwrap_or_else(|| index.span(yaml_start..close.start)),
+            kind: ParseErrorKind::InvalidField {
+                key: key.clone(),
+                expected,
+            },
+        };
+        match key.as_str() {
+            "name" => frontmatter.name = Some(string(value).ok_or_else(|| invalid("a string"))?),
+            "description" => {
+                frontmatter.description = Some(string(value).ok_or_else(|| invalid("a string"))?)
+            }
+            "globs" => {
+                frontmatter.globs =
+                    strings(value).ok_or_else(|| invalid("a string or a list of strings"))?
+            }
+            "depends_on" => {
+                frontmatter.depends_on =
+                    strings(value).ok_or_else(|| invalid("a skill name or a list of them"))?
+            }
+            "extends" => {
+                frontmatter.extends = Some(string(value).ok_or_else(|| invalid("a skill name"))?)
+            }
+            "override" => {
+                frontmatter.overrides = Some(overrides(value).ok_or_else(|| {
+                    invalid("`true`, `disable` or a mapping with a `disable` list of sections")
+                })?)
+            }
+            "version" => {
+                frontmatter.version = Some(scalar(value).ok_or_else(|| invalid("a version"))?)
+            }
+            "requires" => {
+                frontmatter.requires = requires(value)
+                    .ok_or_else(|| invalid("a mapping of skill names to version ranges"))?
+            }
+            _ => {
+                frontmatter.extra.insert(Value::String(key), value);
+            }
+        }
+    }
+
+    let body_start = close.next;
+    Ok(Parts {
+        frontmatter,
+        frontmatter_span: index.span(open.start..body_start),
+        body_span: index.span(body_start..source.len()),
+        sections: sections(source, body_start, &index),
+    })
+}
+
+fn string(value: Value) -> Option<String> {
+    match value {
+        Value::String(s) => Some(s),
+        _ => None,
+    }
+}
+
+/// A string, or a number YAML read from something like `version: 1.0`.
+fn scalar(value: Value) -> Option<String> {
+    match value {
+        Value::String(s) => Some(s),
+        Value::Number(n) => Some(n.to_string()),
+        _ => None,
+    }
+}
+
+fn requires(value: Value) -> Option<BTreeMap<String, String>> {
+    match value {
+        Value::Null => Some(BTreeMap::new()),
+        Value::Mapping(mapping) => mapping
+            .into_iter()
+            .map(|(name, range)| Some((string(name)?, scalar(range)?)))
+            .collect(),
+        _ => None,
+    }
+}
+
+fn overrides(value: Value) -> Option<Override> {
+    match value {
+        Value::Bool(true) => Some(Override::Merge {
+            disable: Vec::new(),
+        }),
+        Value::String(s) if s == "disable" => Some(Override::Disable),
+        Value::Mapping(mapping) => {
+            let mut disable = Vec::new();
+            for (key, value) in mapping {
+                match string(key)?.as_str() {
+                    "disable" => disable = strings(value)?,
+                    _ => return None,
+                }
+            }
+            Some(Override::Merge { disable })
+        }
+        _ => None,
+    }
+}
+
+/// A single string or a list of them.
+fn strings(value: Value) -> Option<Vec<String>> {
+    match value {
+        Value::Null => Some(Vec::new()),
+        Value::String(s) => Some(vec![s]),
+        Value::Sequence(seq) => seq.into_iter().map(string).collect(),
+        _ => None,
+    }
+}
+
+/// serde_yaml appends "at line N column M" to its messages, counted from the
+/// start of the YAML fragment; the location is reported through the span
+/// instead.
+fn yaml_message(error: &serde_yaml::Error) -> String {
+    let mut message = error.to_string();
+    while let Some(at) = message.find(" at line ") {
+        let rest = &message[at + " at line ".len()..];
+        let rest = rest.trim_start_matches(|c: char| c.is_ascii_digit());
+        let rest = rest.strip_prefix(" column ").unwrap_or(rest);
+        let rest = rest.trim_start_matches(|c: char| c.is_ascii_digit());
+        message = format!("{}{}", &message[..at], rest);
+    }
+    message
+}
+
+/// Locate top-level `key:` entries so later errors and lints can point at
+/// them. An entry runs until the next top-level key, minus trailing blank
+/// and comment lines.
+fn field_spans(yaml: &str, base: usize, index: &LineIndex) -> Vec<(String, Span)> {
+    let mut entries: Vec<(String, usize, usize)> = Vec::new();
+    for line in lines(yaml, 0) {
+        let text = line.text;
+        let trimmed = text.trim();
+        if trimmed.is_empty() || trimmed.starts_with('#') {
+            continue;
+        }
+        let top_level = !text.starts_with([' ', '\t', '-']);
+        match text.find(':').filter(|_| top_level) {
+            Some(colon) => {
+                let key = text[..colon].trim().trim_matches(['"', '\'']).to_string();
+                entries.push((key, line.start, line.start + text.len()));
+            }
+            None => {
+                if let Some(last) = entries.last_mut() {
+                    last.2 = line.start + text.len();
+                }
+            }
+        }
+    }
+    entries
+        .into_iter()
+        .map(|(key, start, end)| (key, index.span(base + start..base + end)))
+        .collect()
+}
+
+/// Lines of markdown in `range`, each flagged with whether it belongs to a
+/// fenced code block (fence lines included).
+pub(crate) fn markdown_lines(
+    source: &str,
+    range: Range<usize>,
+) -> impl Iterator<Item = (Line<'_>, bool)> {
+    let mut fence: Option<(char, usize)> = None;
+    lines(source, range.start)
+        .take_while(move |line| line.start < range.end)
+        .map(move |line| {
+            let text = line.text;
+            let indent = text.len() - text.trim_start_matches(' ').len();
+            let trimmed = &text[indent..];
+            let marker = fence_marker(trimmed).filter(|_| indent <= 3);
+            let code = match (fence, marker) {
+                (None, Some(marker)) => {
+                    fence = Some(marker);
+                    true
+                }
+                (Some((ch, len)), Some((marker_ch, marker_len)))
+                    if ch == marker_ch
+                        && marker_len >= len
+                        && trimmed.trim_start_matches(ch).trim().is_empty() =>
+                {
+                    fence = None;
+                    true
+                }
+                (Some(_), _) => true,
+                (None, None) => false,
+            };
+            (line, code)
+        })
+}
+
+/// ATX headings of the body, skipping fenced code blocks.
+pub(crate) fn sections(source: &str, body_start: usize, index: &LineIndex) -> Vec<Section> {
+    let mut headings: Vec<(u8, String, usize, usize)> = Vec::new();
+    for (line, code) in markdown_lines(source, body_start..source.len()) {
+        let text = line.text;
+        let indent = text.len() - text.trim_start_matches(' ').len();
+        if code || indent > 3 {
+            continue;
+        }
+        if let Some((level, title)) = heading(&text[indent..]) {
+            headings.push((level, title, line.start, line.next));
+        }
+    }
+
+    let end = source.len();
+    headings
+        .iter()
+        .enumerate()
+        .map(|(i, (level, title, start, content_start))| {
+            let section_end = headings[i + 1..]
+                .iter()
+                .find(|(next_level, ..)| next_level <= level)
+                .map_or(end, |(_, _, next_start, _)| *next_start);
+            let heading_end = source[*start..*content_start].trim_end().len() + start;
+            Section {
+                level: *level,
+                title: title.clone(),
+                heading: index.span(*start..heading_end),
+                content: index.span(*content_start..section_end),
+                span: index.span(*start..section_end),
+            }
+        })
+        .collect()
+}
+
+fn fence_marker(line: &str) -> Option<(char, usize)> {
+    let ch = line.chars().next().filter(|c| *c == '`' || *c == '~')?;
+    let len = line.len() - line.trim_start_matches(ch).len();
+    (len >= 3).then_some((ch, len))
+}
+
+pub(crate) fn heading(line: &str) -> Option<(u8, String)> {
+    let hashes = line.len() - line.trim_start_matches('#').len();
+    if !(1..=6).contains(&hashes) {
+        return None;
+    }
+    let rest = &line[hashes..];
+    if !rest.is_empty() && !rest.starts_with([' ', '\t']) {
+        return None;
+    }
+    let title = rest.trim();
+    let title = match title.trim_end_matches('#') {
+        stripped if stripped.is_empty() || stripped.ends_with([' ', '\t']) => stripped.trim_end(),
+        _ => title,
+    };
+    Some((hashes as u8, title.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn error(source: &str) -> ParseError {
+        Skill::parse(source).expect_err("source should not parse")
+    }
+
+    #[test]
+    fn parses_known_keys_and_sections() {
+        let skill = Skill::parse(
+            "---\nname: pnpm-workflow\ndescription: Use pnpm\nglobs: \"*.ts\"\n---\n# pnpm\n\n## Install\n\nRun it.\n\n### Flags\n\n## Notes\n",
+        )
+        .unwrap();
+        assert_eq!(skill.name(), Some("pnpm-workflow"));
+        assert_eq!(skill.description(), Some("Use pnpm"));
+        assert_eq!(skill.frontmatter().globs, ["*.ts"]);
+        let titles: Vec<(u8, &str)> = skill
+            .sections()
+            .iter()
+            .map(|s| (s.level, s.title.as_str()))
+            .collect();
+        assert_eq!(
+            titles,
+            [(1, "pnpm"), (2, "Install"), (3, "Flags"), (2, "Notes")]
+        );
+        let install = skill.section("install").unwrap();
+        assert_eq!(skill.section_text(install), "\nRun it.\n\n### Flags\n\n");
+    }
+
+    #[test]
+    fn headings_inside_code_fences_are_not_sections() {
+        let skill = Skill::parse("---\n---\n## Real\n\n```md\n## Fake\n```\n").unwrap();
+        assert_eq!(skill.sections().len(), 1);
+        assert_eq!(skill.sections()[0].title, "Real");
+    }
+
+    #[test]
+    fn missing_frontmatter_points_at_the_first_line() {
+        let e = error("# Title\n");
+        assert_eq!(e.kind, ParseErrorKind::MissingFrontmatter);
+        assert_eq!((e.span.start.line, e.span.start.column), (1, 1));
+        assert_eq!(e.span.end.column, 8);
+    }
+
+    #[test]
+    fn unterminated_frontmatter_points_at_the_opening_delimiter() {
+        let e = error("---\nname: a\n");
+        assert_eq!(e.kind, ParseErrorKind::UnterminatedFrontmatter);
+        assert_eq!(e.span.start.line, 1);
+    }
+
+    #[test]
+    fn yaml_errors_are_located_in_the_file_not_the_fragment() {
+        let e = error("---\nname: a\ndescription: [unclosed\n---\n");
+        let ParseErrorKind::Yaml(message) = &e.kind else {
+            panic!("expected a YAML error, got {:?}", e.kind);
+        };
+        assert!(!message.contains(" at line "), "{message}");
+        assert!(e.span.start.line >= 3, "{}", e.span);
+    }
+
+    #[test]
+    fn invalid_field_points_at_its_key() {
+        let e = error("---\nname: a\nglobs: {a: 1}\n---\n");
+        assert_eq!(
+            e.kind,
+            ParseErrorKind::InvalidField {
+                key: "globs".into(),
+                expected: "a string or a list of strings",
+            }
+        );
+        assert_eq!((e.span.start.line, e.span.start.column), (3, 1));
+    }
+
+    #[test]
+    fn non_mapping_frontmatter_is_rejected() {
+        assert_eq!(error("---\n- a\n---\n").kind, ParseErrorKind::NotAMapping);
+    }
+
+    #[test]
+    fn unknown_keys_survive_a_round_trip() {
+        let source =
+            "---\nname: a\ndescription: b\nauthor: someone\nlint:\n  allow: [examples]\n---\n# A\n";
+        let skill = Skill::parse(source).unwrap();
+        assert_eq!(
+            skill.frontmatter().get("author").and_then(|v| v.as_str()),
+            Some("someone")
+        );
+        let again = Skill::parse(skill.to_markdown()).unwrap();
+        assert_eq!(again.frontmatter().extra, skill.frontmatter().extra);
+        assert_eq!(again.body().trim_start(), skill.body());
+        let keys: Vec<&str> = again.frontmatter().keys().collect();
+        assert_eq!(keys, ["name", "description", "author", "lint"]);
+    }
+
+    #[test]
+    fn field_spans_cover_multi_line_values() {
+        let source =
+            "---\nname: a\nglobs:\n  - \"*.ts\"\n  - \"*.tsx\"\n\n# note\ndescription: b\n---\n";
+        let skill = Skill::parse(source).unwrap();
+        let span = skill.frontmatter().span_of("globs").unwrap();
+        assert_eq!(span.text(source), "globs:\n  - \"*.ts\"\n  - \"*.tsx\"");
+    }
+
+    #[test]
+    fn byte_order_mark_is_skipped() {
+        let skill = Skill::parse("\u{feff}---\nname: a\n---\n").unwrap();
+        assert_eq!(skill.name(), Some("a"));
+    }
+}
@@ -0,0 +1,215 @@
+//! Locating skills inside a project's `.claude/` directory.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::config::{Config, CONFIG_FILE};
+use crate::lock::LOCK_FILE;
+use crate::secrets::BASELINE_FILE;
+use crate::skill::{Skill, SKILL_FILE};
+use crate::{Error, Result};
+
+/// Directory holding the global `SKILL.md` and the `skills/` tree.
+pub const CLAUDE_DIR: &str = ".claude";
+/// Directory under [`CLAUDE_DIR`] with one subdirectory per skill.
+pub const SKILLS_DIR: &str = "skills";
+/// Optional Level 3 documentation inside a skill directory.
+pub const REFERENCES_DIR: &str = "references";
+/// Optional Level 3 executables inside a skill directory.
+pub const SCRIPTS_DIR: &str = "scripts";
+
+/// A project laid out as described in the README:
+///
+/// ```text
+/// .claude/
+/// ├── SKILL.md
+/// └── skills/
+///     └── <name>/SKILL.md
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Project {
+    root: PathBuf,
+}
+
+impl Project {
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        Self { root: root.into() }
+    }
+
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    /// Settings from `skills.toml`, or the defaults when there is none.
+    pub fn config(&self) -> Result<Config> {
+        Config::load(&self.join(CONFIG_FILE))
+    }
+
+    /// `path` relative to the project root, without a leading `./` when the
+    /// root is the current directory.
+    pub fn join(&self, path: impl AsRef<Path>) -> PathBuf {
+        match self.root == Path::new(".") {
+            true => path.as_ref().to_path_buf(),
+            false => self.root.join(path),
+        }
+    }
+
+    /// The `skills.lock` recording installed skills.
+    pub fn lock_file(&self) -> PathBuf {
+        self.join(LOCK_FILE)
+    }
+
+    /// The `skills.secrets.toml` of accepted secret scan findings.
+    pub fn secrets_baseline(&self) -> PathBuf {
+        self.join(BASELINE_FILE)
+    }
+
+    pub fn claude_dir(&self) -> PathBuf {
+        self.join(CLAUDE_DIR)
+    }
+
+    pub fn skills_dir(&self) -> PathBuf {
+        self.claude_dir().join(SKILLS_DIR)
+    }
+
+    /// The project-wide `.claude/SKILL.md`.
+    pub fn global_skill_file(&self) -> PathBuf {
+        self.claude_dir().join(SKILL_FILE)
+    }
+
+    /// Every directory under `.claude/skills`, sorted by name. A project
+    /// without a skills directory has no skills rather than an error.
+    pub fn skills(&self) -> Result<Vec<SkillDir>> {
+        skill_dirs(&self.skills_dir())
+    }
+
+    pub fn skill(&self, name: &str) -> SkillDir {
+        SkillDir::new(self.skills_dir().join(name))
+    }
+}
+
+/// Every subdirectory of `skills_dir`, sorted by name. Hidden directories
+/// are skipped.
+pub fn skill_dirs(skills_dir: &Path) -> Result<Vec<SkillDir>> {
+    let entries = match fs::read_dir(skills_dir) {
+        Ok(entries) => entries,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(Error::io(skills_dir, e)),
+    };
+    let mut dirs = Vec::new();
+    for entry in entries {
+        let entry = entry.map_err(|e| Error::io(skills_dir, e))?;
+        let path = entry.path();
+        let hidden = entry.file_name().to_string_lossy().starts_with('.');
+        if path.is_dir() && !hidden {
+            dirs.push(SkillDir::new(path));
+        }
+    }
+    dirs.sort_by(|a, b| a.name.cmp(&b.name));
+    Ok(dirs)
+}
+
+/// A skill directory, which may or may not contain a `SKILL.md`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SkillDir {
+    /// Directory name, which the README expects to match the skill `name`.
+    pub name: String,
+    pub path: PathBuf,
+}
+
+impl SkillDir {
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        let path = path.into();
+        let name = path
+            .file_name()
+            .map(|n| n.to_string_lossy().into_owned())
+            .unwrap_or_default();
+        Self { name, path }
+    }
+
+    pub fn skill_file(&self) -> PathBuf {
+        self.path.join(SKILL_FILE)
+    }
+
+    /// The name `skill`, read from this directory, is known by: its
+    /// frontmatter `name`, or the directory name when it declares none.
+    /// Graphs, the loader and lint all key skills by it.
+    pub fn skill_name<'a>(&'a self, skill: &'a Skill) -> &'a str {
+        skill.name().unwrap_or(&self.name)
+    }
+
+    pub fn references_dir(&self) -> PathBuf {
+        self.path.join(REFERENCES_DIR)
+    }
+
+    pub fn scripts_dir(&self) -> PathBuf {
+        self.path.join(SCRIPTS_DIR)
+    }
+
+    /// Regular files under `dir` (relative to the skill), recursively and
+    /// sorted. Missing directories yield nothing.
+    pub fn files_in(&self, dir: &str) -> Result<Vec<PathBuf>> {
+        let mut files = Vec::new();
+        collect_files(&self.path.join(dir), &mut files)?;
+        files.sort();
+        Ok(files)
+    }
+}
+
+fn collect_files(dir: &Path, out: &mut Vec<PathBuf>) -> Result<()> {
+    let entries = match fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(()),
+        Err(e) => return Err(Error::io(dir, e)),
+    };
+    for entry in entries {
+        let path = entry.map_err(|e| Error::io(dir, e))?.path();
+        if path.is_dir() {
+            collect_files(&path, out)?;
+        } else if path.is_file() {
+            out.push(path);
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::TempDir;
+
+    #[test]
+    fn skills_are_sorted_and_skip_hidden_directories_and_files() {
+        let dir = TempDir::new();
+        let project = Project::new(dir.path());
+        assert!(project.skills().unwrap().is_empty());
+        dir.write(".claude/skills/zod/SKILL.md", "");
+        dir.write(".claude/skills/astro/SKILL.md", "");
+        dir.write(".claude/skills/.cache/SKILL.md", "");
+        dir.write(".claude/skills/README.md", "");
+        let names: Vec<_> = project
+            .skills()
+            .unwrap()
+            .into_iter()
+            .map(|d| d.name)
+            .collect();
+        assert_eq!(names, ["astro", "zod"]);
+    }
+
+    #[test]
+    fn files_in_walks_subdirectories_in_order() {
+        let dir = TempDir::new();
+        let skill = SkillDir::new(dir.path());
+        assert!(skill.files_in(REFERENCES_DIR).unwrap().is_empty());
+        dir.write("references/b.md", "");
+        dir.write("references/a/deep.md", "");
+        let files = skill.files_in(REFERENCES_DIR).unwrap();
+        assert_eq!(
+            files,
+            [
+                dir.path().join("references/a/deep.md"),
+                dir.path().join("references/b.md")
+            ]
+        );
+    }
+}
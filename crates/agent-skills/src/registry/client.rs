@@ -0,0 +1,374 @@
+//! Talks to a registry [`Server`](super::Server) over plain HTTP, which is
+//! what an internal network usually offers; put a TLS-terminating proxy in
+//! front of the server to reach it from elsewhere.
+
+use std::fs;
+use std::io::{Read, Write};
+use std::net::TcpStream;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use serde::de::DeserializeOwned;
+use serde_json::Value;
+
+use super::server::SIGNATURE_HEADER;
+use super::{is_version, latest, Release};
+use crate::package::Package;
+use crate::signing::signature_path;
+use crate::skill::is_kebab_case;
+use crate::{Error, Result};
+
+const TIMEOUT: Duration = Duration::from_secs(30);
+
+/// A registry reached at an `http://host[:port][/prefix]` URL.
+#[derive(Debug, Clone)]
+pub struct Client {
+    url: String,
+    /// `host:port` to connect to.
+    authority: String,
+    host: String,
+    /// Path the API lives under, without a trailing `/`.
+    prefix: String,
+    token: Option<String>,
+}
+
+struct Response {
+    status: u16,
+    body: Vec<u8>,
+}
+
+impl Client {
+    pub fn new(url: &str) -> Result<Self> {
+        let invalid = |message: &str| Error::Registry {
+            url: url.to_string(),
+            message: message.to_string(),
+        };
+        let rest = match url.split_once("://") {
+            Some(("http", rest)) => rest,
+            Some(("https", _)) => {
+                return Err(invalid(
+                    "https is not supported; use http:// on the internal network",
+                ))
+            }
+            _ => return Err(invalid("expected an http:// URL")),
+        };
+        let (host, prefix) = match rest.find('/') {
+            Some(i) => (&rest[..i], rest[i..].trim_end_matches('/')),
+            None => (rest, ""),
+        };
+        if host.is_empty() {
+            return Err(invalid("no host"));
+        }
+        let authority = match host.rsplit_once(':') {
+            Some((_, port)) if port.parse::<u16>().is_ok() => host.to_string(),
+            _ => format!("{host}:80"),
+        };
+        Ok(Self {
+            url: url.trim_end_matches('/').to_string(),
+            authority,
+            host: host.to_string(),
+            prefix: prefix.to_string(),
+            token: None,
+        })
+    }
+
+    /// Token sent when publishing, for servers that require one.
+    pub fn token(mut self, token: Option<String>) -> Self {
+        self.token = token;
+        self
+    }
+
+    pub fn url(&self) -> &str {
+        &self.url
+    }
+
+    /// The latest release of every skill matching `query`.
+    pub fn search(&self, query: &str) -> Result<Vec<Release>> {
+        let path = format!("search?q={}", percent_encode(query));
+        self.json(&self.get(&path)?)
+    }
+
+    /// Every release of `name`, oldest first.
+    pub fn releases(&self, name: &str) -> Result<Vec<Release>> {
+        if !is_kebab_case(name) {
+            return Err(Error::InvalidName(name.to_string()));
+        }
+        self.json(&self.get(&format!("skills/{name}"))?)
+    }
+
+    /// Save a release, and its signature if it has one, into `dir` as
+    /// `<name>-<version>.skill`. Without a version the latest release that
+    /// is not a pre-release is fetched. The archive must be the listed
+    /// release: same name and version, and content matching its hash.
+    pub fn download(&self, name: &str, version: Option<&str>, dir: &Path) -> Result<PathBuf> {
+        if !is_kebab_case(name) {
+            return Err(Error::InvalidName(name.to_string()));
+        }
+        let releases = self.releases(name)?;
+        let release = match version {
+            Some(version) => releases.iter().find(|r| r.version == version),
+            None => latest(&releases),
+        }
+        .ok_or_else(|| Error::Registry {
+            url: self.url.clone(),
+            message: match version {
+                Some(version) => format!("no version {version} of `{name}`"),
+                None => format!("no release of `{name}`; name a pre-release to install it"),
+            },
+        })?;
+        // The registry picks the version, and it becomes part of a path.
+        if !is_version(&release.version) {
+            return Err(self.error(format!(
+                "`{}` is not a version of `{name}`",
+                release.version
+            )));
+        }
+        let path = format!("skills/{name}/{}", release.version);
+        let archive = self.get(&path)?;
+        let out = dir.join(format!("{name}-{}.skill", release.version));
+        // Nothing is saved unless it is the release that was listed.
+        let package = Package::from_bytes(&out, &archive.body)?;
+        let manifest = &package.manifest;
+        if manifest.name != name || manifest.version.as_deref() != Some(&release.version) {
+            return Err(self.error(format!(
+                "asked for `{name}` {}, got `{}` {}",
+                release.version,
+                manifest.name,
+                manifest.version.as_deref().unwrap_or("without a version")
+            )));
+        }
+        if manifest.hash != release.hash || package.content_hash() != release.hash {
+            return Err(self.error(format!(
+                "`{name}` {} does not match its release hash {}",
+                release.version, release.hash
+            )));
+        }
+        fs::write(&out, &archive.body).map_err(|e| Error::io(&out, e))?;
+        if release.signed {
+            let signature = self.get(&format!("{path}/signature"))?;
+            let sig_path = signature_path(&out);
+            fs::write(&sig_path, &signature.body).map_err(|e| Error::io(&sig_path, e))?;
+        }
+        Ok(out)
+    }
+
+    /// Upload the package at `archive`, with its `.sig` file if there is one.
+    pub fn publish(&self, archive: &Path) -> Result<Release> {
+        let body = fs::read(archive).map_err(|e| Error::io(archive, e))?;
+        let mut headers = vec![("Content-Type", "application/gzip".to_string())];
+        let sig_path = signature_path(archive);
+        if sig_path.is_file() {
+            let signature = fs::read(&sig_path).map_err(|e| Error::io(&sig_path, e))?;
+            headers.push((SIGNATURE_HEADER, STANDARD.encode(signature)));
+        }
+        if let Some(token) = &self.token {
+            headers.push(("Authorization", format!("Bearer {token}")));
+        }
+        let response = self.request("POST", "skills", &headers, &body)?;
+        self.json(&response)
+    }
+
+    fn get(&self, path: &str) -> Result<Response> {
+        self.request("GET", path, &[], &[])
+    }
+
+    fn json<T: DeserializeOwned>(&self, response: &Response) -> Result<T> {
+        serde_json::from_slice(&response.body).map_err(|e| self.error(e.to_string()))
+    }
+
+    fn error(&self, message: String) -> Error {
+        Error::Registry {
+            url: self.url.clone(),
+            message,
+        }
+    }
+
+    /// One HTTP/1.0 exchange, so the server closes the connection and
+    /// never chunks the reply.
+    fn request(
+        &self,
+        method: &str,
+        path: &str,
+        headers: &[(&str, String)],
+        body: &[u8],
+    ) -> Result<Response> {
+        let io = |e: std::io::Error| self.error(e.to_string());
+        let mut stream = TcpStream::connect(&self.authority).map_err(io)?;
+        stream.set_read_timeout(Some(TIMEOUT)).map_err(io)?;
+        stream.set_write_timeout(Some(TIMEOUT)).map_err(io)?;
+        let mut head = format!(
+            "{method} {}/api/v1/{path} HTTP/1.0\r\nHost: {}\r\nUser-Agent: skills/{}\r\nContent-Length: {}\r\n",
+            self.prefix,
+            self.host,
+            env!("CARGO_PKG_VERSION"),
+            body.len()
+        );
+        for (name, value) in headers {
+            head.push_str(&format!("{name}: {value}\r\n"));
+        }
+        head.push_str("\r\n");
+        stream.write_all(head.as_bytes()).map_err(io)?;
+        stream.write_all(body).map_err(io)?;
+        let mut raw = Vec::new();
+        stream.read_to_end(&mut raw).map_err(io)?;
+
+        let split = raw
+            .windows(4)
+            .position(|w| w == b"\r\n\r\n")
+            .ok_or_else(|| self.error("malformed HTTP response".to_string()))?;
+        let head = String::from_utf8_lossy(&raw[..split]);
+        let status = head
+            .split_whitespace()
+            .nth(1)
+            .and_then(|code| code.parse().ok())
+            .ok_or_else(|| self.error("malformed HTTP response".to_string()))?;
+        let response = Response {
+            status,
+            body: raw[split + 4..].to_vec(),
+        };
+        match response.status {
+            200..=299 => Ok(response),
+            status => {
+                let message = serde_json::from_slice::<Value>(&response.body)
+                    .ok()
+                    .and_then(|v| v["error"].as_str().map(str::to_string))
+                    .unwrap_or_else(|| format!("HTTP {status}"));
+                Err(self.error(message))
+            }
+        }
+    }
+}
+
+fn percent_encode(text: &str) -> String {
+    text.bytes()
+        .map(|b| match b {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' => {
+                (b as char).to_string()
+            }
+            b => format!("%{b:02X}"),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::net::TcpListener;
+
+    use super::*;
+    use crate::registry::test_archive;
+    use crate::test_support::TempDir;
+
+    /// A server that answers each request with `reply(path)`.
+    fn rogue(reply: impl Fn(&str) -> Vec<u8> + Send + 'static) -> Client {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        std::thread::spawn(move || {
+            for stream in listener.incoming() {
+                let mut stream = stream.unwrap();
+                let mut buf = [0; 4096];
+                let n = stream.read(&mut buf).unwrap_or(0);
+                let request = String::from_utf8_lossy(&buf[..n]);
+                let path = request.split(' ').nth(1).unwrap_or_default();
+                let body = reply(path);
+                let head = format!("HTTP/1.0 200 OK\r\nContent-Length: {}\r\n\r\n", body.len());
+                let _ = stream.write_all(head.as_bytes());
+                let _ = stream.write_all(&body);
+            }
+        });
+        Client::new(&url).unwrap()
+    }
+
+    /// A listing of one unsigned `deploy` release.
+    fn listing(version: &str, hash: &str) -> Vec<u8> {
+        let release = serde_json::json!([{
+            "name": "deploy", "version": version, "hash": hash, "signed": false,
+            "tokens": {"tokenizer": "chars", "metadata": 1, "instructions": 1, "on_demand": 0},
+        }]);
+        release.to_string().into_bytes()
+    }
+
+    #[test]
+    fn urls_are_split_into_authority_and_prefix() {
+        let client = Client::new("http://skills.internal:8080/registry/").unwrap();
+        assert_eq!(client.url(), "http://skills.internal:8080/registry");
+        assert_eq!(client.authority, "skills.internal:8080");
+        assert_eq!(client.host, "skills.internal:8080");
+        assert_eq!(client.prefix, "/registry");
+        assert_eq!(Client::new("http://host").unwrap().authority, "host:80");
+        for url in ["https://host", "ftp://host", "host", "http://"] {
+            assert!(Client::new(url).is_err(), "{url}");
+        }
+    }
+
+    #[test]
+    fn versions_from_the_registry_must_be_safe_paths() {
+        let client = rogue(|_| listing("../../escape", "sha256:00"));
+        let dir = TempDir::new();
+        let result = client.download("deploy", None, dir.path());
+        match result {
+            Err(Error::Registry { message, .. }) => {
+                assert_eq!(message, "`../../escape` is not a version of `deploy`")
+            }
+            other => panic!("expected a registry error, got {other:?}"),
+        }
+        assert_eq!(fs::read_dir(dir.path()).unwrap().count(), 0);
+    }
+
+    #[test]
+    fn downloads_must_be_the_listed_release() {
+        let dir = TempDir::new();
+        let wanted = test_archive(&dir, "deploy", "1.0.0");
+        let hash = Package::from_bytes(Path::new("x"), &wanted)
+            .unwrap()
+            .manifest
+            .hash;
+        let other = test_archive(&dir, "deploy", "2.0.0");
+        let out = dir.path().join("downloads");
+        fs::create_dir(&out).unwrap();
+        let serve = |hash: String, archive: Vec<u8>| {
+            rogue(move |path| match path {
+                "/api/v1/skills/deploy" => listing("1.0.0", &hash),
+                _ => archive.clone(),
+            })
+        };
+        let message = |result: Result<PathBuf>| match result {
+            Err(Error::Registry { message, .. }) => message,
+            other => panic!("expected a registry error, got {other:?}"),
+        };
+
+        let client = serve(hash.clone(), other);
+        assert_eq!(
+            message(client.download("deploy", None, &out)),
+            "asked for `deploy` 1.0.0, got `deploy` 2.0.0"
+        );
+        let client = serve("sha256:00".into(), wanted.clone());
+        assert_eq!(
+            message(client.download("deploy", None, &out)),
+            "`deploy` 1.0.0 does not match its release hash sha256:00"
+        );
+        let client = serve(hash.clone(), b"not an archive".to_vec());
+        assert!(client.download("deploy", None, &out).is_err());
+        assert_eq!(fs::read_dir(&out).unwrap().count(), 0);
+
+        let client = serve(hash, wanted.clone());
+        let path = client.download("deploy", None, &out).unwrap();
+        assert_eq!(fs::read(path).unwrap(), wanted);
+    }
+
+    #[test]
+    fn names_are_checked_before_any_request() {
+        let client = Client::new("http://127.0.0.1:9").unwrap();
+        let dir = TempDir::new();
+        assert!(matches!(
+            client.download("../deploy", None, dir.path()),
+            Err(Error::InvalidName(_))
+        ));
+    }
+
+    #[test]
+    fn queries_are_percent_encoded() {
+        assert_eq!(percent_encode("deploy to/é"), "deploy%20to%2F%C3%A9");
+    }
+}
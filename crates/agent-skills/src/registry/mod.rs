@@ -0,0 +1,474 @@
+//! A filesystem-backed registry of `.skill` packages, its HTTP server and a
+//! client for it.
+//!
+//! Published versions are immutable and stored as plain files, so a
+//! registry can be backed up or mirrored with `cp`:
+//!
+//! ```text
+//! <root>/<name>/<version>.skill      the package, as uploaded
+//! <root>/<name>/<version>.skill.sig  its signature, if it was signed
+//! <root>/<name>/<version>.toml       its manifest, read for listings
+//! ```
+//!
+//! The server speaks JSON under `/api/v1`:
+//!
+//! | Request                                 | Response                       |
+//! |-----------------------------------------|--------------------------------|
+//! | `GET  /api/v1/search?q=<words>`         | latest [`Release`] of matches  |
+//! | `GET  /api/v1/skills/<name>`            | every [`Release`] of a skill   |
+//! | `GET  /api/v1/skills/<name>/<version>`  | the `.skill` archive           |
+//! | `GET  /api/v1/skills/<name>/<version>/signature` | its `.sig` file       |
+//! | `POST /api/v1/skills`                   | publish the archive in the body |
+//!
+//! A signature is published alongside its package in the base64
+//! `Skill-Signature` header. Signatures are stored as given; whether they
+//! are trusted is decided by whoever unpacks the package.
+
+mod client;
+mod server;
+
+pub use client::Client;
+pub use server::Server;
+
+use std::cmp::Ordering;
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use semver::Version;
+use serde::{Deserialize, Serialize};
+
+use crate::install::TempDir;
+use crate::package::{Manifest, Package, Tokens, EXTENSION};
+use crate::signing::{signature_path, Signature};
+use crate::skill::is_kebab_case;
+use crate::{Error, Result};
+
+/// Environment variable holding the token publishers must present, on the
+/// server, and the token to present, in the client.
+pub const TOKEN_ENV: &str = "SKILLS_REGISTRY_TOKEN";
+
+/// Largest archive accepted for publishing.
+const MAX_UPLOAD: u64 = 16 * 1024 * 1024;
+
+/// One published version of a skill.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Release {
+    pub name: String,
+    pub version: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    /// [`crate::lock::content_hash`] of the skill.
+    pub hash: String,
+    /// Version ranges of other skills it needs.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub requires: BTreeMap<String, String>,
+    pub tokens: Tokens,
+    /// Whether a `.sig` file was published with it.
+    pub signed: bool,
+}
+
+/// The registry's directory tree.
+#[derive(Debug, Clone)]
+pub struct Store {
+    root: PathBuf,
+}
+
+impl Store {
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        Self { root: root.into() }
+    }
+
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    /// Check `archive` like `skills unpack` does and store it as a new
+    /// version. `signature` is the text of its `.sig` file.
+    pub fn publish(&self, archive: &[u8], signature: Option<&str>) -> Result<Release> {
+        let staging = TempDir::new()?;
+        let upload = staging.0.join(format!("upload.{EXTENSION}"));
+        fs::write(&upload, archive).map_err(|e| Error::io(&upload, e))?;
+        let manifest = Package::open(&upload)?.manifest;
+        let version = match &manifest.version {
+            Some(version) if is_version(version) => version.clone(),
+            Some(version) => {
+                return Err(Error::Package {
+                    path: upload,
+                    message: format!("`{version}` cannot be published as a version"),
+                })
+            }
+            None => {
+                return Err(Error::Package {
+                    path: upload,
+                    message: "no `version` in the SKILL.md frontmatter".to_string(),
+                })
+            }
+        };
+        if let Some(signature) = signature {
+            toml::from_str::<Signature>(signature).map_err(|e| Error::Config {
+                path: signature_path(&upload),
+                message: e.message().to_string(),
+            })?;
+        }
+        let archive_path = self.archive(&manifest.name, &version);
+        if archive_path.exists() {
+            return Err(Error::AlreadyPublished {
+                skill: manifest.name,
+                version,
+            });
+        }
+        let dir = self.root.join(&manifest.name);
+        fs::create_dir_all(&dir).map_err(|e| Error::io(&dir, e))?;
+        let manifest_path = dir.join(format!("{version}.toml"));
+        let text = toml::to_string_pretty(&manifest).map_err(|e| Error::Config {
+            path: manifest_path.clone(),
+            message: e.to_string(),
+        })?;
+        if let Some(signature) = signature {
+            let path = signature_path(&archive_path);
+            fs::write(&path, signature).map_err(|e| Error::io(&path, e))?;
+        }
+        fs::write(&manifest_path, text).map_err(|e| Error::io(&manifest_path, e))?;
+        // The archive goes last: its presence is what makes a version exist.
+        fs::write(&archive_path, archive).map_err(|e| Error::io(&archive_path, e))?;
+        Ok(release(manifest, version, signature.is_some()))
+    }
+
+    /// Every version of `name`, oldest first.
+    pub fn releases(&self, name: &str) -> Result<Vec<Release>> {
+        let dir = self.root.join(name);
+        if !is_kebab_case(name) || !dir.is_dir() {
+            return Err(Error::UnknownSkill(name.to_string()));
+        }
+        let mut releases = Vec::new();
+        let entries = fs::read_dir(&dir).map_err(|e| Error::io(&dir, e))?;
+        for entry in entries {
+            let path = entry.map_err(|e| Error::io(&dir, e))?.path();
+            let Some(version) = path
+                .file_name()
+                .and_then(|n| n.to_str())
+                .and_then(|n| n.strip_suffix(&format!(".{EXTENSION}")))
+            else {
+                continue;
+            };
+            let manifest_path = dir.join(format!("{version}.toml"));
+            let text =
+                fs::read_to_string(&manifest_path).map_err(|e| Error::io(&manifest_path, e))?;
+            let manifest: Manifest = toml::from_str(&text).map_err(|e| Error::Config {
+                path: manifest_path.clone(),
+                message: e.message().to_string(),
+            })?;
+            let signed = signature_path(&path).is_file();
+            releases.push(release(manifest, version.to_string(), signed));
+        }
+        releases.sort_by(|a, b| compare_versions(&a.version, &b.version));
+        Ok(releases)
+    }
+
+    /// The latest release of each skill whose name or description holds
+    /// every word of `query`, ignoring case. An empty query lists them all.
+    /// Pre-releases are only shown for skills that have nothing else.
+    pub fn search(&self, query: &str) -> Result<Vec<Release>> {
+        let words: Vec<String> = query.split_whitespace().map(str::to_lowercase).collect();
+        let entries = match fs::read_dir(&self.root) {
+            Ok(entries) => entries,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(e) => return Err(Error::io(&self.root, e)),
+        };
+        let mut names = Vec::new();
+        for entry in entries {
+            let entry = entry.map_err(|e| Error::io(&self.root, e))?;
+            let name = entry.file_name().to_string_lossy().into_owned();
+            if is_kebab_case(&name) && entry.path().is_dir() {
+                names.push(name);
+            }
+        }
+        names.sort();
+        let mut found = Vec::new();
+        for name in names {
+            let mut releases = self.releases(&name)?;
+            let stable = latest(&releases).cloned();
+            let Some(latest) = stable.or_else(|| releases.pop()) else {
+                continue;
+            };
+            let text = format!(
+                "{} {}",
+                latest.name,
+                latest.description.as_deref().unwrap_or("")
+            )
+            .to_lowercase();
+            if words.iter().all(|word| text.contains(word.as_str())) {
+                found.push(latest);
+            }
+        }
+        Ok(found)
+    }
+
+    /// Where a version's archive is, or would be, stored.
+    pub fn archive(&self, name: &str, version: &str) -> PathBuf {
+        self.root.join(name).join(format!("{version}.{EXTENSION}"))
+    }
+}
+
+fn release(manifest: Manifest, version: String, signed: bool) -> Release {
+    Release {
+        name: manifest.name,
+        version,
+        description: manifest.description,
+        hash: manifest.hash,
+        requires: manifest.requires,
+        tokens: manifest.tokens,
+        signed,
+    }
+}
+
+/// Whether `version` is safe to use in a file name and URL.
+pub fn is_version(version: &str) -> bool {
+    !version.is_empty()
+        && !version.starts_with('.')
+        && version
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '.' | '-' | '+' | '_'))
+}
+
+/// Order versions by semver precedence, so `1.10.0` follows `1.9.0` and
+/// `1.0.0-rc.1` comes before `1.0.0`. Versions that are not semver sort
+/// before those that are, by their dot-separated parts, numerically where
+/// both parts are numbers.
+pub fn compare_versions(a: &str, b: &str) -> Ordering {
+    match (Version::parse(a), Version::parse(b)) {
+        (Ok(x), Ok(y)) => x.cmp_precedence(&y).then_with(|| a.cmp(b)),
+        (Ok(_), Err(_)) => Ordering::Greater,
+        (Err(_), Ok(_)) => Ordering::Less,
+        (Err(_), Err(_)) => compare_parts(a, b),
+    }
+}
+
+fn compare_parts(a: &str, b: &str) -> Ordering {
+    let mut left = a.split('.');
+    let mut right = b.split('.');
+    loop {
+        let ordering = match (left.next(), right.next()) {
+            (None, None) => return Ordering::Equal,
+            (None, Some(_)) => Ordering::Less,
+            (Some(_), None) => Ordering::Greater,
+            (Some(x), Some(y)) => match (x.parse::<u64>(), y.parse::<u64>()) {
+                (Ok(x), Ok(y)) => x.cmp(&y),
+                _ => x.cmp(y),
+            },
+        };
+        if ordering != Ordering::Equal {
+            return ordering;
+        }
+    }
+}
+
+/// Whether `version` has a pre-release part, as in `2.0.0-rc.1`. Versions
+/// that are not semver are not pre-releases.
+pub fn is_prerelease(version: &str) -> bool {
+    Version::parse(version).is_ok_and(|v| !v.pre.is_empty())
+}
+
+/// The newest release that is not a pre-release.
+pub fn latest(releases: &[Release]) -> Option<&Release> {
+    releases
+        .iter()
+        .filter(|r| !is_prerelease(&r.version))
+        .max_by(|a, b| compare_versions(&a.version, &b.version))
+}
+
+/// The bytes of a packed `name` skill at `version`, built under `dir`.
+#[cfg(test)]
+pub(crate) fn test_archive(
+    dir: &crate::test_support::TempDir,
+    name: &str,
+    version: &str,
+) -> Vec<u8> {
+    use crate::project::SkillDir;
+    use crate::tokens::{CharEstimate, TokenCounter};
+
+    let skill = dir.path().join(format!("src-{name}-{version}"));
+    dir.write(
+        skill.join("SKILL.md"),
+        format!("---\nname: {name}\nversion: {version}\ndescription: The {name} workflow for the team\n---\n# {name}\n"),
+    );
+    let counter = TokenCounter::new(std::sync::Arc::new(CharEstimate));
+    let package = Package::from_dir(&SkillDir::new(&skill), &counter).unwrap();
+    let out = dir.path().join(format!("{name}-{version}.{EXTENSION}"));
+    package.write(&out).unwrap();
+    fs::read(out).unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use super::*;
+    use crate::project::SkillDir;
+    use crate::signing::{self, SecretKey};
+    use crate::test_support::TempDir;
+    use crate::tokens::{CharEstimate, TokenCounter};
+
+    #[test]
+    fn published_versions_are_listed_in_order() {
+        let dir = TempDir::new();
+        let store = Store::new(dir.path().join("registry"));
+        for version in ["1.10.0", "1.2.0", "1.9.0"] {
+            let archive = test_archive(&dir, "deploy", version);
+            store.publish(&archive, None).unwrap();
+        }
+        let versions: Vec<_> = store
+            .releases("deploy")
+            .unwrap()
+            .into_iter()
+            .map(|r| r.version)
+            .collect();
+        assert_eq!(versions, ["1.2.0", "1.9.0", "1.10.0"]);
+        assert!(store.archive("deploy", "1.2.0").is_file());
+        assert!(dir.path().join("registry/deploy/1.2.0.toml").is_file());
+    }
+
+    #[test]
+    fn published_versions_are_immutable() {
+        let dir = TempDir::new();
+        let store = Store::new(dir.path().join("registry"));
+        let archive = test_archive(&dir, "deploy", "1.0.0");
+        store.publish(&archive, None).unwrap();
+        assert!(matches!(
+            store.publish(&archive, None),
+            Err(Error::AlreadyPublished { skill, version }) if skill == "deploy" && version == "1.0.0"
+        ));
+    }
+
+    #[test]
+    fn packages_without_a_usable_version_are_refused() {
+        let dir = TempDir::new();
+        let store = Store::new(dir.path().join("registry"));
+        let skill = dir.write(
+            "deploy/SKILL.md",
+            "---\nname: deploy\ndescription: Deploy to staging\n---\n# Deploy\n",
+        );
+        let counter = TokenCounter::new(Arc::new(CharEstimate));
+        let out = dir.path().join("deploy.skill");
+        Package::from_dir(&SkillDir::new(skill.parent().unwrap()), &counter)
+            .unwrap()
+            .write(&out)
+            .unwrap();
+        let result = store.publish(&fs::read(&out).unwrap(), None);
+        assert!(matches!(result, Err(Error::Package { .. })));
+        assert!(matches!(
+            store.publish(b"not a package", None),
+            Err(Error::Io { .. } | Error::Package { .. })
+        ));
+        assert!(!dir.path().join("registry").exists());
+    }
+
+    #[test]
+    fn signatures_are_stored_next_to_the_archive() {
+        let dir = TempDir::new();
+        let store = Store::new(dir.path().join("registry"));
+        let archive = test_archive(&dir, "deploy", "1.0.0");
+        let key = SecretKey::generate().unwrap();
+        let path = dir.write("deploy-1.0.0.skill", &archive);
+        let signature = signing::sign(&path, &key).unwrap();
+        let text = toml::to_string(&signature).unwrap();
+        assert!(store.publish(&archive, Some("garbage")).is_err());
+        let release = store.publish(&archive, Some(&text)).unwrap();
+        assert!(release.signed);
+        let stored = signature_path(&store.archive("deploy", "1.0.0"));
+        assert_eq!(fs::read_to_string(stored).unwrap(), text);
+    }
+
+    #[test]
+    fn search_matches_every_word_in_name_or_description() {
+        let dir = TempDir::new();
+        let store = Store::new(dir.path().join("registry"));
+        assert_eq!(store.search("").unwrap(), []);
+        for (name, version) in [("deploy", "1.0.0"), ("deploy", "2.0.0"), ("lint", "1.0.0")] {
+            store
+                .publish(&test_archive(&dir, name, version), None)
+                .unwrap();
+        }
+        let found = |query| -> Vec<(String, String)> {
+            store
+                .search(query)
+                .unwrap()
+                .into_iter()
+                .map(|r| (r.name, r.version))
+                .collect()
+        };
+        assert_eq!(found("DEPLOY team"), [("deploy".into(), "2.0.0".into())]);
+        assert_eq!(found("workflow").len(), 2);
+        assert_eq!(found("deploy lint"), []);
+    }
+
+    #[test]
+    fn search_shows_releases_before_pre_releases() {
+        let dir = TempDir::new();
+        let store = Store::new(dir.path().join("registry"));
+        for (name, version) in [
+            ("deploy", "1.0.0"),
+            ("deploy", "1.1.0-rc.1"),
+            ("lint", "2.0.0-beta"),
+        ] {
+            store
+                .publish(&test_archive(&dir, name, version), None)
+                .unwrap();
+        }
+        let found: Vec<_> = store
+            .search("")
+            .unwrap()
+            .into_iter()
+            .map(|r| r.version)
+            .collect();
+        assert_eq!(found, ["1.0.0", "2.0.0-beta"]);
+        let releases = store.releases("deploy").unwrap();
+        assert_eq!(releases[1].version, "1.1.0-rc.1");
+        assert_eq!(latest(&releases).unwrap().version, "1.0.0");
+        assert!(latest(&store.releases("lint").unwrap()).is_none());
+    }
+
+    #[test]
+    fn unknown_or_invalid_names_are_unknown_skills() {
+        let dir = TempDir::new();
+        let store = Store::new(dir.path());
+        for name in ["missing", "..", "../etc"] {
+            assert!(matches!(store.releases(name), Err(Error::UnknownSkill(_))));
+        }
+    }
+
+    #[test]
+    fn versions_are_safe_path_segments() {
+        for version in ["1.0.0", "2.0.0-rc.1+build_5"] {
+            assert!(is_version(version), "{version}");
+        }
+        for version in ["", "..", ".hidden", "1.0/..", "1.0 0", "../1.0.0"] {
+            assert!(!is_version(version), "{version}");
+        }
+    }
+
+    #[test]
+    fn versions_compare_numerically() {
+        assert_eq!(compare_versions("1.10", "1.9"), Ordering::Greater);
+        assert_eq!(compare_versions("1.0", "1.0.0"), Ordering::Less);
+        assert_eq!(compare_versions("1.0.0", "1.0.0"), Ordering::Equal);
+        assert_eq!(
+            compare_versions("1.0.0-beta", "1.0.0-alpha"),
+            Ordering::Greater
+        );
+    }
+
+    #[test]
+    fn pre_releases_come_before_their_release() {
+        assert_eq!(compare_versions("1.0.0-rc.1", "1.0.0"), Ordering::Less);
+        assert_eq!(
+            compare_versions("1.0.0-rc.10", "1.0.0-rc.9"),
+            Ordering::Greater
+        );
+        assert_eq!(compare_versions("1.10.0", "1.9.0"), Ordering::Greater);
+        assert_eq!(compare_versions("1.0.0", "1.0"), Ordering::Greater);
+        assert!(is_prerelease("2.0.0-rc.1") && !is_prerelease("2.0.0+build"));
+        assert!(!is_prerelease("2.0-rc"));
+    }
+}
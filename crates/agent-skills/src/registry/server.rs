@@ -0,0 +1,365 @@
+//! The registry's HTTP front end, one request at a time.
+
+use std::fs;
+use std::io::Read;
+use std::net::SocketAddr;
+use std::path::PathBuf;
+
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use serde::Serialize;
+use serde_json::json;
+use sha2::{Digest, Sha256};
+use tiny_http::{Header, Method, Request, Response};
+
+use super::{is_version, Store, MAX_UPLOAD};
+use crate::signing::signature_path;
+use crate::skill::is_kebab_case;
+use crate::{Error, Result};
+
+const PREFIX: &str = "/api/v1/";
+
+/// Header carrying the base64 `.sig` file of a package being published.
+pub const SIGNATURE_HEADER: &str = "Skill-Signature";
+
+/// Serves a [`Store`] over HTTP.
+pub struct Server {
+    http: tiny_http::Server,
+    store: Store,
+    token: Option<String>,
+}
+
+/// A reply before it is written.
+struct Reply {
+    status: u16,
+    content_type: &'static str,
+    body: Vec<u8>,
+}
+
+impl Reply {
+    fn json(status: u16, value: &impl Serialize) -> Self {
+        Self {
+            status,
+            content_type: "application/json",
+            body: serde_json::to_vec_pretty(value).expect("registry replies serialize"),
+        }
+    }
+
+    fn error(status: u16, message: impl ToString) -> Self {
+        Self::json(status, &json!({ "error": message.to_string() }))
+    }
+}
+
+impl Server {
+    /// Listen on `addr`, e.g. `127.0.0.1:8080`.
+    pub fn bind(store: Store, addr: &str) -> Result<Self> {
+        let http = tiny_http::Server::http(addr)
+            .map_err(|e| Error::io(addr, std::io::Error::other(e.to_string())))?;
+        Ok(Self {
+            http,
+            store,
+            token: None,
+        })
+    }
+
+    /// Require `Authorization: Bearer <token>` to publish. Reading is
+    /// always open.
+    pub fn token(mut self, token: Option<String>) -> Self {
+        self.token = token;
+        self
+    }
+
+    /// The address actually bound, useful with port `0`.
+    pub fn addr(&self) -> Option<SocketAddr> {
+        self.http.server_addr().to_ip()
+    }
+
+    /// Answer requests until the process is stopped.
+    pub fn run(&self) -> Result<()> {
+        for mut request in self.http.incoming_requests() {
+            let reply = self.handle(&mut request);
+            let content_type = Header::from_bytes("Content-Type", reply.content_type)
+                .expect("static header is valid");
+            let response = Response::from_data(reply.body)
+                .with_status_code(reply.status)
+                .with_header(content_type);
+            // A client that hung up is its own problem.
+            let _ = request.respond(response);
+        }
+        Ok(())
+    }
+
+    fn handle(&self, request: &mut Request) -> Reply {
+        let url = request.url().to_string();
+        let (path, query) = url.split_once('?').unwrap_or((&url, ""));
+        let Some(path) = path.strip_prefix(PREFIX) else {
+            return Reply::error(404, format!("no route for `{path}`"));
+        };
+        let segments: Vec<&str> = path.split('/').filter(|s| !s.is_empty()).collect();
+        let result = match (request.method(), segments.as_slice()) {
+            (Method::Get, ["search"]) => {
+                let query = param(query, "q").unwrap_or_default();
+                self.store
+                    .search(&query)
+                    .map(|found| Reply::json(200, &found))
+            }
+            (Method::Get, ["skills", name]) => self
+                .store
+                .releases(name)
+                .map(|releases| Reply::json(200, &releases)),
+            (Method::Get, ["skills", name, version]) if is_release(name, version) => {
+                self.file(self.store.archive(name, version), "application/gzip")
+            }
+            (Method::Get, ["skills", name, version, "signature"]) if is_release(name, version) => {
+                self.file(
+                    signature_path(&self.store.archive(name, version)),
+                    "application/toml",
+                )
+            }
+            (Method::Post, ["skills"]) => self.publish(request),
+            _ => return Reply::error(404, format!("no route for `{path}`")),
+        };
+        result.unwrap_or_else(|e| {
+            let status = match &e {
+                Error::UnknownSkill(_) => 404,
+                Error::AlreadyPublished { .. } => 409,
+                Error::Package { .. } | Error::InvalidName(_) | Error::Config { .. } => 400,
+                _ => 500,
+            };
+            Reply::error(status, e)
+        })
+    }
+
+    fn file(&self, path: PathBuf, content_type: &'static str) -> Result<Reply> {
+        match fs::read(&path) {
+            Ok(body) => Ok(Reply {
+                status: 200,
+                content_type,
+                body,
+            }),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+                Ok(Reply::error(404, "not published"))
+            }
+            Err(e) => Err(Error::io(&path, e)),
+        }
+    }
+
+    fn publish(&self, request: &mut Request) -> Result<Reply> {
+        let header = |name: &'static str| {
+            request
+                .headers()
+                .iter()
+                .find(|h| h.field.equiv(name))
+                .map(|h| h.value.as_str().to_string())
+        };
+        if let Some(token) = &self.token {
+            let sent = header("Authorization").unwrap_or_default();
+            if !same_secret(&sent, &format!("Bearer {token}")) {
+                return Ok(Reply::error(401, "publishing needs the registry token"));
+            }
+        }
+        let signature = match header(SIGNATURE_HEADER) {
+            Some(b64) => match STANDARD
+                .decode(b64.trim())
+                .ok()
+                .and_then(|bytes| String::from_utf8(bytes).ok())
+            {
+                Some(text) => Some(text),
+                None => {
+                    let message = format!("{SIGNATURE_HEADER} is not base64 text");
+                    return Ok(Reply::error(400, message));
+                }
+            },
+            None => None,
+        };
+        let mut archive = Vec::new();
+        request
+            .as_reader()
+            .take(MAX_UPLOAD + 1)
+            .read_to_end(&mut archive)
+            .map_err(|e| Error::io("request body", e))?;
+        if archive.len() as u64 > MAX_UPLOAD {
+            return Ok(Reply::error(
+                413,
+                format!("packages are limited to {} MiB", MAX_UPLOAD >> 20),
+            ));
+        }
+        let release = self.store.publish(&archive, signature.as_deref())?;
+        Ok(Reply::json(201, &release))
+    }
+}
+
+/// Compare digests rather than the secrets themselves, so how long the
+/// comparison takes says nothing about how much of the token was right.
+fn same_secret(sent: &str, expected: &str) -> bool {
+    Sha256::digest(sent.as_bytes()) == Sha256::digest(expected.as_bytes())
+}
+
+/// Whether URL segments name a release, rather than a path outside the
+/// store.
+fn is_release(name: &str, version: &str) -> bool {
+    is_kebab_case(name) && is_version(version)
+}
+
+/// The decoded value of `key` in a query string.
+fn param(query: &str, key: &str) -> Option<String> {
+    query
+        .split('&')
+        .filter_map(|pair| pair.split_once('=').or(Some((pair, ""))))
+        .find(|(k, _)| *k == key)
+        .map(|(_, v)| percent_decode(v))
+}
+
+fn percent_decode(text: &str) -> String {
+    let bytes = text.as_bytes();
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'+' => out.push(b' '),
+            b'%' if i + 2 < bytes.len() => {
+                let hex = std::str::from_utf8(&bytes[i + 1..i + 3]).ok();
+                match hex.and_then(|hex| u8::from_str_radix(hex, 16).ok()) {
+                    Some(byte) => {
+                        out.push(byte);
+                        i += 2;
+                    }
+                    None => out.push(b'%'),
+                }
+            }
+            byte => out.push(byte),
+        }
+        i += 1;
+    }
+    String::from_utf8_lossy(&out).into_owned()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::registry::{test_archive, Client};
+    use crate::test_support::TempDir;
+
+    /// A server on a free port, answering in the background.
+    fn serve(dir: &TempDir, token: Option<&str>) -> Client {
+        let store = Store::new(dir.path().join("registry"));
+        let server = Server::bind(store, "127.0.0.1:0")
+            .unwrap()
+            .token(token.map(str::to_string));
+        let url = format!("http://{}", server.addr().unwrap());
+        std::thread::spawn(move || server.run());
+        Client::new(&url).unwrap()
+    }
+
+    #[test]
+    fn publish_search_and_download_round_trip() {
+        let dir = TempDir::new();
+        let client = serve(&dir, None);
+        let archive = dir.write("deploy-1.0.0.skill", test_archive(&dir, "deploy", "1.0.0"));
+        let release = client.publish(&archive).unwrap();
+        assert_eq!(
+            (release.name.as_str(), release.version.as_str()),
+            ("deploy", "1.0.0")
+        );
+        assert!(!release.signed);
+
+        assert_eq!(client.releases("deploy").unwrap(), [release]);
+        assert_eq!(client.search("deploy workflow").unwrap().len(), 1);
+
+        let out = dir.path().join("downloads");
+        fs::create_dir(&out).unwrap();
+        let path = client.download("deploy", None, &out).unwrap();
+        assert_eq!(path, out.join("deploy-1.0.0.skill"));
+        assert_eq!(fs::read(&path).unwrap(), fs::read(&archive).unwrap());
+        assert!(client.download("deploy", Some("9.9.9"), &out).is_err());
+        assert!(matches!(
+            client.releases("../etc"),
+            Err(Error::InvalidName(_))
+        ));
+    }
+
+    #[test]
+    fn downloads_without_a_version_skip_pre_releases() {
+        let dir = TempDir::new();
+        let client = serve(&dir, None);
+        let out = dir.path().join("downloads");
+        fs::create_dir(&out).unwrap();
+        let rc = dir.write("rc.skill", test_archive(&dir, "deploy", "1.1.0-rc.1"));
+        client.publish(&rc).unwrap();
+        match client.download("deploy", None, &out) {
+            Err(Error::Registry { message, .. }) => assert_eq!(
+                message,
+                "no release of `deploy`; name a pre-release to install it"
+            ),
+            other => panic!("expected no release, got {other:?}"),
+        }
+        let stable = dir.write("stable.skill", test_archive(&dir, "deploy", "1.0.0"));
+        client.publish(&stable).unwrap();
+        let path = client.download("deploy", None, &out).unwrap();
+        assert_eq!(path, out.join("deploy-1.0.0.skill"));
+        let path = client.download("deploy", Some("1.1.0-rc.1"), &out).unwrap();
+        assert_eq!(path, out.join("deploy-1.1.0-rc.1.skill"));
+    }
+
+    #[test]
+    fn publishing_requires_the_token_when_one_is_set() {
+        let dir = TempDir::new();
+        let client = serve(&dir, Some("s3cret"));
+        let archive = dir.write("deploy-1.0.0.skill", test_archive(&dir, "deploy", "1.0.0"));
+        for token in [None, Some("wrong"), Some("s3cre"), Some("s3cret2")] {
+            let result = client
+                .clone()
+                .token(token.map(str::to_string))
+                .publish(&archive);
+            match result {
+                Err(Error::Registry { message, .. }) => {
+                    assert_eq!(message, "publishing needs the registry token")
+                }
+                other => panic!("{token:?} was accepted: {other:?}"),
+            }
+        }
+        let client = client.token(Some("s3cret".into()));
+        client.publish(&archive).unwrap();
+        match client.publish(&archive) {
+            Err(Error::Registry { message, .. }) => assert!(message.contains("already published")),
+            other => panic!("republished: {other:?}"),
+        }
+    }
+
+    #[test]
+    fn paths_outside_the_store_are_not_served() {
+        let dir = TempDir::new();
+        let client = serve(&dir, None);
+        dir.write("registry/secret.skill", "secret");
+        let out = dir.path().join("downloads");
+        fs::create_dir(&out).unwrap();
+        assert!(client.download("..", Some("secret"), &out).is_err());
+        assert_eq!(fs::read_dir(&out).unwrap().count(), 0);
+    }
+
+    #[test]
+    fn secrets_are_compared_whole() {
+        assert!(same_secret("Bearer abc", "Bearer abc"));
+        assert!(!same_secret("Bearer ab", "Bearer abc"));
+        assert!(!same_secret("", "Bearer abc"));
+    }
+
+    #[test]
+    fn query_parameters_are_decoded() {
+        assert_eq!(
+            param("q=deploy+to%20staging", "q").as_deref(),
+            Some("deploy to staging")
+        );
+        assert_eq!(param("a=1&q", "q").as_deref(), Some(""));
+        assert_eq!(param("a=1", "q"), None);
+        assert_eq!(percent_decode("100%"), "100%");
+        assert_eq!(percent_decode("%zz"), "%zz");
+    }
+
+    #[test]
+    fn only_release_segments_name_files() {
+        assert!(is_release("deploy", "1.0.0"));
+        assert!(!is_release("..", "1.0.0"));
+        assert!(!is_release("deploy", ".."));
+    }
+}
@@ -0,0 +1,370 @@
+//! Offline relevance ranking: which skill should an agent load?
+//!
+//! Agents decide from `description` alone ("Esto requiere pnpm-workflow").
+//! Hosts without a model in the loop can use BM25 over each skill's name and
+//! description instead. Skills here are bilingual, so every word is stemmed
+//! as both English and Spanish and a query word matches when either stem
+//! does.
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::Path;
+
+use rust_stemmers::{Algorithm, Stemmer};
+use serde::Serialize;
+
+use crate::loader::SkillMetadata;
+
+/// BM25 term-frequency saturation.
+const K1: f64 = 1.2;
+/// BM25 length normalization.
+const B: f64 = 0.75;
+/// Name words count this many times, since names are short and deliberate.
+const NAME_WEIGHT: usize = 2;
+/// Words taken from open file paths weigh less than what the user typed.
+const FILE_WEIGHT: f64 = 0.5;
+
+const STOPWORDS_EN: &[&str] = &[
+    "a", "an", "and", "are", "as", "at", "be", "by", "do", "for", "from", "how", "i", "in", "into",
+    "is", "it", "its", "me", "my", "of", "on", "or", "our", "please", "so", "that", "the", "this",
+    "to", "use", "using", "we", "what", "when", "with", "you", "your",
+];
+
+const STOPWORDS_ES: &[&str] = &[
+    "al", "con", "cual", "cuando", "de", "del", "el", "en", "es", "esta", "este", "esto", "la",
+    "las", "lo", "los", "mi", "mis", "o", "para", "por", "que", "se", "si", "su", "sus", "tu",
+    "tus", "un", "una", "unas", "uno", "unos", "usa", "usar", "y",
+];
+
+/// Where a query word came from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Source {
+    Utterance,
+    OpenFile,
+}
+
+/// Which part of the skill a word matched.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Field {
+    Name,
+    Description,
+}
+
+/// One query word that contributed to a skill's score.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct MatchedTerm {
+    /// The word as it appeared in the query.
+    pub word: String,
+    /// The stem both sides were reduced to.
+    pub stem: String,
+    pub source: Source,
+    pub fields: Vec<Field>,
+    pub score: f64,
+}
+
+/// A ranked skill with the reasons for its score.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Match {
+    pub name: String,
+    pub score: f64,
+    pub terms: Vec<MatchedTerm>,
+}
+
+struct Doc {
+    name: String,
+    /// Stem → occurrences, name words weighted.
+    terms: BTreeMap<String, usize>,
+    name_terms: BTreeSet<String>,
+    description_terms: BTreeSet<String>,
+    len: usize,
+}
+
+/// BM25 index over skill metadata.
+pub struct Matcher {
+    analyzer: Analyzer,
+    docs: Vec<Doc>,
+    /// Stem → number of skills containing it.
+    df: BTreeMap<String, usize>,
+    avg_len: f64,
+}
+
+impl Matcher {
+    pub fn new<'a>(skills: impl IntoIterator<Item = &'a SkillMetadata>) -> Self {
+        let analyzer = Analyzer::new();
+        let mut docs = Vec::new();
+        let mut df: BTreeMap<String, usize> = BTreeMap::new();
+        for skill in skills {
+            let mut terms: BTreeMap<String, usize> = BTreeMap::new();
+            let mut name_terms = BTreeSet::new();
+            let mut description_terms = BTreeSet::new();
+            let mut len = 0;
+            for (_, stems) in analyzer.analyze(&skill.name) {
+                for stem in stems {
+                    *terms.entry(stem.clone()).or_default() += NAME_WEIGHT;
+                    name_terms.insert(stem);
+                }
+                len += NAME_WEIGHT;
+            }
+            for (_, stems) in analyzer.analyze(&skill.description) {
+                for stem in stems {
+                    *terms.entry(stem.clone()).or_default() += 1;
+                    description_terms.insert(stem);
+                }
+                len += 1;
+            }
+            for stem in terms.keys() {
+                *df.entry(stem.clone()).or_default() += 1;
+            }
+            docs.push(Doc {
+                name: skill.name.clone(),
+                terms,
+                name_terms,
+                description_terms,
+                len,
+            });
+        }
+        let avg_len = match docs.len() {
+            0 => 0.0,
+            n => docs.iter().map(|d| d.len).sum::<usize>() as f64 / n as f64,
+        };
+        Self {
+            analyzer,
+            docs,
+            df,
+            avg_len,
+        }
+    }
+
+    /// Rank skills for `utterance` and the files open in the editor. Only
+    /// skills with a positive score are returned, best first.
+    pub fn rank(&self, utterance: &str, open_files: &[impl AsRef<Path>]) -> Vec<Match> {
+        let mut query: Vec<(String, Vec<String>, Source)> = Vec::new();
+        let mut seen = BTreeSet::new();
+        let words = self
+            .analyzer
+            .analyze(utterance)
+            .into_iter()
+            .map(|w| (w, Source::Utterance))
+            .chain(open_files.iter().flat_map(|path| {
+                let path = path.as_ref().to_string_lossy();
+                self.analyzer
+                    .analyze(&path)
+                    .into_iter()
+                    .map(|w| (w, Source::OpenFile))
+                    .collect::<Vec<_>>()
+            }));
+        for ((word, stems), source) in words {
+            if seen.insert(stems.clone()) {
+                query.push((word, stems, source));
+            }
+        }
+
+        let mut matches: Vec<Match> = self
+            .docs
+            .iter()
+            .filter_map(|doc| {
+                let terms: Vec<MatchedTerm> = query
+                    .iter()
+                    .filter_map(|(word, stems, source)| self.score_word(doc, word, stems, *source))
+                    .collect();
+                let score: f64 = terms.iter().map(|t| t.score).sum();
+                (score > 0.0).then(|| Match {
+                    name: doc.name.clone(),
+                    score,
+                    terms,
+                })
+            })
+            .collect();
+        matches.sort_by(|a, b| {
+            b.score
+                .total_cmp(&a.score)
+                .then_with(|| a.name.cmp(&b.name))
+        });
+        matches
+    }
+
+    /// Best-scoring stem of one query word, so a word whose English and
+    /// Spanish stems both match is not counted twice.
+    fn score_word(
+        &self,
+        doc: &Doc,
+        word: &str,
+        stems: &[String],
+        source: Source,
+    ) -> Option<MatchedTerm> {
+        let n = self.docs.len() as f64;
+        let weight = match source {
+            Source::Utterance => 1.0,
+            Source::OpenFile => FILE_WEIGHT,
+        };
+        stems
+            .iter()
+            .filter_map(|stem| {
+                let tf = *doc.terms.get(stem)? as f64;
+                let df = self.df.get(stem).copied().unwrap_or(0) as f64;
+                let idf = ((n - df + 0.5) / (df + 0.5) + 1.0).ln();
+                let norm = 1.0 - B + B * doc.len as f64 / self.avg_len.max(1.0);
+                let score = weight * idf * tf * (K1 + 1.0) / (tf + K1 * norm);
+                Some((stem, score))
+            })
+            .max_by(|a, b| a.1.total_cmp(&b.1))
+            .map(|(stem, score)| {
+                let mut fields = Vec::new();
+                if doc.name_terms.contains(stem) {
+                    fields.push(Field::Name);
+                }
+                if doc.description_terms.contains(stem) {
+                    fields.push(Field::Description);
+                }
+                MatchedTerm {
+                    word: word.to_string(),
+                    stem: stem.clone(),
+                    source,
+                    fields,
+                    score,
+                }
+            })
+    }
+}
+
+/// Splits text into words and stems each one as English and Spanish.
+pub(crate) struct Analyzer {
+    english: Stemmer,
+    spanish: Stemmer,
+}
+
+impl Analyzer {
+    pub(crate) fn new() -> Self {
+        Self {
+            english: Stemmer::create(Algorithm::English),
+            spanish: Stemmer::create(Algorithm::Spanish),
+        }
+    }
+
+    /// `(word, stems)` for every non-stopword, stems deduplicated.
+    pub(crate) fn analyze(&self, text: &str) -> Vec<(String, Vec<String>)> {
+        text.split(|c: char| !c.is_alphanumeric())
+            .filter(|w| !w.is_empty())
+            .map(str::to_lowercase)
+            .filter(|w| w.chars().count() > 1 && !is_stopword(&fold(w)))
+            .map(|word| {
+                let mut stems = vec![
+                    fold(&self.english.stem(&word)),
+                    fold(&self.spanish.stem(&word)),
+                ];
+                stems.dedup();
+                (word, stems)
+            })
+            .collect()
+    }
+}
+
+fn is_stopword(word: &str) -> bool {
+    STOPWORDS_EN.contains(&word) || STOPWORDS_ES.contains(&word)
+}
+
+/// Drop Spanish diacritics so "instalación" and "instalacion" agree.
+pub(crate) fn fold(word: &str) -> String {
+    word.chars()
+        .map(|c| match c {
+            'á' | 'à' | 'ä' => 'a',
+            'é' | 'è' | 'ë' => 'e',
+            'í' | 'ì' | 'ï' => 'i',
+            'ó' | 'ò' | 'ö' => 'o',
+            'ú' | 'ù' | 'ü' => 'u',
+            'ñ' => 'n',
+            other => other,
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    fn metadata(name: &str, description: &str) -> SkillMetadata {
+        SkillMetadata {
+            name: name.into(),
+            description: description.into(),
+            globs: Vec::new(),
+            path: PathBuf::from(name),
+            tokens: 0,
+        }
+    }
+
+    fn matcher() -> Matcher {
+        let skills = [
+            metadata(
+                "pnpm-workflow",
+                "Install dependencies and run scripts with pnpm",
+            ),
+            metadata(
+                "astro-components",
+                "Escribir componentes de Astro con instalación de integraciones",
+            ),
+            metadata("zod-schemas", "Validate input with Zod schemas"),
+        ];
+        Matcher::new(&skills)
+    }
+
+    fn names(matches: &[Match]) -> Vec<&str> {
+        matches.iter().map(|m| m.name.as_str()).collect()
+    }
+
+    #[test]
+    fn stopwords_short_words_and_punctuation_are_dropped() {
+        let words: Vec<_> = Analyzer::new()
+            .analyze("Use the pnpm-lock, a y x")
+            .into_iter()
+            .map(|(word, _)| word)
+            .collect();
+        assert_eq!(words, ["pnpm", "lock"]);
+    }
+
+    #[test]
+    fn words_are_stemmed_as_english_and_spanish_without_accents() {
+        let analyzer = Analyzer::new();
+        let stems = |word: &str| analyzer.analyze(word).remove(0).1;
+        assert!(stems("installing").contains(&"instal".to_string()));
+        let accented = stems("instalación");
+        assert!(stems("instalacion").iter().any(|s| accented.contains(s)));
+    }
+
+    #[test]
+    fn the_best_skill_ranks_first_and_unrelated_ones_are_left_out() {
+        let matches = matcher().rank("how do I install dependencies with pnpm?", &[] as &[&str]);
+        // "install" also matches "instalación" through its Spanish stem.
+        assert_eq!(names(&matches), ["pnpm-workflow", "astro-components"]);
+        assert!(matches[0].score > 2.0 * matches[1].score);
+        let terms: Vec<_> = matches[0].terms.iter().map(|t| t.word.as_str()).collect();
+        assert_eq!(terms, ["install", "dependencies", "pnpm"]);
+        assert_eq!(
+            matches[0].terms[2].fields,
+            [Field::Name, Field::Description]
+        );
+    }
+
+    #[test]
+    fn spanish_queries_match_spanish_descriptions() {
+        let matches = matcher().rank("crear un componente", &[] as &[&str]);
+        assert_eq!(names(&matches), ["astro-components"]);
+    }
+
+    #[test]
+    fn open_files_count_for_less_than_the_utterance() {
+        let matcher = matcher();
+        let typed = matcher.rank("zod", &[] as &[&str]);
+        let open = matcher.rank("", &["src/zod/user.ts"]);
+        assert_eq!(names(&open), ["zod-schemas"]);
+        assert_eq!(open[0].terms[0].source, Source::OpenFile);
+        assert!(open[0].score < typed[0].score);
+    }
+
+    #[test]
+    fn an_empty_index_ranks_nothing() {
+        let matcher = Matcher::new(&[]);
+        assert!(matcher.rank("pnpm", &[] as &[&str]).is_empty());
+    }
+}
@@ -0,0 +1,524 @@
+//! Picking one version of every skill so that all `requires` ranges hold.
+//!
+//! ```yaml
+//! name: react-extension
+//! version: 2.1.0
+//! requires:
+//!   typescript-extension: ">=1.2, <2"
+//! ```
+//!
+//! Versions and ranges follow [semver](https://semver.org) as Cargo reads
+//! them, so a bare `1.2` means `^1.2`. A skill without a `version` only
+//! satisfies `*`. The skills installed in the project are always part of
+//! the set; their installed versions are preferred, then the newest
+//! registry release that fits.
+
+use std::collections::{BTreeMap, VecDeque};
+use std::fmt;
+
+use semver::{Version, VersionReq};
+use serde::Serialize;
+
+use crate::project::Project;
+use crate::registry::Client;
+use crate::skill::Skill;
+use crate::{Error, Result};
+
+/// Where a candidate version comes from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Origin {
+    Installed,
+    Registry,
+}
+
+/// One version of a skill the resolver may pick.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Candidate {
+    pub name: String,
+    /// `None` for skills without a valid `version`.
+    pub version: Option<Version>,
+    /// Ranges that fail to parse are left out; `skills lint` reports them.
+    pub requires: Vec<(String, VersionReq)>,
+    pub origin: Origin,
+}
+
+impl Candidate {
+    /// A candidate from raw frontmatter values.
+    pub fn new(
+        name: impl Into<String>,
+        version: Option<&str>,
+        requires: &BTreeMap<String, String>,
+        origin: Origin,
+    ) -> Self {
+        Self {
+            name: name.into(),
+            version: version.and_then(|v| Version::parse(v).ok()),
+            requires: requires
+                .iter()
+                .filter_map(|(name, range)| Some((name.clone(), VersionReq::parse(range).ok()?)))
+                .collect(),
+            origin,
+        }
+    }
+
+    /// The candidate an installed skill offers.
+    pub fn installed(name: &str, skill: &Skill) -> Self {
+        let frontmatter = skill.frontmatter();
+        Self::new(
+            name,
+            frontmatter.version.as_deref(),
+            &frontmatter.requires,
+            Origin::Installed,
+        )
+    }
+
+    fn satisfies(&self, range: &VersionReq) -> bool {
+        match &self.version {
+            Some(version) => range.matches(version),
+            None => *range == VersionReq::STAR,
+        }
+    }
+
+    /// `` `name` 1.2.0 ``, or just the name when unversioned.
+    fn label(&self) -> String {
+        match &self.version {
+            Some(version) => format!("`{}` {version}", self.name),
+            None => format!("`{}`", self.name),
+        }
+    }
+}
+
+/// The version picked for one skill.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Resolved {
+    pub name: String,
+    pub version: Option<String>,
+    pub origin: Origin,
+}
+
+/// A skill no available version of which satisfies every range on it.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Conflict {
+    pub skill: String,
+    /// `(who, range)` for every requirement on the skill.
+    pub required_by: Vec<(String, String)>,
+    /// Versions that were considered, best first.
+    pub available: Vec<String>,
+}
+
+impl fmt::Display for Conflict {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "no version of `{}` fits", self.skill)?;
+        let demands: Vec<String> = self
+            .required_by
+            .iter()
+            .map(|(by, range)| format!("{by} requires {range}"))
+            .collect();
+        if !demands.is_empty() {
+            write!(f, ": {}", demands.join(", "))?;
+        }
+        match self.available.is_empty() {
+            true => write!(f, "; none is installed or published"),
+            false => write!(f, "; available: {}", self.available.join(", ")),
+        }
+    }
+}
+
+/// A requirement picked up while walking the graph.
+struct Demand<'a> {
+    name: &'a str,
+    range: &'a VersionReq,
+    by: &'a Candidate,
+}
+
+/// Candidate versions by skill name, in order of preference.
+#[derive(Debug, Clone, Default)]
+pub struct Resolver {
+    candidates: BTreeMap<String, Vec<Candidate>>,
+}
+
+impl Resolver {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The skills installed in `project`. Skills that fail to parse are
+    /// skipped; `skills lint` reports them.
+    pub fn from_project(project: &Project) -> Result<Self> {
+        let mut resolver = Self::new();
+        for dir in project.skills()? {
+            let Ok(skill) = Skill::from_path(dir.skill_file()) else {
+                continue;
+            };
+            resolver = resolver.candidate(Candidate::installed(dir.skill_name(&skill), &skill));
+        }
+        Ok(resolver)
+    }
+
+    /// Offer another version. Earlier candidates of a name are preferred.
+    pub fn candidate(mut self, candidate: Candidate) -> Self {
+        self.candidates
+            .entry(candidate.name.clone())
+            .or_default()
+            .push(candidate);
+        self
+    }
+
+    /// Drop every candidate of `name`, as when the skill is about to be
+    /// replaced.
+    pub fn without(mut self, name: &str) -> Self {
+        self.candidates.remove(name);
+        self
+    }
+
+    /// Add the releases `client` has of `names`, of the installed skills
+    /// and of everything they reach through `requires`. Installed versions
+    /// stay preferred; releases follow, newest first.
+    pub fn registry(mut self, client: &Client, names: &[String]) -> Result<Self> {
+        let mut queue: VecDeque<String> = self.installed().into();
+        queue.extend(names.iter().cloned());
+        let mut seen: Vec<String> = Vec::new();
+        while let Some(name) = queue.pop_front() {
+            if seen.contains(&name) {
+                continue;
+            }
+            seen.push(name.clone());
+            let releases = match client.releases(&name) {
+                Ok(releases) => releases,
+                // Unpublished, or not a publishable name.
+                Err(Error::Registry { .. } | Error::InvalidName(_)) => Vec::new(),
+                Err(e) => return Err(e),
+            };
+            let mut offered: Vec<Candidate> = releases
+                .iter()
+                .map(|r| Candidate::new(&r.name, Some(&r.version), &r.requires, Origin::Registry))
+                .filter(|c| c.version.is_some())
+                .collect();
+            offered.sort_by(|a, b| b.version.cmp(&a.version));
+            let known = self.candidates.entry(name).or_default();
+            for candidate in offered {
+                if !known.iter().any(|c| c.version == candidate.version) {
+                    known.push(candidate);
+                }
+            }
+            for candidate in known.iter() {
+                queue.extend(candidate.requires.iter().map(|(dep, _)| dep.clone()));
+            }
+        }
+        Ok(self)
+    }
+
+    /// Pick one version of each of `roots` and of everything they require,
+    /// transitively.
+    pub fn resolve(&self, roots: &[String]) -> Result<Vec<Resolved>> {
+        let mut picked = BTreeMap::new();
+        let mut demands = Vec::new();
+        let mut conflict = None;
+        if !self.step(roots, &mut picked, &mut demands, &mut conflict) {
+            let conflict = conflict.expect("a failed search records its conflict");
+            return Err(Error::Conflict(Box::new(conflict)));
+        }
+        Ok(picked
+            .into_values()
+            .map(|c| Resolved {
+                name: c.name.clone(),
+                version: c.version.as_ref().map(Version::to_string),
+                origin: c.origin,
+            })
+            .collect())
+    }
+
+    /// Names of the installed skills.
+    pub fn installed(&self) -> Vec<String> {
+        self.candidates
+            .iter()
+            .filter(|(_, candidates)| candidates.iter().any(|c| c.origin == Origin::Installed))
+            .map(|(name, _)| name.clone())
+            .collect()
+    }
+
+    /// Resolve every installed skill.
+    pub fn resolve_all(&self) -> Result<Vec<Resolved>> {
+        self.resolve(&self.installed())
+    }
+
+    /// Depth-first search with backtracking; the first dead end is kept in
+    /// `conflict` to explain a failure.
+    fn step<'a>(
+        &'a self,
+        roots: &'a [String],
+        picked: &mut BTreeMap<&'a str, &'a Candidate>,
+        demands: &mut Vec<Demand<'a>>,
+        conflict: &mut Option<Conflict>,
+    ) -> bool {
+        let next = roots
+            .iter()
+            .map(String::as_str)
+            .chain(demands.iter().map(|d| d.name))
+            .find(|name| !picked.contains_key(name));
+        let Some(name) = next else {
+            return true;
+        };
+        let options = self.candidates.get(name).map_or(&[][..], Vec::as_slice);
+        for candidate in options {
+            let fits = demands
+                .iter()
+                .filter(|d| d.name == name)
+                .all(|d| candidate.satisfies(d.range));
+            if !fits {
+                continue;
+            }
+            // Its own requirements must hold for what is already picked.
+            let clash = candidate.requires.iter().find(|(dep, range)| {
+                picked
+                    .get(dep.as_str())
+                    .is_some_and(|p| !p.satisfies(range))
+            });
+            if let Some((dep, range)) = clash {
+                let extra = Demand {
+                    name: dep,
+                    range,
+                    by: candidate,
+                };
+                self.record(dep, demands.iter().chain([&extra]), conflict);
+                continue;
+            }
+            let mark = demands.len();
+            demands.extend(candidate.requires.iter().map(|(dep, range)| Demand {
+                name: dep,
+                range,
+                by: candidate,
+            }));
+            picked.insert(name, candidate);
+            if self.step(roots, picked, demands, conflict) {
+                return true;
+            }
+            picked.remove(name);
+            demands.truncate(mark);
+        }
+        self.record(name, demands.iter(), conflict);
+        false
+    }
+
+    fn record<'a, 'b: 'a>(
+        &self,
+        name: &str,
+        demands: impl Iterator<Item = &'a Demand<'b>>,
+        conflict: &mut Option<Conflict>,
+    ) {
+        if conflict.is_some() {
+            return;
+        }
+        let options = self.candidates.get(name).map_or(&[][..], Vec::as_slice);
+        *conflict = Some(Conflict {
+            skill: name.to_string(),
+            required_by: demands
+                .filter(|d| d.name == name)
+                .map(|d| (d.by.label(), d.range.to_string()))
+                .collect(),
+            available: options
+                .iter()
+                .map(|c| {
+                    let version = c
+                        .version
+                        .as_ref()
+                        .map_or("unversioned".into(), |v| v.to_string());
+                    match c.origin {
+                        Origin::Installed => format!("{version} (installed)"),
+                        Origin::Registry => version,
+                    }
+                })
+                .collect(),
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::registry::{test_archive, Server, Store};
+    use crate::test_support::TempDir;
+
+    fn candidate(
+        name: &str,
+        version: Option<&str>,
+        requires: &[(&str, &str)],
+        origin: Origin,
+    ) -> Candidate {
+        let requires = requires
+            .iter()
+            .map(|(n, r)| (n.to_string(), r.to_string()))
+            .collect();
+        Candidate::new(name, version, &requires, origin)
+    }
+
+    fn installed(name: &str, version: &str, requires: &[(&str, &str)]) -> Candidate {
+        candidate(name, Some(version), requires, Origin::Installed)
+    }
+
+    fn release(name: &str, version: &str, requires: &[(&str, &str)]) -> Candidate {
+        candidate(name, Some(version), requires, Origin::Registry)
+    }
+
+    fn picked(resolved: &[Resolved]) -> Vec<(&str, Option<&str>)> {
+        resolved
+            .iter()
+            .map(|r| (r.name.as_str(), r.version.as_deref()))
+            .collect()
+    }
+
+    fn conflict(result: Result<Vec<Resolved>>) -> Conflict {
+        match result {
+            Err(Error::Conflict(conflict)) => *conflict,
+            other => panic!("expected a conflict, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn installed_versions_are_preferred_when_they_fit() {
+        let resolver = Resolver::new()
+            .candidate(installed("react", "2.1.0", &[("typescript", ">=1.2, <2")]))
+            .candidate(installed("typescript", "1.4.0", &[]))
+            .candidate(release("typescript", "1.9.0", &[]));
+        let resolved = resolver.resolve_all().unwrap();
+        assert_eq!(
+            picked(&resolved),
+            [("react", Some("2.1.0")), ("typescript", Some("1.4.0"))]
+        );
+        assert_eq!(resolved[1].origin, Origin::Installed);
+    }
+
+    #[test]
+    fn a_registry_release_replaces_an_installed_version_that_does_not_fit() {
+        let resolver = Resolver::new()
+            .candidate(installed("react", "2.1.0", &[("typescript", "^1.5")]))
+            .candidate(installed("typescript", "1.4.0", &[]))
+            .candidate(release("typescript", "1.9.0", &[]))
+            .candidate(release("typescript", "1.5.0", &[]));
+        let resolved = resolver.resolve_all().unwrap();
+        assert_eq!(resolved[1].version.as_deref(), Some("1.9.0"));
+        assert_eq!(resolved[1].origin, Origin::Registry);
+    }
+
+    #[test]
+    fn the_search_backtracks_past_versions_that_clash_later() {
+        // The newest `ui` needs a `core` that `app` rules out.
+        let resolver = Resolver::new()
+            .candidate(installed("app", "1.0.0", &[("ui", "*"), ("core", "^1")]))
+            .candidate(release("ui", "2.0.0", &[("core", "^2")]))
+            .candidate(release("ui", "1.0.0", &[("core", "^1")]))
+            .candidate(release("core", "2.0.0", &[]))
+            .candidate(release("core", "1.3.0", &[]));
+        let resolved = resolver.resolve(&["app".into()]).unwrap();
+        assert_eq!(
+            picked(&resolved),
+            [
+                ("app", Some("1.0.0")),
+                ("core", Some("1.3.0")),
+                ("ui", Some("1.0.0"))
+            ]
+        );
+    }
+
+    #[test]
+    fn conflicts_name_every_demand_and_the_versions_available() {
+        let resolver = Resolver::new()
+            .candidate(installed("react", "2.1.0", &[("typescript", "^1.5")]))
+            .candidate(installed("vue", "3.0.0", &[("typescript", "^2")]))
+            .candidate(installed("typescript", "1.4.0", &[]))
+            .candidate(release("typescript", "2.0.0", &[]));
+        let conflict = conflict(resolver.resolve_all());
+        assert_eq!(conflict.skill, "typescript");
+        assert_eq!(conflict.available, ["1.4.0 (installed)", "2.0.0"]);
+        assert_eq!(
+            conflict.to_string(),
+            "no version of `typescript` fits: `react` 2.1.0 requires ^1.5; available: 1.4.0 (installed), 2.0.0"
+        );
+    }
+
+    #[test]
+    fn missing_skills_are_conflicts() {
+        let resolver =
+            Resolver::new().candidate(installed("react", "2.1.0", &[("typescript", "^1")]));
+        assert_eq!(
+            conflict(resolver.resolve_all()).to_string(),
+            "no version of `typescript` fits: `react` 2.1.0 requires ^1; none is installed or published"
+        );
+    }
+
+    #[test]
+    fn unversioned_skills_only_satisfy_any_version() {
+        let unversioned = candidate("typescript", None, &[], Origin::Installed);
+        assert!(unversioned.satisfies(&VersionReq::STAR));
+        assert!(!unversioned.satisfies(&VersionReq::parse("^1").unwrap()));
+        let resolver = Resolver::new()
+            .candidate(installed("react", "2.1.0", &[("typescript", "*")]))
+            .candidate(unversioned);
+        assert_eq!(
+            picked(&resolver.resolve_all().unwrap()),
+            [("react", Some("2.1.0")), ("typescript", None)]
+        );
+    }
+
+    #[test]
+    fn invalid_versions_and_ranges_are_left_out() {
+        let c = candidate(
+            "react",
+            Some("two"),
+            &[("typescript", "not a range"), ("vite", "5")],
+            Origin::Installed,
+        );
+        assert_eq!(c.version, None);
+        assert_eq!(c.requires.len(), 1);
+        assert_eq!(c.requires[0].1.to_string(), "^5");
+    }
+
+    #[test]
+    fn from_project_reads_installed_skills() {
+        let dir = TempDir::new();
+        dir.write(
+            ".claude/skills/react/SKILL.md",
+            "---\nname: react\ndescription: React\nversion: 2.1.0\nrequires:\n  typescript: ^1\n---\n",
+        );
+        dir.write(
+            ".claude/skills/typescript/SKILL.md",
+            "---\nname: typescript\ndescription: TS\nversion: 1.4.0\n---\n",
+        );
+        dir.write(".claude/skills/broken/SKILL.md", "no frontmatter");
+        let resolver = Resolver::from_project(&Project::new(dir.path())).unwrap();
+        assert_eq!(resolver.installed(), ["react", "typescript"]);
+        assert_eq!(resolver.resolve_all().unwrap().len(), 2);
+    }
+
+    #[test]
+    fn registry_releases_are_added_newest_first_after_installed_ones() {
+        let dir = TempDir::new();
+        let store = Store::new(dir.path().join("registry"));
+        for version in ["1.0.0", "1.2.0", "2.0.0"] {
+            store
+                .publish(&test_archive(&dir, "lint", version), None)
+                .unwrap();
+        }
+        let server = Server::bind(store, "127.0.0.1:0").unwrap();
+        let client = Client::new(&format!("http://{}", server.addr().unwrap())).unwrap();
+        std::thread::spawn(move || server.run());
+
+        let resolver = Resolver::new()
+            .candidate(installed("app", "1.0.0", &[("lint", "^1")]))
+            .candidate(installed("lint", "1.0.0", &[]))
+            .registry(&client, &["missing".into()])
+            .unwrap();
+        let versions: Vec<String> = resolver.candidates["lint"]
+            .iter()
+            .map(|c| format!("{} {:?}", c.version.as_ref().unwrap(), c.origin))
+            .collect();
+        assert_eq!(
+            versions,
+            ["1.0.0 Installed", "2.0.0 Registry", "1.2.0 Registry"]
+        );
+        assert_eq!(resolver.candidates["missing"], []);
+        let resolved = resolver.resolve_all().unwrap();
+        assert_eq!(resolved[1].version.as_deref(), Some("1.0.0"));
+    }
+}
@@ -0,0 +1,296 @@
+//! Process isolation for skill scripts.
+//!
+//! On Linux a sandboxed script runs in its own network namespace, so it has
+//! no network unless allowed, and under a Landlock ruleset that lets it read
+//! and execute anything but write only to its working directory, the temp
+//! directory and `/dev/null`. Kernels without Landlock still get the
+//! network namespace; where no namespace can be created the script does not
+//! run at all. Other platforms cannot isolate scripts.
+
+use std::path::PathBuf;
+use std::process::Command;
+
+#[cfg(not(target_os = "linux"))]
+use crate::Error;
+use crate::Result;
+
+/// Isolation to apply to one script before it executes.
+pub(crate) struct Sandbox {
+    #[cfg(target_os = "linux")]
+    inner: linux::Sandbox,
+}
+
+impl Sandbox {
+    /// Prepare isolation in the parent, where failures can still be
+    /// reported with a message.
+    #[cfg(target_os = "linux")]
+    pub(crate) fn new(network: bool, writable: &[PathBuf]) -> Result<Self> {
+        Ok(Self {
+            inner: linux::Sandbox::new(network, writable)?,
+        })
+    }
+
+    #[cfg(not(target_os = "linux"))]
+    pub(crate) fn new(_network: bool, _writable: &[PathBuf]) -> Result<Self> {
+        Err(Error::Sandbox(
+            "scripts can only be isolated on Linux".to_string(),
+        ))
+    }
+
+    /// Whether the script will actually be isolated: in its own network
+    /// namespace, or under Landlock where the kernel enforces it.
+    pub(crate) fn isolates(&self) -> bool {
+        #[cfg(target_os = "linux")]
+        return self.inner.isolates();
+        #[cfg(not(target_os = "linux"))]
+        false
+    }
+
+    /// Apply the isolation in the child between fork and exec.
+    pub(crate) fn apply(self, command: &mut Command) {
+        #[cfg(target_os = "linux")]
+        self.inner.apply(command);
+        #[cfg(not(target_os = "linux"))]
+        let _ = command;
+    }
+}
+
+#[cfg(target_os = "linux")]
+mod linux {
+    use std::env;
+    use std::io;
+    use std::os::unix::process::CommandExt;
+    use std::path::PathBuf;
+    use std::process::Command;
+
+    use landlock::{
+        path_beneath_rules, Access, AccessFs, AccessNet, Ruleset, RulesetAttr, RulesetCreated,
+        RulesetCreatedAttr, ABI,
+    };
+
+    use crate::{Error, Result};
+
+    pub(super) struct Sandbox {
+        network: bool,
+        /// Whether the kernel enforces the ruleset; without it the ruleset
+        /// restricts nothing.
+        landlock: bool,
+        ruleset: RulesetCreated,
+        uid_map: Vec<u8>,
+        gid_map: Vec<u8>,
+    }
+
+    impl Sandbox {
+        pub(super) fn new(network: bool, writable: &[PathBuf]) -> Result<Self> {
+            let fs_abi = ABI::V3;
+            let mut ruleset = Ruleset::default()
+                .handle_access(AccessFs::from_all(fs_abi))
+                .map_err(landlock_error)?;
+            if !network {
+                // Kernels with Landlock network support also refuse TCP,
+                // on top of the namespace.
+                ruleset = ruleset
+                    .handle_access(AccessNet::from_all(ABI::V4))
+                    .map_err(landlock_error)?;
+            }
+            let mut writable = writable.to_vec();
+            writable.push(env::temp_dir());
+            writable.push(PathBuf::from("/dev/null"));
+            let ruleset = ruleset
+                .create()
+                .and_then(|r| r.add_rules(path_beneath_rules(["/"], AccessFs::from_read(fs_abi))))
+                .and_then(|r| {
+                    r.add_rules(path_beneath_rules(&writable, AccessFs::from_all(fs_abi)))
+                })
+                .map_err(landlock_error)?;
+            // SAFETY: getuid and getgid cannot fail.
+            let (uid, gid) = unsafe { (libc::getuid(), libc::getgid()) };
+            Ok(Self {
+                network,
+                landlock: landlock_supported(),
+                ruleset,
+                uid_map: format!("{uid} {uid} 1\n").into_bytes(),
+                gid_map: format!("{gid} {gid} 1\n").into_bytes(),
+            })
+        }
+
+        pub(super) fn isolates(&self) -> bool {
+            !self.network || self.landlock
+        }
+
+        pub(super) fn apply(self, command: &mut Command) {
+            let Self {
+                network,
+                landlock: _,
+                ruleset,
+                uid_map,
+                gid_map,
+            } = self;
+            let mut ruleset = Some(ruleset);
+            // SAFETY: the closure runs in the forked child, where only
+            // async-signal-safe calls are allowed: it makes raw syscalls on
+            // buffers prepared by the parent and does not allocate unless
+            // Landlock itself fails.
+            unsafe {
+                command.pre_exec(move || {
+                    if !network {
+                        unshare_network(&uid_map, &gid_map)?;
+                    }
+                    if let Some(ruleset) = ruleset.take() {
+                        ruleset
+                            .restrict_self()
+                            .map_err(|_| io::Error::from_raw_os_error(libc::EPERM))?;
+                    }
+                    Ok(())
+                });
+            }
+        }
+    }
+
+    /// Move into a fresh network namespace with only a loopback interface,
+    /// which starts down. Unprivileged users need a user namespace for that,
+    /// mapping their own ids so files they create keep their owner.
+    pub(super) fn unshare_network(uid_map: &[u8], gid_map: &[u8]) -> io::Result<()> {
+        // SAFETY: unshare only affects the calling process.
+        if unsafe { libc::unshare(libc::CLONE_NEWNET) } == 0 {
+            return Ok(());
+        }
+        // SAFETY: as above.
+        if unsafe { libc::unshare(libc::CLONE_NEWUSER | libc::CLONE_NEWNET) } != 0 {
+            return Err(io::Error::last_os_error());
+        }
+        write_proc(b"/proc/self/setgroups\0", b"deny")?;
+        write_proc(b"/proc/self/uid_map\0", uid_map)?;
+        write_proc(b"/proc/self/gid_map\0", gid_map)
+    }
+
+    /// Write `contents` to the NUL-terminated `path` with raw syscalls.
+    fn write_proc(path: &[u8], contents: &[u8]) -> io::Result<()> {
+        // SAFETY: `path` is NUL-terminated and both buffers outlive the
+        // calls.
+        unsafe {
+            let fd = libc::open(path.as_ptr().cast(), libc::O_WRONLY | libc::O_CLOEXEC);
+            if fd < 0 {
+                return Err(io::Error::last_os_error());
+            }
+            let written = libc::write(fd, contents.as_ptr().cast(), contents.len());
+            let error = io::Error::last_os_error();
+            libc::close(fd);
+            match written < 0 {
+                true => Err(error),
+                false => Ok(()),
+            }
+        }
+    }
+
+    fn landlock_error(error: landlock::RulesetError) -> Error {
+        Error::Sandbox(error.to_string())
+    }
+
+    /// Whether the kernel enforces Landlock, asked the way the crate does.
+    pub(super) fn landlock_supported() -> bool {
+        const LANDLOCK_CREATE_RULESET_VERSION: libc::c_uint = 1;
+        // SAFETY: with a null attribute and the version flag the call only
+        // returns the supported ABI.
+        let abi = unsafe {
+            libc::syscall(
+                libc::SYS_landlock_create_ruleset,
+                std::ptr::null::<libc::c_void>(),
+                0usize,
+                LANDLOCK_CREATE_RULESET_VERSION,
+            )
+        };
+        abi >= 1
+    }
+}
+
+#[cfg(all(test, target_os = "linux"))]
+mod tests {
+    use std::os::unix::process::CommandExt;
+    use std::path::Path;
+    use std::process::Command;
+    use std::sync::Arc;
+
+    use super::linux::{landlock_supported, unshare_network};
+    use crate::script::{Runner, ScriptOutput};
+    use crate::test_support::TempDir;
+    use crate::tokens::{CharEstimate, TokenCounter};
+    use crate::{Error, Result};
+
+    fn runner(dir: &TempDir) -> Runner {
+        Runner::new(TokenCounter::new(Arc::new(CharEstimate))).working_dir(dir.path())
+    }
+
+    /// Write `body` as an executable script in `dir`.
+    fn script(dir: &TempDir, body: &str) -> std::path::PathBuf {
+        use std::os::unix::fs::PermissionsExt;
+        let path = dir.write("probe.sh", format!("#!/bin/sh\n{body}\n"));
+        std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o755)).unwrap();
+        path
+    }
+
+    /// Whether this process may create a network namespace, tried in a
+    /// child the way the sandbox does.
+    fn can_unshare() -> bool {
+        // SAFETY: getuid and getgid cannot fail.
+        let (uid, gid) = unsafe { (libc::getuid(), libc::getgid()) };
+        let uid_map = format!("{uid} {uid} 1\n").into_bytes();
+        let gid_map = format!("{gid} {gid} 1\n").into_bytes();
+        let mut command = Command::new("true");
+        // SAFETY: as in the sandbox, the closure only makes raw syscalls.
+        unsafe {
+            command.pre_exec(move || unshare_network(&uid_map, &gid_map));
+        }
+        command.status().is_ok_and(|status| status.success())
+    }
+
+    /// Where no namespace can be created a script without network access
+    /// must not run at all.
+    fn isolated(output: Result<ScriptOutput>) -> Option<ScriptOutput> {
+        match can_unshare() {
+            true => Some(output.unwrap()),
+            false => {
+                assert!(matches!(output, Err(Error::Sandbox(_))), "{output:?}");
+                None
+            }
+        }
+    }
+
+    #[test]
+    fn sandboxed_scripts_only_see_loopback() {
+        let dir = TempDir::new();
+        let path = script(&dir, "tail -n +3 /proc/net/dev | cut -d: -f1");
+        let Some(output) = isolated(runner(&dir).run(&path, &[])) else {
+            return;
+        };
+        assert!(output.sandboxed);
+        assert_eq!(output.stdout.trim(), "lo");
+    }
+
+    #[test]
+    fn sandboxed_scripts_write_only_where_allowed() {
+        let dir = TempDir::new();
+        let outside = concat!(env!("CARGO_MANIFEST_DIR"), "/target-sandbox-probe");
+        let path = script(&dir, "touch inside && touch \"$1\"; echo $?");
+        let output = runner(&dir).run(&path, &[outside.to_string()]);
+        let Some(output) = isolated(output) else {
+            return;
+        };
+        let escaped = Path::new(outside).exists();
+        let _ = std::fs::remove_file(outside);
+        assert!(dir.path().join("inside").is_file());
+        // Without Landlock only the network is isolated.
+        assert_eq!(escaped, !landlock_supported(), "{}", output.text());
+    }
+
+    #[test]
+    fn scripts_report_the_isolation_actually_applied() {
+        let dir = TempDir::new();
+        let path = script(&dir, "true");
+        // With the network allowed only Landlock is left to isolate it.
+        let output = runner(&dir).network(true).run(&path, &[]).unwrap();
+        assert_eq!(output.sandboxed, landlock_supported());
+        let output = runner(&dir).sandbox(false).run(&path, &[]).unwrap();
+        assert!(!output.sandboxed);
+    }
+}
@@ -0,0 +1,290 @@
+//! `skills new`: create a skill with the layout the README documents.
+
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::skill::{self, is_kebab_case, Frontmatter, SKILL_FILE};
+use crate::{Error, Result};
+
+const DEFAULT_DESCRIPTION: &str =
+    "Breve descripción de qué hace este skill (usado para decidir cuándo cargarlo)";
+
+/// Built-in starting points.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Template {
+    /// A lone `SKILL.md`.
+    Basic,
+    /// `SKILL.md` plus `references/examples.md`.
+    WithReferences,
+    /// `SKILL.md` plus an executable `scripts/process.sh`.
+    WithScripts,
+    /// The README's complete `api-testing` example.
+    ApiTesting,
+}
+
+impl Template {
+    pub const ALL: &'static [Template] = &[
+        Template::Basic,
+        Template::WithReferences,
+        Template::WithScripts,
+        Template::ApiTesting,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Template::Basic => "basic",
+            Template::WithReferences => "with-references",
+            Template::WithScripts => "with-scripts",
+            Template::ApiTesting => "api-testing",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL.iter().copied().find(|t| t.name() == name)
+    }
+
+    fn body(self) -> &'static str {
+        match self {
+            Template::Basic => include_str!("../templates/basic.md"),
+            Template::WithReferences => include_str!("../templates/with-references.md"),
+            Template::WithScripts => include_str!("../templates/with-scripts.md"),
+            Template::ApiTesting => include_str!("../templates/api-testing.md"),
+        }
+    }
+
+    /// Files besides `SKILL.md`: path relative to the skill, contents and
+    /// whether the file is executable.
+    fn extra_files(self) -> &'static [(&'static str, &'static str, bool)] {
+        match self {
+            Template::Basic => &[],
+            Template::WithReferences => &[(
+                "references/examples.md",
+                include_str!("../templates/examples.md"),
+                false,
+            )],
+            Template::WithScripts => &[(
+                "scripts/process.sh",
+                include_str!("../templates/process.sh"),
+                true,
+            )],
+            Template::ApiTesting => &[(
+                "references/test-template.md",
+                include_str!("../templates/test-template.md"),
+                false,
+            )],
+        }
+    }
+
+    fn default_description(self) -> &'static str {
+        match self {
+            Template::ApiTesting => "Workflow para testing de APIs con Postman y Jest",
+            _ => DEFAULT_DESCRIPTION,
+        }
+    }
+
+    fn default_globs(self) -> &'static [&'static str] {
+        match self {
+            Template::ApiTesting => &["*.test.ts"],
+            _ => &[],
+        }
+    }
+}
+
+impl fmt::Display for Template {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+/// Options for [`create`].
+#[derive(Debug, Clone)]
+pub struct NewSkill {
+    pub name: String,
+    pub template: Template,
+    /// Replaces the template's placeholder description.
+    pub description: Option<String>,
+    /// Replaces the template's `globs`.
+    pub globs: Vec<String>,
+    /// Overwrite files of an existing skill.
+    pub force: bool,
+}
+
+impl NewSkill {
+    pub fn new(name: impl Into<String>, template: Template) -> Self {
+        Self {
+            name: name.into(),
+            template,
+            description: None,
+            globs: Vec::new(),
+            force: false,
+        }
+    }
+}
+
+/// Create `<skills_dir>/<name>/` from a template and return the files
+/// written, `SKILL.md` first.
+pub fn create(skills_dir: &Path, options: &NewSkill) -> Result<Vec<PathBuf>> {
+    let name = &options.name;
+    if !is_kebab_case(name) {
+        return Err(Error::InvalidName(name.clone()));
+    }
+    let dir = skills_dir.join(name);
+    if dir.exists() && !options.force {
+        return Err(Error::AlreadyExists(dir));
+    }
+
+    let template = options.template;
+    let frontmatter = Frontmatter {
+        name: Some(name.clone()),
+        description: Some(
+            options
+                .description
+                .clone()
+                .unwrap_or_else(|| template.default_description().to_string()),
+        ),
+        globs: match options.globs.is_empty() {
+            true => template
+                .default_globs()
+                .iter()
+                .map(|g| g.to_string())
+                .collect(),
+            false => options.globs.clone(),
+        },
+        ..Frontmatter::default()
+    };
+    let body = format!("\n{}", fill(template.body(), name));
+
+    let mut written = Vec::new();
+    let skill_file = dir.join(SKILL_FILE);
+    write(&skill_file, &skill::render(&frontmatter, &body), false)?;
+    written.push(skill_file);
+    for (path, contents, executable) in template.extra_files() {
+        let path = dir.join(path);
+        write(&path, &fill(contents, name), *executable)?;
+        written.push(path);
+    }
+    Ok(written)
+}
+
+/// Substitute `{{name}}` and `{{title}}` in template text.
+fn fill(text: &str, name: &str) -> String {
+    text.replace("{{name}}", name)
+        .replace("{{title}}", &title(name))
+}
+
+/// `my-new-skill` → `My New Skill`; `api-testing` → `API Testing`.
+fn title(name: &str) -> String {
+    name.split('-')
+        .map(|word| match word {
+            "api" | "cli" | "css" | "html" | "json" | "sql" | "ui" => word.to_uppercase(),
+            _ => {
+                let mut chars = word.chars();
+                chars
+                    .next()
+                    .map(|c| c.to_uppercase().chain(chars).collect())
+                    .unwrap_or_default()
+            }
+        })
+        .collect::<Vec<String>>()
+        .join(" ")
+}
+
+fn write(path: &Path, contents: &str, executable: bool) -> Result<()> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(|e| Error::io(parent, e))?;
+    }
+    fs::write(path, contents).map_err(|e| Error::io(path, e))?;
+    if executable {
+        set_executable(path)?;
+    }
+    Ok(())
+}
+
+#[cfg(unix)]
+fn set_executable(path: &Path) -> Result<()> {
+    use std::os::unix::fs::PermissionsExt;
+    fs::set_permissions(path, fs::Permissions::from_mode(0o755)).map_err(|e| Error::io(path, e))
+}
+
+#[cfg(not(unix))]
+fn set_executable(_path: &Path) -> Result<()> {
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::TempDir;
+    use crate::Skill;
+
+    #[test]
+    fn every_template_creates_a_skill_that_parses() {
+        let dir = TempDir::new();
+        for &template in Template::ALL {
+            let name = format!("demo-{template}");
+            let written = create(dir.path(), &NewSkill::new(&name, template)).unwrap();
+            assert_eq!(written[0], dir.path().join(&name).join(SKILL_FILE));
+            assert_eq!(written.len(), 1 + template.extra_files().len());
+            let skill = Skill::from_path(&written[0]).unwrap();
+            assert_eq!(skill.name(), Some(name.as_str()));
+            assert!(!skill.source().contains("{{"), "{template}");
+        }
+    }
+
+    #[test]
+    fn options_replace_the_template_defaults() {
+        let dir = TempDir::new();
+        let mut options = NewSkill::new("api-testing", Template::ApiTesting);
+        options.description = Some("Test the billing API with Jest".into());
+        options.globs = vec!["*.spec.ts".into()];
+        let written = create(dir.path(), &options).unwrap();
+        let skill = Skill::from_path(&written[0]).unwrap();
+        assert_eq!(skill.description(), Some("Test the billing API with Jest"));
+        assert_eq!(skill.frontmatter().globs, ["*.spec.ts"]);
+        assert!(skill.sections()[0].title.contains("API Testing"));
+    }
+
+    #[test]
+    fn names_must_be_kebab_case() {
+        let dir = TempDir::new();
+        for name in ["../escape", "Demo", "a--b", ""] {
+            assert!(
+                matches!(
+                    create(dir.path(), &NewSkill::new(name, Template::Basic)),
+                    Err(Error::InvalidName(_))
+                ),
+                "{name}"
+            );
+        }
+        assert_eq!(fs::read_dir(dir.path()).unwrap().count(), 0);
+    }
+
+    #[test]
+    fn existing_skills_are_only_overwritten_with_force() {
+        let dir = TempDir::new();
+        let mut options = NewSkill::new("demo", Template::Basic);
+        create(dir.path(), &options).unwrap();
+        assert!(matches!(
+            create(dir.path(), &options),
+            Err(Error::AlreadyExists(path)) if path == dir.path().join("demo")
+        ));
+        options.force = true;
+        assert!(create(dir.path(), &options).is_ok());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn template_scripts_are_executable() {
+        let dir = TempDir::new();
+        let written = create(dir.path(), &NewSkill::new("demo", Template::WithScripts)).unwrap();
+        assert!(crate::lint::is_executable(&written[1]));
+    }
+
+    #[test]
+    fn titles_capitalize_words_and_acronyms() {
+        assert_eq!(title("my-new-skill"), "My New Skill");
+        assert_eq!(title("api-testing"), "API Testing");
+        assert_eq!(title("css-ui"), "CSS UI");
+    }
+}
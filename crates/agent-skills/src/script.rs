@@ -0,0 +1,525 @@
+//! Running skill scripts and capturing their output.
+//!
+//! Only a script's output enters an agent's context window, so this is what
+//! Level 3 accounting measures. A [`Runner`] executes scripts with a cleared
+//! environment, a time limit and a cap on captured output, and by default
+//! inside a sandbox without network access.
+
+use std::collections::VecDeque;
+use std::env;
+use std::fmt::Write as _;
+use std::io::{self, Read};
+use std::path::{Path, PathBuf};
+use std::process::{Child, Command, ExitStatus, Stdio};
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use serde::Serialize;
+
+use crate::config::ScriptsConfig;
+use crate::sandbox::Sandbox;
+use crate::tokens::TokenCounter;
+use crate::{Error, Result};
+
+/// Default limit for a single script run.
+pub const DEFAULT_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Default cap on the bytes kept from each of stdout and stderr.
+pub const DEFAULT_MAX_OUTPUT: usize = 32 * 1024;
+
+/// How long to keep reading output after the script is killed. Anything
+/// that left its process group may hold the pipes open indefinitely.
+const DRAIN_GRACE: Duration = Duration::from_secs(1);
+
+/// Environment variables passed through to scripts by default.
+pub const DEFAULT_ENV: &[&str] = &[
+    "HOME", "LANG", "LC_ALL", "LC_CTYPE", "LOGNAME", "PATH", "SHELL", "TERM", "TMPDIR", "TZ",
+    "USER",
+];
+
+/// What a script printed and how it exited.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ScriptOutput {
+    /// Exit code, or `None` when killed by a signal or the timeout.
+    pub code: Option<i32>,
+    /// Signal that killed the script, other than the timeout's.
+    pub signal: Option<i32>,
+    pub timed_out: bool,
+    pub stdout: String,
+    pub stderr: String,
+    /// Bytes cut from the middle of stdout and stderr together.
+    pub truncated: usize,
+    /// Tokens of [`ScriptOutput::text`].
+    pub tokens: usize,
+    pub elapsed_ms: u64,
+    /// Whether the sandbox isolated the script. Off when it is turned off,
+    /// and when the network is allowed on a kernel without Landlock.
+    pub sandboxed: bool,
+}
+
+impl ScriptOutput {
+    pub fn success(&self) -> bool {
+        self.code == Some(0)
+    }
+
+    /// The text that would be handed to the agent.
+    pub fn text(&self) -> String {
+        match (self.stdout.is_empty(), self.stderr.is_empty()) {
+            (_, true) => self.stdout.clone(),
+            (true, false) => self.stderr.clone(),
+            (false, false) => format!("{}\n{}", self.stdout.trim_end(), self.stderr),
+        }
+    }
+}
+
+/// Executes scripts under one set of limits.
+#[derive(Clone)]
+pub struct Runner {
+    counter: TokenCounter,
+    working_dir: PathBuf,
+    timeout: Duration,
+    env: Vec<String>,
+    max_output: usize,
+    network: bool,
+    sandbox: bool,
+    writable: Vec<PathBuf>,
+}
+
+impl Runner {
+    /// A runner with the default limits, counting output with `counter`.
+    pub fn new(counter: TokenCounter) -> Self {
+        Self {
+            counter,
+            working_dir: PathBuf::from("."),
+            timeout: DEFAULT_TIMEOUT,
+            env: DEFAULT_ENV.iter().map(|v| v.to_string()).collect(),
+            max_output: DEFAULT_MAX_OUTPUT,
+            network: false,
+            sandbox: true,
+            writable: Vec::new(),
+        }
+    }
+
+    /// Apply the `[scripts]` section of `skills.toml`.
+    pub fn config(self, config: &ScriptsConfig) -> Self {
+        let mut runner = self
+            .timeout(Duration::from_secs(config.timeout))
+            .max_output(config.max_output)
+            .network(config.network)
+            .sandbox(config.sandbox);
+        for name in &config.env {
+            runner = runner.allow_env(name);
+        }
+        runner
+    }
+
+    pub fn counter(mut self, counter: TokenCounter) -> Self {
+        self.counter = counter;
+        self
+    }
+
+    /// Directory scripts run in. The sandbox lets them write to it.
+    pub fn working_dir(mut self, dir: impl Into<PathBuf>) -> Self {
+        self.working_dir = dir.into();
+        self
+    }
+
+    /// Time limit for a single run, after which the script and everything
+    /// it started are killed.
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// Replace the environment variables passed through; every other
+    /// variable is removed.
+    pub fn env(mut self, names: impl IntoIterator<Item = impl Into<String>>) -> Self {
+        self.env = names.into_iter().map(Into::into).collect();
+        self
+    }
+
+    /// Pass one more environment variable through.
+    pub fn allow_env(mut self, name: impl Into<String>) -> Self {
+        let name = name.into();
+        if !self.env.contains(&name) {
+            self.env.push(name);
+        }
+        self
+    }
+
+    /// Keep at most this many bytes of each stream, half from the start and
+    /// half from the end. Zero keeps everything.
+    pub fn max_output(mut self, bytes: usize) -> Self {
+        self.max_output = bytes;
+        self
+    }
+
+    /// Let sandboxed scripts use the network. Off by default.
+    pub fn network(mut self, network: bool) -> Self {
+        self.network = network;
+        self
+    }
+
+    /// Run scripts in the sandbox. On by default; off runs them with the
+    /// user's full permissions.
+    pub fn sandbox(mut self, sandbox: bool) -> Self {
+        self.sandbox = sandbox;
+        self
+    }
+
+    /// Let sandboxed scripts write under `path` as well.
+    pub fn writable(mut self, path: impl Into<PathBuf>) -> Self {
+        self.writable.push(path.into());
+        self
+    }
+
+    /// Execute `script` with `args`.
+    pub fn run(&self, script: &Path, args: &[String]) -> Result<ScriptOutput> {
+        // A relative program path would be resolved against the working
+        // directory in the child.
+        let program = absolute(script)?;
+        let mut command = Command::new(&program);
+        command
+            .args(args)
+            .current_dir(&self.working_dir)
+            .env_clear()
+            .stdin(Stdio::null())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped());
+        for name in &self.env {
+            if let Some(value) = env::var_os(name) {
+                command.env(name, value);
+            }
+        }
+        #[cfg(unix)]
+        std::os::unix::process::CommandExt::process_group(&mut command, 0);
+        let sandboxed = match self.sandbox {
+            true => {
+                let mut writable = self.writable.clone();
+                writable.push(absolute(&self.working_dir)?);
+                let sandbox = Sandbox::new(self.network, &writable)?;
+                let isolates = sandbox.isolates();
+                sandbox.apply(&mut command);
+                isolates
+            }
+            false => false,
+        };
+
+        let started = Instant::now();
+        let mut child = command.spawn().map_err(|e| self.spawn_error(&program, e))?;
+        let stdout = drain(child.stdout.take(), self.max_output);
+        let stderr = drain(child.stderr.take(), self.max_output);
+        let (status, timed_out) = loop {
+            match child.try_wait().map_err(|e| Error::io(script, e))? {
+                Some(status) => {
+                    // Whatever the script left behind would hold the pipes
+                    // open and outlive the run.
+                    kill(&mut child);
+                    break (Some(status), false);
+                }
+                None if started.elapsed() >= self.timeout => {
+                    kill(&mut child);
+                    break (None, true);
+                }
+                None => thread::sleep(Duration::from_millis(10)),
+            }
+        };
+        let elapsed = started.elapsed();
+        let deadline = Instant::now() + DRAIN_GRACE;
+        let (stdout, stdout_cut) = stdout.finish(deadline);
+        let (stderr, stderr_cut) = stderr.finish(deadline);
+        let mut output = ScriptOutput {
+            code: status.as_ref().and_then(ExitStatus::code),
+            signal: status.as_ref().and_then(signal),
+            timed_out,
+            stdout,
+            stderr,
+            truncated: stdout_cut + stderr_cut,
+            tokens: 0,
+            elapsed_ms: elapsed.as_millis().try_into().unwrap_or(u64::MAX),
+            sandboxed,
+        };
+        output.tokens = self.counter.count(&output.text());
+        Ok(output)
+    }
+
+    /// The child reports a failed sandbox setup as a bare errno, so tell it
+    /// apart from an unrunnable script by the errors only setup returns.
+    fn spawn_error(&self, program: &Path, error: io::Error) -> Error {
+        #[cfg(unix)]
+        if self.sandbox
+            && matches!(
+                error.raw_os_error(),
+                Some(libc::EPERM | libc::EUSERS | libc::ENOSPC)
+            )
+        {
+            return Error::Sandbox(format!(
+                "{}: cannot isolate the script ({error}); allow network access or \
+                 turn the sandbox off in the [scripts] section of skills.toml",
+                program.display()
+            ));
+        }
+        Error::io(program, error)
+    }
+}
+
+fn absolute(path: &Path) -> Result<PathBuf> {
+    match path.is_relative() {
+        true => Ok(env::current_dir()
+            .map_err(|e| Error::io(path, e))?
+            .join(path)),
+        false => Ok(path.to_path_buf()),
+    }
+}
+
+/// Kill the script, if still running, and anything it started in its
+/// process group.
+fn kill(child: &mut Child) {
+    #[cfg(unix)]
+    if let Ok(pid) = libc::pid_t::try_from(child.id()) {
+        // SAFETY: kill has no memory-safety preconditions.
+        unsafe { libc::kill(-pid, libc::SIGKILL) };
+    }
+    let _ = child.kill();
+    let _ = child.wait();
+}
+
+#[cfg(unix)]
+fn signal(status: &ExitStatus) -> Option<i32> {
+    std::os::unix::process::ExitStatusExt::signal(status)
+}
+
+#[cfg(not(unix))]
+fn signal(_status: &ExitStatus) -> Option<i32> {
+    None
+}
+
+/// Read a pipe on a separate thread so a chatty script cannot fill it and
+/// block while we wait for it to exit.
+fn drain(pipe: Option<impl Read + Send + 'static>, limit: usize) -> Drain {
+    let capture = Arc::new(Mutex::new(Capture::new(limit)));
+    let shared = Arc::clone(&capture);
+    let reader = thread::spawn(move || {
+        let Some(mut pipe) = pipe else {
+            return;
+        };
+        let mut buf = [0; 8192];
+        loop {
+            match pipe.read(&mut buf) {
+                Ok(0) => break,
+                Ok(n) => lock(&shared).push(&buf[..n]),
+                Err(e) if e.kind() == io::ErrorKind::Interrupted => {}
+                Err(_) => break,
+            }
+        }
+    });
+    Drain { reader, capture }
+}
+
+/// A pipe being read by [`drain`].
+struct Drain {
+    reader: thread::JoinHandle<()>,
+    capture: Arc<Mutex<Capture>>,
+}
+
+impl Drain {
+    /// What was read once the pipe closes, or by `deadline` if something
+    /// that escaped the process group still holds it open. The reader is
+    /// then left to finish on its own.
+    fn finish(self, deadline: Instant) -> (String, usize) {
+        while !self.reader.is_finished() && Instant::now() < deadline {
+            thread::sleep(Duration::from_millis(10));
+        }
+        let capture = lock(&self.capture);
+        capture.text()
+    }
+}
+
+fn lock(capture: &Mutex<Capture>) -> MutexGuard<'_, Capture> {
+    capture.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+/// The first and last bytes of a stream, up to a limit.
+struct Capture {
+    head_limit: usize,
+    tail_limit: usize,
+    head: Vec<u8>,
+    tail: VecDeque<u8>,
+    cut: usize,
+}
+
+impl Capture {
+    fn new(limit: usize) -> Self {
+        let (head_limit, tail_limit) = match limit {
+            0 => (usize::MAX, 0),
+            limit => (limit - limit / 2, limit / 2),
+        };
+        Self {
+            head_limit,
+            tail_limit,
+            head: Vec::new(),
+            tail: VecDeque::new(),
+            cut: 0,
+        }
+    }
+
+    fn push(&mut self, bytes: &[u8]) {
+        let room = self.head_limit - self.head.len();
+        let (head, rest) = bytes.split_at(room.min(bytes.len()));
+        self.head.extend_from_slice(head);
+        self.tail.extend(rest);
+        let over = self.tail.len().saturating_sub(self.tail_limit);
+        self.tail.drain(..over);
+        self.cut += over;
+    }
+
+    /// The kept text, with a marker where bytes were cut, and how many.
+    fn text(&self) -> (String, usize) {
+        let mut text = String::from_utf8_lossy(&self.head).into_owned();
+        if self.cut > 0 {
+            if !text.is_empty() && !text.ends_with('\n') {
+                text.push('\n');
+            }
+            let _ = writeln!(text, "[... {} bytes truncated ...]", self.cut);
+        }
+        let (front, back) = self.tail.as_slices();
+        text.push_str(&String::from_utf8_lossy(&[front, back].concat()));
+        (text, self.cut)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::TempDir;
+    use crate::tokens::CharEstimate;
+
+    fn runner(dir: &TempDir) -> Runner {
+        Runner::new(TokenCounter::new(Arc::new(CharEstimate)))
+            .working_dir(dir.path())
+            .sandbox(false)
+    }
+
+    #[cfg(unix)]
+    fn script(dir: &TempDir, name: &str, body: &str) -> PathBuf {
+        use std::os::unix::fs::PermissionsExt;
+        let path = dir.write(name, format!("#!/bin/sh\n{body}\n"));
+        std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o755)).unwrap();
+        path
+    }
+
+    #[test]
+    fn capture_keeps_the_head_and_tail() {
+        let mut capture = Capture::new(8);
+        capture.push(b"0123");
+        capture.push(b"456789abcdef");
+        assert_eq!(
+            capture.text(),
+            ("0123\n[... 8 bytes truncated ...]\ncdef".to_string(), 8)
+        );
+        let mut unlimited = Capture::new(0);
+        unlimited.push(&[b'x'; 100]);
+        assert_eq!(unlimited.text().1, 0);
+    }
+
+    #[test]
+    fn text_joins_stdout_and_stderr() {
+        let output = |stdout: &str, stderr: &str| ScriptOutput {
+            code: Some(0),
+            signal: None,
+            timed_out: false,
+            stdout: stdout.into(),
+            stderr: stderr.into(),
+            truncated: 0,
+            tokens: 0,
+            elapsed_ms: 0,
+            sandboxed: false,
+        };
+        assert_eq!(output("out\n", "").text(), "out\n");
+        assert_eq!(output("", "err\n").text(), "err\n");
+        assert_eq!(output("out\n", "err\n").text(), "out\nerr\n");
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn scripts_run_with_arguments_in_the_working_directory() {
+        let dir = TempDir::new();
+        let path = script(
+            &dir,
+            "run.sh",
+            "echo \"$1 $(basename \"$PWD\")\"; echo oops >&2; exit 3",
+        );
+        let output = runner(&dir).run(&path, &["hello".into()]).unwrap();
+        let name = dir.path().file_name().unwrap().to_string_lossy();
+        assert_eq!(output.stdout, format!("hello {name}\n"));
+        assert_eq!(output.stderr, "oops\n");
+        assert_eq!(output.code, Some(3));
+        assert!(!output.success());
+        assert_eq!(output.tokens, output.text().chars().count().div_ceil(4));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn the_environment_is_cleared_except_for_allowed_names() {
+        let dir = TempDir::new();
+        let path = script(&dir, "env.sh", "echo \"[$PATH][$CARGO_PKG_NAME]\"");
+        let output = runner(&dir).run(&path, &[]).unwrap();
+        assert!(output.stdout.ends_with("][]\n"), "{}", output.stdout);
+        let output = runner(&dir)
+            .allow_env("CARGO_PKG_NAME")
+            .run(&path, &[])
+            .unwrap();
+        assert!(
+            output.stdout.ends_with("[agent-skills]\n"),
+            "{}",
+            output.stdout
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn scripts_are_killed_at_the_timeout() {
+        let dir = TempDir::new();
+        let path = script(&dir, "slow.sh", "echo started; sleep 30");
+        let output = runner(&dir)
+            .timeout(Duration::from_millis(200))
+            .run(&path, &[])
+            .unwrap();
+        assert!(output.timed_out);
+        assert_eq!(output.code, None);
+        assert_eq!(output.stdout, "started\n");
+        assert!(output.elapsed_ms < 5_000);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn a_process_that_escapes_the_group_does_not_hang_the_run() {
+        let dir = TempDir::new();
+        let path = script(&dir, "escape.sh", "echo done; setsid sleep 30 &");
+        let started = Instant::now();
+        let output = runner(&dir).run(&path, &[]).unwrap();
+        assert!(started.elapsed() < Duration::from_secs(10));
+        assert_eq!(output.stdout, "done\n");
+        assert!(output.success());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn output_is_capped() {
+        let dir = TempDir::new();
+        let path = script(&dir, "loud.sh", "head -c 10000 /dev/zero");
+        let output = runner(&dir).max_output(100).run(&path, &[]).unwrap();
+        assert_eq!(output.truncated, 9900);
+        assert!(output.stdout.contains("[... 9900 bytes truncated ...]"));
+    }
+
+    #[test]
+    fn missing_scripts_are_io_errors() {
+        let dir = TempDir::new();
+        assert!(matches!(
+            runner(&dir).run(&dir.path().join("missing.sh"), &[]),
+            Err(Error::Io { .. })
+        ));
+    }
+}
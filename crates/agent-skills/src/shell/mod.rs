@@ -0,0 +1,519 @@
+//! Static safety analysis of shell scripts.
+//!
+//! Skills ship `scripts/` that agents run on developer machines, often from
+//! collections written by someone else. [`analyze`] reads a bash or POSIX
+//! shell script without running it and reports constructs worth a second
+//! look before it does: recursive deletes of variable paths, downloads
+//! piped into an interpreter, `sudo`, writes outside the project, network
+//! access and `eval` of expanded text.
+//!
+//! The parser is deliberately forgiving: it understands quoting,
+//! expansions, pipelines, redirections and substitutions, which is enough
+//! to find commands, and never fails on syntax it does not know.
+
+mod parse;
+
+use std::ops::Range;
+use std::path::Path;
+
+use serde::Serialize;
+
+use parse::{Command, Pipeline, Word};
+
+/// What a [`Finding`] is about.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Concern {
+    /// `rm -r` of a path built from a variable that may be empty.
+    RecursiveRemove,
+    /// A download piped or substituted into an interpreter.
+    PipeToShell,
+    /// `sudo`, `doas`, `su` and friends.
+    Privilege,
+    /// A write to an absolute, home or parent-directory path.
+    WriteOutsideProject,
+    Network,
+    /// `eval` or `sh -c` of expanded text.
+    Eval,
+}
+
+/// One dangerous construct.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Finding {
+    pub concern: Concern,
+    pub message: String,
+    /// Byte range in the script.
+    pub range: Range<usize>,
+}
+
+/// Interpreters that run a program read from stdin or given as text.
+const INTERPRETERS: &[&str] = &[
+    "sh", "bash", "zsh", "dash", "ksh", "fish", "python", "python3", "perl", "ruby", "node",
+];
+const DOWNLOADERS: &[&str] = &["curl", "wget", "fetch"];
+const NETWORK: &[&str] = &[
+    "curl", "wget", "fetch", "nc", "ncat", "netcat", "socat", "telnet", "ssh", "scp", "sftp",
+    "ftp", "rsync",
+];
+const GIT_NETWORK: &[&str] = &["clone", "fetch", "pull", "push", "ls-remote"];
+const PRIVILEGE: &[&str] = &["sudo", "doas", "pkexec", "su"];
+/// Commands run with the rest of the line, skipped to find the real one.
+const WRAPPERS: &[&str] = &[
+    "env", "command", "exec", "nohup", "time", "nice", "xargs", "builtin",
+];
+/// Reserved words that may precede a command.
+const KEYWORDS: &[&str] = &[
+    "if", "then", "else", "elif", "while", "until", "do", "!", "{",
+];
+/// Commands whose non-option arguments are all written to.
+const WRITES_ARGS: &[&str] = &[
+    "tee", "touch", "mkdir", "rmdir", "rm", "chmod", "chown", "truncate",
+];
+/// Commands whose last argument is written to.
+const WRITES_LAST: &[&str] = &["cp", "mv", "install", "ln"];
+/// Absolute paths scripts may write to.
+const WRITABLE: &[&str] = &[
+    "/tmp",
+    "/dev/null",
+    "/dev/tty",
+    "/dev/stdout",
+    "/dev/stderr",
+    "/dev/fd/",
+    "/proc/self/fd/",
+];
+
+/// Whether `path` holding `source` is a shell script: a `.sh` or `.bash`
+/// file, or one whose shebang names a POSIX shell.
+pub fn is_shell_script(path: &Path, source: &str) -> bool {
+    if matches!(
+        path.extension().and_then(|e| e.to_str()),
+        Some("sh" | "bash")
+    ) {
+        return true;
+    }
+    let Some(shebang) = source.lines().next().and_then(|l| l.strip_prefix("#!")) else {
+        return false;
+    };
+    let mut parts = shebang.split_whitespace();
+    let program = match parts.next().map(basename) {
+        Some("env") => parts.find(|p| !p.starts_with('-')).map(basename),
+        other => other,
+    };
+    matches!(program, Some("sh" | "bash" | "dash" | "ksh" | "zsh"))
+}
+
+/// Every dangerous construct in a shell script, in source order.
+pub fn analyze(source: &str) -> Vec<Finding> {
+    let mut findings = Vec::new();
+    analyze_range(source, 0..source.len(), &mut findings);
+    findings.sort_by_key(|f| (f.range.start, f.concern));
+    findings.dedup();
+    findings
+}
+
+fn analyze_range(source: &str, range: Range<usize>, out: &mut Vec<Finding>) {
+    for pipeline in parse::parse(source, range) {
+        for (i, command) in pipeline.iter().enumerate() {
+            check(source, &pipeline, i, command, out);
+            let words = command
+                .words
+                .iter()
+                .chain(command.redirects.iter().map(|r| &r.target));
+            for word in words {
+                for body in &word.substitutions {
+                    analyze_range(source, body.clone(), out);
+                }
+            }
+        }
+    }
+}
+
+fn check(
+    source: &str,
+    pipeline: &Pipeline,
+    index: usize,
+    command: &Command,
+    out: &mut Vec<Finding>,
+) {
+    let (words, privileged) = effective(&command.words);
+    if let Some(word) = privileged {
+        out.push(finding(
+            Concern::Privilege,
+            word,
+            format!(
+                "runs `{}`; skills should not need elevated privileges",
+                shown(&word.value)
+            ),
+        ));
+    }
+    for redirect in &command.redirects {
+        let target = &redirect.target;
+        if target.value.starts_with("/dev/tcp/") || target.value.starts_with("/dev/udp/") {
+            out.push(finding(
+                Concern::Network,
+                target,
+                format!(
+                    "opens a network connection through `{}`",
+                    shown(&target.value)
+                ),
+            ));
+        } else if redirect.op.contains('>') && is_outside(&target.value) {
+            out.push(finding(
+                Concern::WriteOutsideProject,
+                target,
+                format!("writes to `{}`, outside the project", shown(&target.value)),
+            ));
+        }
+    }
+    let Some(program) = words.first() else {
+        return;
+    };
+    let name = basename(&program.value);
+    let args = &words[1..];
+
+    // Recursive deletes are judged on their own.
+    if name != "rm" || !remove(args, out) {
+        writes(name, args, out);
+    }
+
+    if NETWORK.contains(&name) || (name == "git" && git_network(args)) {
+        out.push(finding(
+            Concern::Network,
+            program,
+            format!("`{name}` uses the network; sandboxed scripts run without it unless allowed"),
+        ));
+    }
+
+    let downloaded = |word: &Word| {
+        word.substitutions
+            .iter()
+            .any(|body| starts_with_download(source, body.clone()))
+    };
+    let is_interpreter = INTERPRETERS.contains(&name);
+    let piped = is_interpreter
+        && pipeline[..index]
+            .iter()
+            .any(|c| effective(&c.words).0.first().is_some_and(is_download));
+    let substituted =
+        (is_interpreter || matches!(name, "eval" | "source" | ".")) && args.iter().any(downloaded);
+    if piped || substituted {
+        out.push(finding(
+            Concern::PipeToShell,
+            program,
+            format!(
+                "runs a downloaded script with `{name}`; download it to a file and verify it first"
+            ),
+        ));
+        return;
+    }
+
+    let evaluated = match name {
+        "eval" => args.iter().find(|w| w.expands),
+        _ if is_interpreter => args
+            .iter()
+            .position(|w| w.value == "-c")
+            .and_then(|i| args.get(i + 1))
+            .filter(|w| w.expands),
+        _ => None,
+    };
+    if let Some(word) = evaluated {
+        out.push(finding(
+            Concern::Eval,
+            word,
+            format!(
+                "`{name}` runs `{}` as code, whatever it expands to",
+                shown(&word.value)
+            ),
+        ));
+    }
+}
+
+/// The words from the command name on, skipping assignments, reserved
+/// words and wrappers such as `env` or `sudo`, and the privilege wrapper
+/// if there was one.
+fn effective(words: &[Word]) -> (&[Word], Option<&Word>) {
+    let mut privileged = None;
+    let mut i = 0;
+    while let Some(word) = words.get(i) {
+        let name = basename(&word.value);
+        if is_assignment(&word.value) || KEYWORDS.contains(&word.value.as_str()) {
+            i += 1;
+        } else if PRIVILEGE.contains(&name) || WRAPPERS.contains(&name) {
+            if PRIVILEGE.contains(&name) {
+                privileged.get_or_insert(word);
+            }
+            i += 1;
+            // Options of the wrapper, such as `sudo -u user` or `env -i`.
+            while let Some(option) = words.get(i).filter(|w| w.value.starts_with('-')) {
+                i += 1;
+                if matches!(option.value.as_str(), "-u" | "-g" | "-n" | "-C" | "-c") && name != "su"
+                {
+                    i += 1;
+                }
+            }
+        } else {
+            break;
+        }
+    }
+    (&words[i.min(words.len())..], privileged)
+}
+
+/// Check an `rm` command, returning whether it is recursive.
+fn remove(args: &[Word], out: &mut Vec<Finding>) -> bool {
+    let mut recursive = false;
+    let mut targets = Vec::new();
+    let mut options_done = false;
+    for word in args {
+        match word.value.as_str() {
+            "--" if !options_done => options_done = true,
+            "--recursive" if !options_done => recursive = true,
+            flag if !options_done && flag.starts_with('-') && !flag.starts_with("--") => {
+                recursive |= flag.contains(['r', 'R']);
+            }
+            _ => targets.push(word),
+        }
+    }
+    if !recursive {
+        return false;
+    }
+    for target in targets {
+        let value = target.value.trim_end_matches(['/', '*']);
+        if target.starts_unguarded {
+            out.push(finding(
+                Concern::RecursiveRemove,
+                target,
+                format!(
+                    "`rm -r` of `{}` deletes from the wrong place when a variable is empty; use `${{VAR:?}}`",
+                    shown(&target.value)
+                ),
+            ));
+        } else if value.is_empty() || matches!(value, "~" | "$HOME" | "${HOME}" | "." | "..") {
+            out.push(finding(
+                Concern::RecursiveRemove,
+                target,
+                format!(
+                    "`rm -r` of `{}` deletes far more than the skill owns",
+                    shown(&target.value)
+                ),
+            ));
+        } else if is_outside(&target.value) {
+            out.push(finding(
+                Concern::WriteOutsideProject,
+                target,
+                format!(
+                    "`rm -r` deletes `{}`, outside the project",
+                    shown(&target.value)
+                ),
+            ));
+        }
+    }
+    true
+}
+
+fn writes(name: &str, args: &[Word], out: &mut Vec<Finding>) {
+    let operands: Vec<&Word> = args.iter().filter(|w| !w.value.starts_with('-')).collect();
+    let written: Vec<&Word> = if WRITES_ARGS.contains(&name) {
+        // `chmod 755 file`, `chown user file`: the first operand is not a
+        // path.
+        let skip = usize::from(matches!(name, "chmod" | "chown"));
+        operands.into_iter().skip(skip).collect()
+    } else if WRITES_LAST.contains(&name) && operands.len() > 1 {
+        operands.last().copied().into_iter().collect()
+    } else if name == "dd" {
+        args.iter().filter(|w| w.value.starts_with("of=")).collect()
+    } else {
+        Vec::new()
+    };
+    for word in written {
+        let path = word.value.strip_prefix("of=").unwrap_or(&word.value);
+        if is_outside(path) {
+            out.push(finding(
+                Concern::WriteOutsideProject,
+                word,
+                format!("`{name}` writes to `{path}`, outside the project"),
+            ));
+        }
+    }
+}
+
+fn git_network(args: &[Word]) -> bool {
+    args.iter()
+        .find(|w| !w.value.starts_with('-'))
+        .is_some_and(|w| GIT_NETWORK.contains(&w.value.as_str()))
+}
+
+fn is_download(word: &Word) -> bool {
+    DOWNLOADERS.contains(&basename(&word.value))
+}
+
+fn starts_with_download(source: &str, body: Range<usize>) -> bool {
+    parse::parse(source, body).iter().any(|pipeline| {
+        pipeline
+            .iter()
+            .any(|c| effective(&c.words).0.first().is_some_and(is_download))
+    })
+}
+
+/// An absolute, home or parent-directory path other than the temp dir and
+/// the standard streams.
+fn is_outside(path: &str) -> bool {
+    if path.starts_with('/') {
+        return !WRITABLE.iter().any(|w| {
+            path == *w
+                || (path.starts_with(w) && (w.ends_with('/') || path[w.len()..].starts_with('/')))
+        });
+    }
+    path == ".."
+        || path.starts_with("../")
+        || path.starts_with('~')
+        || path.starts_with("$HOME")
+        || path.starts_with("${HOME}")
+}
+
+fn is_assignment(word: &str) -> bool {
+    let Some((name, _)) = word.split_once('=') else {
+        return false;
+    };
+    !name.is_empty()
+        && !name.starts_with(|c: char| c.is_ascii_digit())
+        && name.bytes().all(|b| b.is_ascii_alphanumeric() || b == b'_')
+}
+
+/// A word for a message: its first line, shortened.
+fn shown(text: &str) -> String {
+    const MAX: usize = 60;
+    let line = text.lines().next().unwrap_or_default();
+    match line.chars().count() > MAX || line.len() < text.trim_end().len() {
+        true => format!("{}...", line.chars().take(MAX).collect::<String>()),
+        false => line.to_string(),
+    }
+}
+
+fn basename(program: &str) -> &str {
+    program.rsplit('/').next().unwrap_or(program)
+}
+
+fn finding(concern: Concern, word: &Word, message: String) -> Finding {
+    Finding {
+        concern,
+        message,
+        range: word.span.clone(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn concerns(source: &str) -> Vec<Concern> {
+        analyze(source).into_iter().map(|f| f.concern).collect()
+    }
+
+    #[test]
+    fn shell_scripts_are_recognized_by_extension_or_shebang() {
+        assert!(is_shell_script(Path::new("run.sh"), ""));
+        assert!(is_shell_script(Path::new("run"), "#!/bin/bash\n"));
+        assert!(is_shell_script(
+            Path::new("run"),
+            "#!/usr/bin/env -S bash\n"
+        ));
+        assert!(!is_shell_script(
+            Path::new("run.py"),
+            "#!/usr/bin/env python3\n"
+        ));
+        assert!(!is_shell_script(Path::new("notes.txt"), "echo hi\n"));
+    }
+
+    #[test]
+    fn recursive_removes_of_unguarded_variables_are_flagged() {
+        let findings = analyze("rm -rf \"$BUILD_DIR/\"*\n");
+        assert_eq!(findings.len(), 1);
+        assert_eq!(findings[0].concern, Concern::RecursiveRemove);
+        assert_eq!(findings[0].range, 7..21);
+        assert_eq!(concerns("rm -rf \"${BUILD_DIR:?}/\"*\n"), []);
+        assert_eq!(concerns("rm -rf out/$NAME\n"), []);
+        assert_eq!(concerns("rm \"$FILE\"\n"), []);
+        assert_eq!(concerns("rm -r ~\n"), [Concern::RecursiveRemove]);
+        assert_eq!(
+            concerns("rm -r -- /etc/app\n"),
+            [Concern::WriteOutsideProject]
+        );
+    }
+
+    #[test]
+    fn downloads_run_by_an_interpreter_are_flagged() {
+        assert_eq!(
+            concerns("curl -fsSL https://example.com/install.sh | sh\n"),
+            [Concern::Network, Concern::PipeToShell]
+        );
+        assert_eq!(
+            concerns("bash -c \"$(wget -qO- https://example.com/x)\"\n"),
+            [Concern::PipeToShell, Concern::Network]
+        );
+        assert_eq!(
+            concerns("curl -o out.json https://example.com\n"),
+            [Concern::Network]
+        );
+    }
+
+    #[test]
+    fn privilege_escalation_is_flagged_and_looked_through() {
+        assert_eq!(
+            concerns("sudo -u root rm -rf \"$DIR\"\n"),
+            [Concern::Privilege, Concern::RecursiveRemove]
+        );
+        assert_eq!(concerns("if doas true; then :; fi\n"), [Concern::Privilege]);
+    }
+
+    #[test]
+    fn writes_outside_the_project_are_flagged() {
+        assert_eq!(
+            concerns("echo x > /etc/hosts\n"),
+            [Concern::WriteOutsideProject]
+        );
+        assert_eq!(
+            concerns("cp config ~/.bashrc\n"),
+            [Concern::WriteOutsideProject]
+        );
+        assert_eq!(
+            concerns("dd if=img of=/dev/sda\n"),
+            [Concern::WriteOutsideProject]
+        );
+        assert_eq!(concerns("echo x > /tmp/out 2>/dev/null\n"), []);
+        assert_eq!(concerns("chmod 755 scripts/run.sh\n"), []);
+    }
+
+    #[test]
+    fn network_access_is_reported() {
+        assert_eq!(
+            concerns("git clone https://example.com/r\n"),
+            [Concern::Network]
+        );
+        assert_eq!(concerns("git status\n"), []);
+        assert_eq!(
+            concerns("exec 3<>/dev/tcp/example.com/80\n"),
+            [Concern::Network]
+        );
+    }
+
+    #[test]
+    fn eval_of_expanded_text_is_flagged() {
+        assert_eq!(concerns("eval \"$CMD\"\n"), [Concern::Eval]);
+        assert_eq!(concerns("sh -c \"$1\"\n"), [Concern::Eval]);
+        assert_eq!(concerns("eval 'set -x'\n"), []);
+    }
+
+    #[test]
+    fn substitutions_are_analyzed_too() {
+        assert_eq!(
+            concerns("out=$(sudo cat /etc/shadow)\n"),
+            [Concern::Privilege]
+        );
+    }
+
+    #[test]
+    fn comments_and_heredocs_are_not_commands() {
+        let source = "# sudo rm -rf /\ncat <<EOF\nsudo rm -rf /\nEOF\necho done\n";
+        assert_eq!(concerns(source), []);
+    }
+}
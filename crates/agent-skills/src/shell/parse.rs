@@ -0,0 +1,468 @@
+//! Just enough of the shell grammar to find commands: words with their
+//! quoting and expansions, redirections, pipelines and command
+//! substitutions. Compound commands are flattened into the simple commands
+//! they contain, and here-document bodies are skipped.
+
+use std::ops::Range;
+
+/// One word as the shell would split it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct Word {
+    /// Byte range in the script.
+    pub span: Range<usize>,
+    /// The text with quotes removed and expansions kept as written.
+    pub value: String,
+    /// Whether a parameter, arithmetic or command expansion occurs.
+    pub expands: bool,
+    /// Whether the word starts with an expansion that may be empty, such
+    /// as `$DIR/build` but not `${DIR:?}/build` or `out/$DIR`.
+    pub starts_unguarded: bool,
+    /// Bodies of `$(...)`, backtick and `<(...)` substitutions.
+    pub substitutions: Vec<Range<usize>>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct Redirect {
+    pub op: &'static str,
+    pub target: Word,
+}
+
+/// A command name and its arguments, with redirections.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub(crate) struct Command {
+    pub words: Vec<Word>,
+    pub redirects: Vec<Redirect>,
+}
+
+/// Commands joined by `|` or `|&`.
+pub(crate) type Pipeline = Vec<Command>;
+
+/// Every pipeline in `source[range]`, with spans relative to `source`.
+pub(crate) fn parse(source: &str, range: Range<usize>) -> Vec<Pipeline> {
+    let mut parser = Parser {
+        src: source,
+        pos: range.start,
+        end: range.end,
+        heredocs: Vec::new(),
+    };
+    parser.pipelines()
+}
+
+enum Token {
+    Word(Word),
+    /// `|` or `|&`.
+    Pipe,
+    /// `;`, `&`, `&&`, `||`, newlines and grouping, which all end a
+    /// pipeline as far as the analysis is concerned.
+    Separator,
+    Redirect(&'static str),
+}
+
+const REDIRECTS: &[&str] = &[
+    "&>>", "<<<", "<<-", "&>", ">>", ">|", ">&", "<&", "<>", "<<", ">", "<",
+];
+
+struct Parser<'a> {
+    src: &'a str,
+    pos: usize,
+    end: usize,
+    /// Delimiters of here-documents whose bodies start after the next
+    /// newline, and whether leading tabs are stripped.
+    heredocs: Vec<(String, bool)>,
+}
+
+impl Parser<'_> {
+    fn pipelines(&mut self) -> Vec<Pipeline> {
+        let mut pipelines = Vec::new();
+        let mut pipeline: Pipeline = Vec::new();
+        let mut command = Command::default();
+        while let Some(token) = self.token() {
+            match token {
+                Token::Word(word) => command.words.push(word),
+                Token::Redirect(op) => {
+                    let Some(Token::Word(target)) = self.token() else {
+                        continue;
+                    };
+                    if op.starts_with("<<") && op != "<<<" {
+                        self.heredocs.push((target.value.clone(), op == "<<-"));
+                    }
+                    command.redirects.push(Redirect { op, target });
+                }
+                Token::Pipe => pipeline.push(std::mem::take(&mut command)),
+                Token::Separator => {
+                    finish(&mut pipelines, &mut pipeline, &mut command);
+                }
+            }
+        }
+        finish(&mut pipelines, &mut pipeline, &mut command);
+        pipelines
+    }
+
+    fn peek(&self) -> Option<u8> {
+        (self.pos < self.end).then(|| self.src.as_bytes()[self.pos])
+    }
+
+    fn at(&self, offset: usize) -> Option<u8> {
+        let i = self.pos + offset;
+        (i < self.end).then(|| self.src.as_bytes()[i])
+    }
+
+    fn rest(&self) -> &str {
+        &self.src[self.pos..self.end]
+    }
+
+    fn token(&mut self) -> Option<Token> {
+        loop {
+            match self.peek()? {
+                b' ' | b'\t' | b'\r' => self.pos += 1,
+                b'\\' if self.at(1) == Some(b'\n') => self.pos += 2,
+                b'#' => self.pos += self.rest().find('\n').unwrap_or(self.end - self.pos),
+                b'\n' => {
+                    self.pos += 1;
+                    self.skip_heredocs();
+                    return Some(Token::Separator);
+                }
+                _ => break,
+            }
+        }
+        let rest = self.rest();
+        if rest.starts_with("|&") || (rest.starts_with('|') && !rest.starts_with("||")) {
+            self.pos += if rest.starts_with("|&") { 2 } else { 1 };
+            return Some(Token::Pipe);
+        }
+        // Process substitutions are words, not redirections.
+        if !(rest.starts_with("<(") || rest.starts_with(">(")) {
+            if let Some(op) = REDIRECTS.iter().find(|op| rest.starts_with(**op)) {
+                self.pos += op.len();
+                return Some(Token::Redirect(op));
+            }
+        }
+        let c = self.peek()?;
+        let standalone = self.at(1).map_or(true, is_blank);
+        match c {
+            b';' | b'&' | b'|' => {
+                // `&&`, `||`, `;;` and `;&` separate just the same.
+                self.pos += match self.at(1) {
+                    Some(b'&' | b'|' | b';') => 2,
+                    _ => 1,
+                };
+                return Some(Token::Separator);
+            }
+            b'(' | b')' => {
+                self.pos += 1;
+                return Some(Token::Separator);
+            }
+            // `{` and `}` only group when they stand alone.
+            b'{' | b'}' if standalone => {
+                self.pos += 1;
+                return Some(Token::Separator);
+            }
+            _ => {}
+        }
+        let word = self.word();
+        // `2>file`: the digits name a file descriptor.
+        if word.value.bytes().all(|b| b.is_ascii_digit())
+            && matches!(self.peek(), Some(b'<' | b'>'))
+        {
+            return self.token();
+        }
+        Some(Token::Word(word))
+    }
+
+    fn word(&mut self) -> Word {
+        let start = self.pos;
+        let mut word = Word {
+            span: start..start,
+            value: String::new(),
+            expands: false,
+            starts_unguarded: false,
+            substitutions: Vec::new(),
+        };
+        while let Some(c) = self.peek() {
+            match c {
+                b' ' | b'\t' | b'\r' | b'\n' | b';' | b'&' | b'|' | b')' => break,
+                b'<' | b'>' if self.at(1) == Some(b'(') => self.substitution(&mut word, 2),
+                b'<' | b'>' | b'(' => break,
+                b'\\' => {
+                    let next = self.char_len(self.pos + 1);
+                    word.value
+                        .push_str(&self.src[self.pos + 1..self.pos + 1 + next]);
+                    self.pos += 1 + next;
+                }
+                b'\'' => {
+                    let body = self.pos + 1;
+                    let close = self.src[body..self.end]
+                        .find('\'')
+                        .map_or(self.end, |i| body + i);
+                    word.value.push_str(&self.src[body..close]);
+                    self.pos = (close + 1).min(self.end);
+                }
+                b'"' => {
+                    self.pos += 1;
+                    while let Some(c) = self.peek() {
+                        match c {
+                            b'"' => {
+                                self.pos += 1;
+                                break;
+                            }
+                            b'\\' => {
+                                let next = self.char_len(self.pos + 1);
+                                word.value
+                                    .push_str(&self.src[self.pos + 1..self.pos + 1 + next]);
+                                self.pos += 1 + next;
+                            }
+                            b'$' | b'`' => self.expansion(&mut word),
+                            _ => self.literal(&mut word),
+                        }
+                    }
+                }
+                b'$' | b'`' => self.expansion(&mut word),
+                _ => self.literal(&mut word),
+            }
+        }
+        word.span.end = self.pos;
+        if word.span.is_empty() {
+            // Never stall on a character no rule above consumes.
+            self.pos += self.char_len(self.pos);
+            word.span.end = self.pos;
+        }
+        word
+    }
+
+    fn literal(&mut self, word: &mut Word) {
+        let len = self.char_len(self.pos);
+        word.value.push_str(&self.src[self.pos..self.pos + len]);
+        self.pos += len;
+    }
+
+    /// `$name`, `${...}`, `$(...)`, `$((...))` or a backtick substitution.
+    fn expansion(&mut self, word: &mut Word) {
+        let start = self.pos;
+        let rest = self.rest();
+        if rest.starts_with("$((") {
+            self.pos = self.matching(self.pos + 1, b'(', b')');
+            unguarded(word);
+        } else if rest.starts_with("$(") {
+            self.substitution(word, 2);
+            return;
+        } else if rest.starts_with('`') {
+            let body = self.pos + 1;
+            let close = self.src[body..self.end]
+                .find('`')
+                .map_or(self.end, |i| body + i);
+            word.substitutions.push(body..close);
+            self.pos = (close + 1).min(self.end);
+            unguarded(word);
+        } else if rest.starts_with("${") {
+            self.pos = self.matching(self.pos + 1, b'{', b'}');
+            if !is_guarded(&self.src[start..self.pos]) {
+                unguarded(word);
+            }
+        } else {
+            let name = rest[1..]
+                .bytes()
+                .take_while(|b| b.is_ascii_alphanumeric() || *b == b'_')
+                .count();
+            let special = rest[1..]
+                .bytes()
+                .next()
+                .is_some_and(|b| b"@*#?$!-0123456789".contains(&b));
+            self.pos += 1 + match (name, special) {
+                (0, true) => 1,
+                (n, _) => n,
+            };
+            if self.pos == start + 1 {
+                // A lone `$` is literal.
+                word.value.push('$');
+                return;
+            }
+            unguarded(word);
+        }
+        word.expands = true;
+        word.value.push_str(&self.src[start..self.pos]);
+    }
+
+    /// `$(...)`, `<(...)` or `>(...)`, with `open` bytes before the body.
+    fn substitution(&mut self, word: &mut Word, open: usize) {
+        let start = self.pos;
+        let end = self.matching(self.pos + open - 1, b'(', b')');
+        word.substitutions
+            .push(start + open..end.saturating_sub(1).max(start + open));
+        word.value.push_str(&self.src[start..end]);
+        word.expands = true;
+        unguarded(word);
+        self.pos = end;
+    }
+
+    /// The offset just past the bracket closing the one at `open`, skipping
+    /// quoted text.
+    fn matching(&self, open: usize, left: u8, right: u8) -> usize {
+        let bytes = self.src.as_bytes();
+        let mut depth = 0usize;
+        let mut i = open;
+        while i < self.end {
+            match bytes[i] {
+                b'\\' => i += 1,
+                b'\'' => {
+                    i += self.src[i + 1..self.end]
+                        .find('\'')
+                        .map_or(self.end, |n| n + 1);
+                }
+                c if c == left => depth += 1,
+                c if c == right => {
+                    depth -= 1;
+                    if depth == 0 {
+                        return i + 1;
+                    }
+                }
+                _ => {}
+            }
+            i += 1;
+        }
+        self.end
+    }
+
+    /// Bytes in the character at `offset`, zero at the end.
+    fn char_len(&self, offset: usize) -> usize {
+        self.src[offset.min(self.end)..self.end]
+            .chars()
+            .next()
+            .map_or(0, char::len_utf8)
+    }
+
+    /// Skip the bodies of here-documents opened on the line just ended.
+    fn skip_heredocs(&mut self) {
+        for (delimiter, strip_tabs) in std::mem::take(&mut self.heredocs) {
+            while self.pos < self.end {
+                let line_end = self.rest().find('\n').map_or(self.end, |i| self.pos + i);
+                let line = &self.src[self.pos..line_end];
+                let line = match strip_tabs {
+                    true => line.trim_start_matches('\t'),
+                    false => line,
+                };
+                let done = line.trim_end_matches('\r') == delimiter;
+                self.pos = (line_end + 1).min(self.end);
+                if done {
+                    break;
+                }
+            }
+        }
+    }
+}
+
+/// Record an expansion that may be empty, before its text is added.
+fn unguarded(word: &mut Word) {
+    if word.value.is_empty() {
+        word.starts_unguarded = true;
+    }
+}
+
+/// Whether `${...}` can never expand to nothing: `${VAR:?message}`
+/// aborts instead and `${VAR:-default}` falls back to a non-empty default.
+fn is_guarded(expansion: &str) -> bool {
+    let inner = expansion.trim_start_matches("${").trim_end_matches('}');
+    let name = inner
+        .bytes()
+        .take_while(|b| b.is_ascii_alphanumeric() || *b == b'_')
+        .count();
+    let operator = inner[name..].trim_start_matches(':');
+    match operator.chars().next() {
+        Some('?') => true,
+        Some('-' | '=') => operator.len() > 1,
+        _ => false,
+    }
+}
+
+fn is_blank(c: u8) -> bool {
+    matches!(c, b' ' | b'\t' | b'\n' | b'\r')
+}
+
+fn finish(pipelines: &mut Vec<Pipeline>, pipeline: &mut Pipeline, command: &mut Command) {
+    if !command.words.is_empty() || !command.redirects.is_empty() {
+        pipeline.push(std::mem::take(command));
+    }
+    if !pipeline.is_empty() {
+        pipelines.push(std::mem::take(pipeline));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn all(source: &str) -> Vec<Pipeline> {
+        parse(source, 0..source.len())
+    }
+
+    fn values(command: &Command) -> Vec<&str> {
+        command.words.iter().map(|w| w.value.as_str()).collect()
+    }
+
+    #[test]
+    fn quotes_are_removed_and_spans_kept() {
+        let source = "echo 'a b' \"c\"d\\ e\n";
+        let pipelines = all(source);
+        let words = &pipelines[0][0].words;
+        assert_eq!(values(&pipelines[0][0]), ["echo", "a b", "cd e"]);
+        assert_eq!(&source[words[1].span.clone()], "'a b'");
+        assert!(!words[1].expands);
+    }
+
+    #[test]
+    fn expansions_are_tracked() {
+        let pipelines = all("rm -r \"$A\"/x ${B:?}/y out/$C '$D'\n");
+        let words = &pipelines[0][0].words;
+        assert!(words[2].expands && words[2].starts_unguarded);
+        assert!(words[3].expands && !words[3].starts_unguarded);
+        assert!(words[4].expands && !words[4].starts_unguarded);
+        assert!(!words[5].expands);
+    }
+
+    #[test]
+    fn pipelines_and_separators_split_commands() {
+        let pipelines = all("a | b |& c; d && e\nf &\n");
+        let shape: Vec<usize> = pipelines.iter().map(Vec::len).collect();
+        assert_eq!(shape, [3, 1, 1, 1]);
+        assert_eq!(values(&pipelines[0][2]), ["c"]);
+    }
+
+    #[test]
+    fn redirects_take_the_next_word_as_target() {
+        let pipelines = all("cmd >out.txt 2>>\"$LOG\" <in\n");
+        let command = &pipelines[0][0];
+        assert_eq!(values(command), ["cmd"]);
+        let redirects: Vec<(&str, &str)> = command
+            .redirects
+            .iter()
+            .map(|r| (r.op, r.target.value.as_str()))
+            .collect();
+        assert_eq!(redirects, [(">", "out.txt"), (">>", "$LOG"), ("<", "in")]);
+    }
+
+    #[test]
+    fn substitution_bodies_are_recorded() {
+        let source = "x=$(date) y=`id` diff <(a) b\n";
+        let pipelines = all(source);
+        let bodies: Vec<&str> = pipelines[0][0]
+            .words
+            .iter()
+            .flat_map(|w| &w.substitutions)
+            .map(|r| &source[r.clone()])
+            .collect();
+        assert_eq!(bodies, ["date", "id", "a"]);
+    }
+
+    #[test]
+    fn heredoc_bodies_are_skipped() {
+        let pipelines = all("cat <<-'END'\n\tsudo x\n\tEND\necho ok\n");
+        let commands: Vec<Vec<&str>> = pipelines.iter().flatten().map(values).collect();
+        assert_eq!(commands, [vec!["cat"], vec!["echo", "ok"]]);
+    }
+
+    #[test]
+    fn unknown_syntax_does_not_fail() {
+        let pipelines = all("case $x in a) b ;; esac; [[ -n \"$y\" ]] && ((i++))\n");
+        assert!(!pipelines.is_empty());
+        assert!(all("echo 'unterminated").len() == 1);
+    }
+}
@@ -0,0 +1,405 @@
+//! Detached Ed25519 signatures for `.skill` packages.
+//!
+//! `skills sign` writes `<archive>.sig` next to the package:
+//!
+//! ```toml
+//! version = 1
+//! key = "ed25519:0Oq3...="
+//! signature = "mB5c...=="
+//! ```
+//!
+//! The signature covers the archive bytes as written by `skills pack`.
+//! Whether a signature is required before unpacking, and which keys are
+//! trusted, is set under `[trust]` in `skills.toml`.
+
+use std::collections::BTreeMap;
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use ed25519_dalek::{Signer, SigningKey, VerifyingKey};
+use serde::{Deserialize, Serialize};
+
+use crate::config::TrustConfig;
+use crate::{Error, Result};
+
+/// Appended to the archive's file name to find its signature.
+pub const SIGNATURE_EXTENSION: &str = "sig";
+
+/// Current signature file format.
+const VERSION: u32 = 1;
+
+const PUBLIC_PREFIX: &str = "ed25519:";
+const SECRET_PREFIX: &str = "ed25519-secret:";
+
+/// A publisher's public key, written `ed25519:<base64>`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct PublicKey(VerifyingKey);
+
+impl fmt::Display for PublicKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{PUBLIC_PREFIX}{}", STANDARD.encode(self.0.as_bytes()))
+    }
+}
+
+impl FromStr for PublicKey {
+    type Err = String;
+
+    fn from_str(text: &str) -> std::result::Result<Self, String> {
+        let invalid =
+            || format!("`{text}` is not an Ed25519 public key (`{PUBLIC_PREFIX}<base64>`)");
+        let bytes = text
+            .strip_prefix(PUBLIC_PREFIX)
+            .and_then(|b64| STANDARD.decode(b64).ok())
+            .and_then(|bytes| <[u8; 32]>::try_from(bytes).ok())
+            .ok_or_else(invalid)?;
+        VerifyingKey::from_bytes(&bytes)
+            .map(Self)
+            .map_err(|_| invalid())
+    }
+}
+
+impl TryFrom<String> for PublicKey {
+    type Error = String;
+
+    fn try_from(text: String) -> std::result::Result<Self, String> {
+        text.parse()
+    }
+}
+
+impl From<PublicKey> for String {
+    fn from(key: PublicKey) -> Self {
+        key.to_string()
+    }
+}
+
+/// A signing key, kept in a file only its owner can read.
+pub struct SecretKey(SigningKey);
+
+impl SecretKey {
+    /// A new key from the operating system's random source.
+    pub fn generate() -> Result<Self> {
+        let mut seed = [0; 32];
+        getrandom::getrandom(&mut seed)
+            .map_err(|e| Error::io("getrandom", std::io::Error::other(e.to_string())))?;
+        Ok(Self(SigningKey::from_bytes(&seed)))
+    }
+
+    pub fn public(&self) -> PublicKey {
+        PublicKey(self.0.verifying_key())
+    }
+
+    pub fn load(path: &Path) -> Result<Self> {
+        let text = fs::read_to_string(path).map_err(|e| Error::io(path, e))?;
+        let seed = text
+            .trim()
+            .strip_prefix(SECRET_PREFIX)
+            .and_then(|b64| STANDARD.decode(b64).ok())
+            .and_then(|bytes| <[u8; 32]>::try_from(bytes).ok())
+            .ok_or_else(|| Error::Config {
+                path: path.to_path_buf(),
+                message: "not an Ed25519 secret key".to_string(),
+            })?;
+        Ok(Self(SigningKey::from_bytes(&seed)))
+    }
+
+    /// Write the key to a new file. An existing file is never replaced.
+    pub fn save(&self, path: &Path) -> Result<()> {
+        if path.exists() {
+            return Err(Error::AlreadyExists(path.to_path_buf()));
+        }
+        let text = format!("{SECRET_PREFIX}{}\n", STANDARD.encode(self.0.to_bytes()));
+        let mut options = fs::OpenOptions::new();
+        options.write(true).create_new(true);
+        #[cfg(unix)]
+        std::os::unix::fs::OpenOptionsExt::mode(&mut options, 0o600);
+        let mut file = options.open(path).map_err(|e| Error::io(path, e))?;
+        std::io::Write::write_all(&mut file, text.as_bytes()).map_err(|e| Error::io(path, e))
+    }
+}
+
+/// The contents of a `.sig` file.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Signature {
+    pub version: u32,
+    /// Key that made the signature.
+    pub key: PublicKey,
+    /// Base64 Ed25519 signature of the archive bytes.
+    pub signature: String,
+}
+
+impl Signature {
+    pub fn load(path: &Path) -> Result<Self> {
+        let text = fs::read_to_string(path).map_err(|e| Error::io(path, e))?;
+        let signature: Self = toml::from_str(&text).map_err(|e| Error::Config {
+            path: path.to_path_buf(),
+            message: e.message().to_string(),
+        })?;
+        if signature.version != VERSION {
+            return Err(Error::Config {
+                path: path.to_path_buf(),
+                message: format!("unsupported signature version {}", signature.version),
+            });
+        }
+        Ok(signature)
+    }
+
+    pub fn save(&self, path: &Path) -> Result<()> {
+        let text = toml::to_string_pretty(self).map_err(|e| Error::Config {
+            path: path.to_path_buf(),
+            message: e.to_string(),
+        })?;
+        fs::write(path, text).map_err(|e| Error::io(path, e))
+    }
+}
+
+/// `<archive>.sig`.
+pub fn signature_path(archive: &Path) -> PathBuf {
+    let mut path = archive.as_os_str().to_owned();
+    path.push(format!(".{SIGNATURE_EXTENSION}"));
+    PathBuf::from(path)
+}
+
+/// Sign the archive at `archive` and write its `.sig` file.
+pub fn sign(archive: &Path, key: &SecretKey) -> Result<Signature> {
+    let bytes = fs::read(archive).map_err(|e| Error::io(archive, e))?;
+    let signature = Signature {
+        version: VERSION,
+        key: key.public(),
+        signature: STANDARD.encode(key.0.sign(&bytes).to_bytes()),
+    };
+    signature.save(&signature_path(archive))?;
+    Ok(signature)
+}
+
+/// A signature checked against a trusted key.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Verified {
+    /// Name the key is trusted under in `[trust.keys]`.
+    pub publisher: String,
+    pub key: PublicKey,
+}
+
+/// Check the archive's `.sig` file and that one of `keys` made it over
+/// `bytes`, the archive as the caller read it.
+pub fn verify(
+    archive: &Path,
+    bytes: &[u8],
+    keys: &BTreeMap<String, PublicKey>,
+) -> Result<Verified> {
+    let fail = |message: String| Error::Signature {
+        path: archive.to_path_buf(),
+        message,
+    };
+    let sig_path = signature_path(archive);
+    if !sig_path.is_file() {
+        return Err(fail(format!("not signed; no {}", sig_path.display())));
+    }
+    let signature = Signature::load(&sig_path)?;
+    let publisher = keys
+        .iter()
+        .find(|(_, key)| **key == signature.key)
+        .map(|(name, _)| name.clone())
+        .ok_or_else(|| {
+            fail(format!(
+                "signed by {}, which is not in [trust.keys]",
+                signature.key
+            ))
+        })?;
+    let valid = STANDARD
+        .decode(&signature.signature)
+        .ok()
+        .and_then(|sig| ed25519_dalek::Signature::from_slice(&sig).ok())
+        .is_some_and(|sig| signature.key.0.verify_strict(bytes, &sig).is_ok());
+    if !valid {
+        return Err(fail(format!(
+            "signature by `{publisher}` does not match; the archive changed after it was signed"
+        )));
+    }
+    Ok(Verified {
+        publisher,
+        key: signature.key,
+    })
+}
+
+/// What to do with unsigned or untrusted packages before unpacking them.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Policy {
+    /// Refuse packages without a valid signature from a trusted key.
+    RequireSignature,
+    /// Unpack them, but say why they could not be verified.
+    #[default]
+    Warn,
+    /// Do not look at signatures.
+    Off,
+}
+
+/// Outcome of [`check`] when the policy lets the package through.
+#[derive(Debug)]
+pub enum Checked {
+    Verified(Verified),
+    /// Allowed by [`Policy::Warn`] despite this failure.
+    Unverified(Error),
+    /// [`Policy::Off`].
+    Skipped,
+}
+
+/// Apply the project's trust policy to `bytes`, read from `archive`.
+/// Unpack those same bytes with [`crate::package::Package::from_bytes`].
+pub fn check(archive: &Path, bytes: &[u8], trust: &TrustConfig) -> Result<Checked> {
+    match trust.policy {
+        Policy::Off => Ok(Checked::Skipped),
+        Policy::Warn => Ok(match verify(archive, bytes, &trust.keys) {
+            Ok(verified) => Checked::Verified(verified),
+            Err(e) => Checked::Unverified(e),
+        }),
+        Policy::RequireSignature => verify(archive, bytes, &trust.keys).map(Checked::Verified),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::TempDir;
+
+    fn trusted(name: &str, key: &SecretKey) -> BTreeMap<String, PublicKey> {
+        BTreeMap::from([(name.to_string(), key.public())])
+    }
+
+    fn signed(dir: &TempDir, key: &SecretKey) -> (PathBuf, Vec<u8>) {
+        let archive = dir.write("deploy-1.0.0.skill", b"archive bytes");
+        sign(&archive, key).unwrap();
+        (archive, b"archive bytes".to_vec())
+    }
+
+    fn failure(result: Result<Verified>) -> String {
+        match result {
+            Err(Error::Signature { message, .. }) => message,
+            other => panic!("expected a signature error, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn public_keys_round_trip_through_text() {
+        let key = SecretKey::generate().unwrap().public();
+        let text = key.to_string();
+        assert!(text.starts_with("ed25519:"));
+        assert_eq!(text.parse::<PublicKey>().unwrap(), key);
+        assert!("ed25519:AAAA".parse::<PublicKey>().is_err());
+        assert!("rsa:AAAA".parse::<PublicKey>().is_err());
+    }
+
+    #[test]
+    fn secret_keys_are_saved_once_and_private() {
+        let dir = TempDir::new();
+        let path = dir.path().join("key");
+        let key = SecretKey::generate().unwrap();
+        key.save(&path).unwrap();
+        assert_eq!(SecretKey::load(&path).unwrap().public(), key.public());
+        assert!(matches!(key.save(&path), Err(Error::AlreadyExists(_))));
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let mode = fs::metadata(&path).unwrap().permissions().mode();
+            assert_eq!(mode & 0o777, 0o600);
+        }
+        let garbage = dir.write("garbage", "not a key");
+        assert!(matches!(
+            SecretKey::load(&garbage),
+            Err(Error::Config { .. })
+        ));
+    }
+
+    #[test]
+    fn a_signature_from_a_trusted_key_verifies() {
+        let dir = TempDir::new();
+        let key = SecretKey::generate().unwrap();
+        let (archive, bytes) = signed(&dir, &key);
+        assert_eq!(
+            signature_path(&archive),
+            dir.path().join("deploy-1.0.0.skill.sig")
+        );
+        let verified = verify(&archive, &bytes, &trusted("alice", &key)).unwrap();
+        assert_eq!(verified.publisher, "alice");
+        assert_eq!(verified.key, key.public());
+    }
+
+    #[test]
+    fn changed_bytes_do_not_verify() {
+        let dir = TempDir::new();
+        let key = SecretKey::generate().unwrap();
+        let (archive, _) = signed(&dir, &key);
+        let message = failure(verify(&archive, b"other bytes", &trusted("alice", &key)));
+        assert!(message.contains("does not match"), "{message}");
+    }
+
+    #[test]
+    fn untrusted_and_unsigned_archives_fail() {
+        let dir = TempDir::new();
+        let key = SecretKey::generate().unwrap();
+        let (archive, bytes) = signed(&dir, &key);
+        let other = SecretKey::generate().unwrap();
+        let message = failure(verify(&archive, &bytes, &trusted("bob", &other)));
+        assert!(message.contains("not in [trust.keys]"), "{message}");
+
+        let unsigned = dir.write("unsigned.skill", b"archive bytes");
+        let message = failure(verify(&unsigned, &bytes, &trusted("alice", &key)));
+        assert!(message.starts_with("not signed"), "{message}");
+    }
+
+    #[test]
+    fn a_signature_by_a_trusted_key_over_other_bytes_fails() {
+        let dir = TempDir::new();
+        let key = SecretKey::generate().unwrap();
+        let (archive, bytes) = signed(&dir, &key);
+        let attacker = SecretKey::generate().unwrap();
+        // Claim the trusted key, with the attacker's signature.
+        let mut signature = Signature::load(&signature_path(&archive)).unwrap();
+        signature.signature = STANDARD.encode(attacker.0.sign(&bytes).to_bytes());
+        signature.save(&signature_path(&archive)).unwrap();
+        assert!(verify(&archive, &bytes, &trusted("alice", &key)).is_err());
+    }
+
+    #[test]
+    fn signature_files_of_other_versions_are_rejected() {
+        let dir = TempDir::new();
+        let key = SecretKey::generate().unwrap();
+        let (archive, _) = signed(&dir, &key);
+        let path = signature_path(&archive);
+        let text = fs::read_to_string(&path).unwrap();
+        fs::write(&path, text.replace("version = 1", "version = 2")).unwrap();
+        assert!(matches!(Signature::load(&path), Err(Error::Config { .. })));
+    }
+
+    #[test]
+    fn the_policy_decides_what_a_failure_means() {
+        let dir = TempDir::new();
+        let key = SecretKey::generate().unwrap();
+        let (archive, bytes) = signed(&dir, &key);
+        let mut trust = TrustConfig {
+            policy: Policy::RequireSignature,
+            keys: trusted("alice", &key),
+        };
+        assert!(matches!(
+            check(&archive, &bytes, &trust),
+            Ok(Checked::Verified(_))
+        ));
+        assert!(check(&archive, b"tampered", &trust).is_err());
+        trust.policy = Policy::Warn;
+        assert!(matches!(
+            check(&archive, b"tampered", &trust),
+            Ok(Checked::Unverified(Error::Signature { .. }))
+        ));
+        trust.policy = Policy::Off;
+        assert!(matches!(
+            check(&archive, b"tampered", &trust),
+            Ok(Checked::Skipped)
+        ));
+    }
+}
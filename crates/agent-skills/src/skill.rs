@@ -0,0 +1,274 @@
+//! The typed `SKILL.md` model: YAML frontmatter followed by markdown.
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+
+use serde_yaml::{Mapping, Value};
+
+use crate::parse::{self, ParseError};
+use crate::span::Span;
+use crate::{Error, Result};
+
+/// File name every skill directory is expected to contain.
+pub const SKILL_FILE: &str = "SKILL.md";
+
+/// A parsed `SKILL.md` file.
+///
+/// The original source is kept so every span in the model can be resolved
+/// back to text, and so tools can report diagnostics against the file the
+/// author actually wrote.
+#[derive(Debug, Clone)]
+pub struct Skill {
+    pub(crate) source: String,
+    pub(crate) frontmatter: Frontmatter,
+    pub(crate) frontmatter_span: Span,
+    pub(crate) body_span: Span,
+    pub(crate) sections: Vec<Section>,
+}
+
+impl Skill {
+    pub fn parse(source: impl Into<String>) -> std::result::Result<Self, ParseError> {
+        parse::parse(source.into())
+    }
+
+    pub fn from_path(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+        let source = fs::read_to_string(path).map_err(|source| Error::io(path, source))?;
+        Self::parse(source).map_err(|source| Error::Parse {
+            path: path.to_path_buf(),
+            source,
+        })
+    }
+
+    pub fn source(&self) -> &str {
+        &self.source
+    }
+
+    pub fn frontmatter(&self) -> &Frontmatter {
+        &self.frontmatter
+    }
+
+    pub fn name(&self) -> Option<&str> {
+        self.frontmatter.name.as_deref()
+    }
+
+    pub fn description(&self) -> Option<&str> {
+        self.frontmatter.description.as_deref()
+    }
+
+    /// The Level 1 text an agent keeps in context for this skill:
+    /// `name: description`.
+    pub fn metadata(&self) -> String {
+        match (self.name(), self.description().map(str::trim)) {
+            (Some(name), Some(description)) => format!("{name}: {description}"),
+            (Some(only), None) | (None, Some(only)) => only.to_string(),
+            (None, None) => String::new(),
+        }
+    }
+
+    /// Span of the frontmatter block, including both `---` delimiters.
+    pub fn frontmatter_span(&self) -> Span {
+        self.frontmatter_span
+    }
+
+    /// Span of the markdown following the frontmatter.
+    pub fn body_span(&self) -> Span {
+        self.body_span
+    }
+
+    pub fn body(&self) -> &str {
+        self.body_span.text(&self.source)
+    }
+
+    /// Headings of the body in document order. A section extends up to the
+    /// next heading of the same or a higher level, so nested sections are
+    /// contained in their parent's span.
+    pub fn sections(&self) -> &[Section] {
+        &self.sections
+    }
+
+    /// First section whose title matches `title`, ignoring ASCII case.
+    pub fn section(&self, title: &str) -> Option<&Section> {
+        self.sections
+            .iter()
+            .find(|s| s.title.eq_ignore_ascii_case(title))
+    }
+
+    /// Markdown under a section's heading, excluding the heading line.
+    pub fn section_text(&self, section: &Section) -> &str {
+        section.content.text(&self.source)
+    }
+
+    /// Number of lines in the whole file, frontmatter included.
+    pub fn line_count(&self) -> usize {
+        self.source.lines().count()
+    }
+
+    /// Render the skill back to `SKILL.md` text. The body is emitted verbatim;
+    /// the frontmatter is re-serialized, so comments inside it are dropped.
+    pub fn to_markdown(&self) -> String {
+        render(&self.frontmatter, self.body())
+    }
+}
+
+/// Whether `name` is a valid skill name: lowercase ASCII letters and digits
+/// in words joined by single hyphens.
+pub fn is_kebab_case(name: &str) -> bool {
+    !name.is_empty()
+        && name.split('-').all(|part| {
+            !part.is_empty()
+                && part
+                    .bytes()
+                    .all(|b| b.is_ascii_lowercase() || b.is_ascii_digit())
+        })
+}
+
+/// Build `SKILL.md` text from frontmatter and a markdown body.
+pub fn render(frontmatter: &Frontmatter, body: &str) -> String {
+    let mut out = String::from("---\n");
+    out.push_str(&frontmatter.to_yaml());
+    out.push_str("---\n");
+    if !body.is_empty() && !body.starts_with('\n') {
+        out.push('\n');
+    }
+    out.push_str(body);
+    out
+}
+
+/// The YAML block at the top of a `SKILL.md`.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Frontmatter {
+    pub name: Option<String>,
+    pub description: Option<String>,
+    /// File patterns hinting when the skill is relevant. Accepts either a
+    /// single string or a list in YAML.
+    pub globs: Vec<String>,
+    /// Semantic version of the skill, e.g. `1.4.0`. Kept as written; see
+    /// [`crate::resolve`] for how it is interpreted.
+    pub version: Option<String>,
+    /// Other skills this one builds on, by name, with the semver range of
+    /// versions it works with (`">=1.2, <2"`, `"^3"`, `"*"`).
+    pub requires: BTreeMap<String, String>,
+    /// Skills whose instructions must be in context before this one's. See
+    /// [`crate::graph`].
+    pub depends_on: Vec<String>,
+    /// A skill whose instructions this one refines: sections with the same
+    /// title replace the base's, the rest are added after it.
+    pub extends: Option<String>,
+    /// The `override` key: how this skill treats the same-named skill of a
+    /// lower-precedence `.claude/` layer. See [`crate::layers`].
+    pub overrides: Option<Override>,
+    /// Keys this crate does not interpret, in file order. Kept verbatim so
+    /// third-party extensions survive a parse/render round trip.
+    pub extra: Mapping,
+    pub(crate) spans: Vec<(String, Span)>,
+}
+
+impl Frontmatter {
+    /// Span of a top-level `key: value` entry in the original file.
+    pub fn span_of(&self, key: &str) -> Option<Span> {
+        self.spans.iter().find(|(k, _)| k == key).map(|(_, s)| *s)
+    }
+
+    /// Top-level keys in file order.
+    pub fn keys(&self) -> impl Iterator<Item = &str> {
+        self.spans.iter().map(|(k, _)| k.as_str())
+    }
+
+    /// An uninterpreted key, see [`Frontmatter::extra`].
+    pub fn get(&self, key: &str) -> Option<&Value> {
+        self.extra.get(key)
+    }
+
+    /// Serialize back to YAML (without `---` delimiters). Known keys come
+    /// first, followed by the extra keys in their original order.
+    pub fn to_yaml(&self) -> String {
+        let mut map = Mapping::new();
+        if let Some(name) = &self.name {
+            map.insert("name".into(), name.clone().into());
+        }
+        if let Some(description) = &self.description {
+            map.insert("description".into(), description.clone().into());
+        }
+        match self.globs.as_slice() {
+            [] => {}
+            [glob] => {
+                map.insert("globs".into(), glob.clone().into());
+            }
+            globs => {
+                map.insert("globs".into(), globs.to_vec().into());
+            }
+        }
+        if let Some(version) = &self.version {
+            map.insert("version".into(), version.clone().into());
+        }
+        if !self.requires.is_empty() {
+            let requires: Mapping = self
+                .requires
+                .iter()
+                .map(|(name, range)| (name.clone().into(), range.clone().into()))
+                .collect();
+            map.insert("requires".into(), requires.into());
+        }
+        match self.depends_on.as_slice() {
+            [] => {}
+            [name] => {
+                map.insert("depends_on".into(), name.clone().into());
+            }
+            names => {
+                map.insert("depends_on".into(), names.to_vec().into());
+            }
+        }
+        if let Some(extends) = &self.extends {
+            map.insert("extends".into(), extends.clone().into());
+        }
+        match &self.overrides {
+            None => {}
+            Some(Override::Disable) => {
+                map.insert("override".into(), "disable".into());
+            }
+            Some(Override::Merge { disable }) if disable.is_empty() => {
+                map.insert("override".into(), true.into());
+            }
+            Some(Override::Merge { disable }) => {
+                let mut patch = Mapping::new();
+                patch.insert("disable".into(), disable.clone().into());
+                map.insert("override".into(), patch.into());
+            }
+        }
+        for (key, value) in &self.extra {
+            map.insert(key.clone(), value.clone());
+        }
+        match map.is_empty() {
+            true => String::new(),
+            false => serde_yaml::to_string(&map).expect("frontmatter mapping serializes"),
+        }
+    }
+}
+
+/// What a skill does to the same-named skill it inherits from a
+/// lower-precedence layer. Without one, it replaces that skill outright.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Override {
+    /// `override: disable`: drop the inherited skill; the body is ignored.
+    Disable,
+    /// `override: true`, or `override: { disable: [<title>, ..] }`: keep the
+    /// inherited skill, replace its `##` sections that this body also has,
+    /// add the others and drop the sections listed.
+    Merge { disable: Vec<String> },
+}
+
+/// A markdown heading and the content under it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Section {
+    /// Heading level, 1 for `#` through 6 for `######`.
+    pub level: u8,
+    pub title: String,
+    /// The heading line.
+    pub heading: Span,
+    /// The content after the heading line.
+    pub content: Span,
+    /// Heading and content together.
+    pub span: Span,
+}
@@ -0,0 +1,163 @@
+//! Source positions and spans pointing back into the original file.
+
+use std::fmt;
+use std::ops::Range;
+
+use serde::Serialize;
+
+/// A location in a source file.
+///
+/// `line` and `column` are 1-based; `column` counts characters, not bytes.
+/// `offset` is the byte offset into the source.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize)]
+pub struct Position {
+    pub offset: usize,
+    pub line: usize,
+    pub column: usize,
+}
+
+impl fmt::Display for Position {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:{}", self.line, self.column)
+    }
+}
+
+/// A half-open range `[start, end)` in a source file.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize)]
+pub struct Span {
+    pub start: Position,
+    pub end: Position,
+}
+
+impl Span {
+    /// Byte range covered by this span.
+    pub fn range(&self) -> Range<usize> {
+        self.start.offset..self.end.offset
+    }
+
+    /// The slice of `source` covered by this span.
+    pub fn text<'a>(&self, source: &'a str) -> &'a str {
+        &source[self.range()]
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.start.offset == self.end.offset
+    }
+}
+
+impl fmt::Display for Span {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.start.fmt(f)
+    }
+}
+
+/// Maps byte offsets to line/column positions and back.
+#[derive(Debug, Clone)]
+pub struct LineIndex<'a> {
+    source: &'a str,
+    line_starts: Vec<usize>,
+}
+
+impl<'a> LineIndex<'a> {
+    pub fn new(source: &'a str) -> Self {
+        let mut line_starts = vec![0];
+        line_starts.extend(source.match_indices('\n').map(|(i, _)| i + 1));
+        Self {
+            source,
+            line_starts,
+        }
+    }
+
+    /// Number of lines, counting a trailing line without a newline.
+    pub fn line_count(&self) -> usize {
+        match self.source.ends_with('\n') {
+            true => self.line_starts.len() - 1,
+            false => self.line_starts.len(),
+        }
+    }
+
+    /// Byte offset of the first character of `line` (1-based).
+    ///
+    /// Lines past the end of the file map to the end of the source.
+    pub fn line_start(&self, line: usize) -> usize {
+        self.line_starts
+            .get(line.saturating_sub(1))
+            .copied()
+            .unwrap_or(self.source.len())
+    }
+
+    /// Position of the byte at `offset`, clamped to the source length and
+    /// rounded down to a character boundary.
+    pub fn position(&self, offset: usize) -> Position {
+        let mut offset = offset.min(self.source.len());
+        while !self.source.is_char_boundary(offset) {
+            offset -= 1;
+        }
+        let line = self.line_starts.partition_point(|&start| start <= offset);
+        let start = self.line_starts[line - 1];
+        Position {
+            offset,
+            line,
+            column: self.source[start..offset].chars().count() + 1,
+        }
+    }
+
+    /// Byte offset of a 1-based `line` and `column`, clamped to the end of
+    /// that line.
+    pub fn offset(&self, line: usize, column: usize) -> usize {
+        let start = self.line_start(line);
+        let mut end = self.line_start(line + 1);
+        if end > start && self.source[..end].ends_with('\n') {
+            end -= 1;
+        }
+        self.source[start..end]
+            .char_indices()
+            .nth(column.saturating_sub(1))
+            .map_or(end, |(i, _)| start + i)
+    }
+
+    pub fn span(&self, range: Range<usize>) -> Span {
+        Span {
+            start: self.position(range.start),
+            end: self.position(range.end),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn positions_are_one_based_and_count_characters() {
+        let index = LineIndex::new("ab\nñé x\n");
+        assert_eq!(
+            index.position(0),
+            Position {
+                offset: 0,
+                line: 1,
+                column: 1
+            }
+        );
+        // `x` is preceded by two 2-byte characters and a space.
+        let x = "ab\nñé x\n".find('x').unwrap();
+        let position = index.position(x);
+        assert_eq!((position.line, position.column), (2, 4));
+        assert_eq!(index.offset(2, 4), x);
+    }
+
+    #[test]
+    fn offsets_inside_a_character_round_down() {
+        let index = LineIndex::new("ñ");
+        assert_eq!(index.position(1).offset, 0);
+    }
+
+    #[test]
+    fn offsets_clamp_to_the_line_and_the_source() {
+        let index = LineIndex::new("one\ntwo");
+        assert_eq!(index.offset(1, 99), 3);
+        assert_eq!(index.offset(9, 1), 7);
+        assert_eq!(index.line_count(), 2);
+        assert_eq!(LineIndex::new("one\n").line_count(), 1);
+    }
+}
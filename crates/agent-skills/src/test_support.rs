@@ -0,0 +1,25 @@
+//! Helpers shared by the unit tests.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// A scratch directory removed when dropped.
+pub(crate) struct TempDir(crate::install::TempDir);
+
+impl TempDir {
+    pub(crate) fn new() -> Self {
+        Self(crate::install::TempDir::new().expect("create temp dir"))
+    }
+
+    pub(crate) fn path(&self) -> &Path {
+        &self.0 .0
+    }
+
+    /// Write `contents` to `relative`, creating parent directories.
+    pub(crate) fn write(&self, relative: impl AsRef<Path>, contents: impl AsRef<[u8]>) -> PathBuf {
+        let path = self.path().join(relative);
+        fs::create_dir_all(path.parent().expect("file has a parent")).expect("create parent");
+        fs::write(&path, contents).expect("write file");
+        path
+    }
+}
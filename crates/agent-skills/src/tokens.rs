@@ -0,0 +1,328 @@
+//! Token accounting per progressive-disclosure level.
+//!
+//! - Level 1: the `name: description` line of every skill, always loaded.
+//! - Level 2: the whole `SKILL.md`, loaded once the skill is relevant.
+//! - Level 3: files under `references/` and the output of `scripts/`,
+//!   loaded on demand.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use serde::Serialize;
+
+use crate::config::ScriptsConfig;
+use crate::project::{Project, SkillDir, REFERENCES_DIR, SCRIPTS_DIR};
+use crate::script::Runner;
+use crate::skill::Skill;
+use crate::{Error, Result};
+
+/// Counts tokens in text. Implement this to plug in a model's own tokenizer.
+pub trait Tokenizer: Send + Sync {
+    fn name(&self) -> &str;
+    fn count(&self, text: &str) -> usize;
+}
+
+/// The usual rule of thumb of four characters per token, rounded up.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct CharEstimate;
+
+impl Tokenizer for CharEstimate {
+    fn name(&self) -> &str {
+        "chars"
+    }
+
+    fn count(&self, text: &str) -> usize {
+        text.chars().count().div_ceil(4)
+    }
+}
+
+/// A BPE vocabulary bundled with the binary.
+#[cfg(feature = "bpe")]
+#[derive(Clone, Copy)]
+pub struct Bpe {
+    name: &'static str,
+    bpe: &'static tiktoken_rs::CoreBPE,
+}
+
+#[cfg(feature = "bpe")]
+impl Bpe {
+    pub fn cl100k() -> Self {
+        Self {
+            name: "cl100k",
+            bpe: tiktoken_rs::cl100k_base_singleton(),
+        }
+    }
+
+    pub fn o200k() -> Self {
+        Self {
+            name: "o200k",
+            bpe: tiktoken_rs::o200k_base_singleton(),
+        }
+    }
+}
+
+#[cfg(feature = "bpe")]
+impl Tokenizer for Bpe {
+    fn name(&self) -> &str {
+        self.name
+    }
+
+    fn count(&self, text: &str) -> usize {
+        self.bpe.encode_ordinary(text).len()
+    }
+}
+
+/// Names accepted by [`tokenizer`].
+pub const TOKENIZERS: &[&str] = &[
+    #[cfg(feature = "bpe")]
+    "cl100k",
+    #[cfg(feature = "bpe")]
+    "o200k",
+    "chars",
+];
+
+/// Look a tokenizer up by name; `None` selects the default, which is the
+/// first entry of [`TOKENIZERS`].
+pub fn tokenizer(name: Option<&str>) -> Result<Arc<dyn Tokenizer>> {
+    match name.unwrap_or(TOKENIZERS[0]) {
+        #[cfg(feature = "bpe")]
+        "cl100k" => Ok(Arc::new(Bpe::cl100k())),
+        #[cfg(feature = "bpe")]
+        "o200k" => Ok(Arc::new(Bpe::o200k())),
+        "chars" => Ok(Arc::new(CharEstimate)),
+        other => Err(Error::UnknownTokenizer(other.to_string())),
+    }
+}
+
+/// Measures skills with a [`Tokenizer`].
+#[derive(Clone)]
+pub struct TokenCounter {
+    tokenizer: Arc<dyn Tokenizer>,
+    run_scripts: bool,
+    scripts: ScriptsConfig,
+}
+
+impl TokenCounter {
+    pub fn new(tokenizer: Arc<dyn Tokenizer>) -> Self {
+        Self {
+            tokenizer,
+            run_scripts: false,
+            scripts: ScriptsConfig::default(),
+        }
+    }
+
+    /// Execute each script to measure its output. Off by default, since it
+    /// runs code from the skill.
+    pub fn run_scripts(mut self, run: bool) -> Self {
+        self.run_scripts = run;
+        self
+    }
+
+    /// Limits and sandboxing for scripts run to measure them.
+    pub fn scripts(mut self, config: ScriptsConfig) -> Self {
+        self.scripts = config;
+        self
+    }
+
+    pub fn tokenizer(&self) -> &dyn Tokenizer {
+        &*self.tokenizer
+    }
+
+    pub fn count(&self, text: &str) -> usize {
+        self.tokenizer.count(text)
+    }
+
+    /// Level 1 cost of a skill.
+    pub fn metadata(&self, skill: &Skill) -> usize {
+        self.count(&skill.metadata())
+    }
+
+    /// Level 2 cost of a skill.
+    pub fn instructions(&self, skill: &Skill) -> usize {
+        self.count(skill.source())
+    }
+
+    pub fn skill(&self, dir: &SkillDir, skill: &Skill) -> Result<SkillCost> {
+        let mut references = Vec::new();
+        for path in dir.files_in(REFERENCES_DIR)? {
+            let bytes = fs::read(&path).map_err(|e| Error::io(&path, e))?;
+            references.push(FileCost {
+                path: relative(&dir.path, &path),
+                tokens: self.count(&String::from_utf8_lossy(&bytes)),
+            });
+        }
+        let runner = Runner::new(self.clone())
+            .config(&self.scripts)
+            .working_dir(&dir.path);
+        let mut scripts = Vec::new();
+        for path in dir.files_in(SCRIPTS_DIR)? {
+            let output = match self.run_scripts {
+                true => Some(runner.run(&path, &[])?),
+                false => None,
+            };
+            scripts.push(ScriptCost {
+                path: relative(&dir.path, &path),
+                output: output.map(|o| o.tokens),
+            });
+        }
+        Ok(SkillCost {
+            name: dir.skill_name(skill).to_string(),
+            metadata: self.metadata(skill),
+            instructions: self.instructions(skill),
+            references,
+            scripts,
+        })
+    }
+
+    /// Cost of every skill in the project. Skills that fail to parse are
+    /// skipped; `skills lint` reports them.
+    pub fn project(&self, project: &Project) -> Result<ProjectCost> {
+        let global_path = project.global_skill_file();
+        let global = match global_path.is_file() {
+            true => {
+                let text =
+                    fs::read_to_string(&global_path).map_err(|e| Error::io(&global_path, e))?;
+                Some(self.count(&text))
+            }
+            false => None,
+        };
+        let mut skills = Vec::new();
+        for dir in project.skills()? {
+            let Ok(skill) = Skill::from_path(dir.skill_file()) else {
+                continue;
+            };
+            skills.push(self.skill(&dir, &skill)?);
+        }
+        Ok(ProjectCost {
+            tokenizer: self.tokenizer.name().to_string(),
+            global,
+            startup: skills.iter().map(|s| s.metadata).sum(),
+            skills,
+        })
+    }
+}
+
+fn relative(base: &Path, path: &Path) -> PathBuf {
+    path.strip_prefix(base).unwrap_or(path).to_path_buf()
+}
+
+/// Token cost of one skill at each level.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct SkillCost {
+    pub name: String,
+    /// Level 1.
+    pub metadata: usize,
+    /// Level 2.
+    pub instructions: usize,
+    /// Level 3 documentation.
+    pub references: Vec<FileCost>,
+    /// Level 3 scripts.
+    pub scripts: Vec<ScriptCost>,
+}
+
+impl SkillCost {
+    /// Level 3 cost if every reference and measured script output were loaded.
+    pub fn on_demand(&self) -> usize {
+        self.references.iter().map(|r| r.tokens).sum::<usize>()
+            + self.scripts.iter().filter_map(|s| s.output).sum::<usize>()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct FileCost {
+    /// Relative to the skill directory.
+    pub path: PathBuf,
+    pub tokens: usize,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ScriptCost {
+    /// Relative to the skill directory.
+    pub path: PathBuf,
+    /// Tokens in the script's output, when it was run.
+    pub output: Option<usize>,
+}
+
+/// Token cost of a whole `.claude` tree.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ProjectCost {
+    pub tokenizer: String,
+    /// The global `.claude/SKILL.md`, if present.
+    pub global: Option<usize>,
+    /// Level 1 metadata of all skills, paid at the start of every session.
+    pub startup: usize,
+    pub skills: Vec<SkillCost>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::TempDir;
+
+    const SKILL: &str = "---\nname: demo\ndescription: Twelve chars\n---\n# Demo\n";
+
+    fn counter() -> TokenCounter {
+        TokenCounter::new(Arc::new(CharEstimate))
+    }
+
+    #[test]
+    fn char_estimate_rounds_up_per_character() {
+        assert_eq!(CharEstimate.count(""), 0);
+        assert_eq!(CharEstimate.count("abcd"), 1);
+        assert_eq!(CharEstimate.count("abcde"), 2);
+        assert_eq!(CharEstimate.count("ñññ"), 1);
+    }
+
+    #[test]
+    fn tokenizers_are_looked_up_by_name() {
+        assert_eq!(tokenizer(Some("chars")).unwrap().name(), "chars");
+        assert_eq!(tokenizer(None).unwrap().name(), TOKENIZERS[0]);
+        assert!(matches!(
+            tokenizer(Some("gpt-2")),
+            Err(Error::UnknownTokenizer(name)) if name == "gpt-2"
+        ));
+    }
+
+    #[test]
+    fn levels_count_metadata_and_the_whole_file() {
+        let skill = Skill::parse(SKILL).unwrap();
+        // "demo: Twelve chars" is 18 characters.
+        assert_eq!(counter().metadata(&skill), 5);
+        assert_eq!(
+            counter().instructions(&skill),
+            SKILL.chars().count().div_ceil(4)
+        );
+    }
+
+    #[test]
+    fn project_cost_covers_references_and_lists_scripts_without_running_them() {
+        let dir = TempDir::new();
+        dir.write(".claude/SKILL.md", "12345678");
+        dir.write(".claude/skills/demo/SKILL.md", SKILL);
+        dir.write(".claude/skills/demo/references/api.md", "x".repeat(40));
+        dir.write(".claude/skills/demo/scripts/run.sh", "exit 1\n");
+        dir.write(".claude/skills/broken/SKILL.md", "no frontmatter");
+        let cost = counter().project(&Project::new(dir.path())).unwrap();
+        assert_eq!(cost.tokenizer, "chars");
+        assert_eq!(cost.global, Some(2));
+        assert_eq!(cost.skills.len(), 1);
+        let skill = &cost.skills[0];
+        assert_eq!(cost.startup, skill.metadata);
+        assert_eq!(
+            skill.references,
+            [FileCost {
+                path: PathBuf::from("references/api.md"),
+                tokens: 10
+            }]
+        );
+        assert_eq!(
+            skill.scripts,
+            [ScriptCost {
+                path: PathBuf::from("scripts/run.sh"),
+                output: None
+            }]
+        );
+        assert_eq!(skill.on_demand(), 10);
+    }
+}
@@ -0,0 +1,144 @@
+//! Round trips through every export target: what a backend renders, it
+//! must parse back with name, description, globs and body intact. Rule
+//! names become file names, so none may lead outside the project.
+
+use std::path::Path;
+use std::sync::Arc;
+
+use agent_skills::export::{self, ReferencePolicy, Rule, GLOBAL_RULE, TARGETS};
+use agent_skills::tokens::{CharEstimate, TokenCounter};
+use agent_skills::{Error, Project};
+
+fn fixture() -> Project {
+    Project::new(Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/project"))
+}
+
+fn references(inline_threshold: usize) -> ReferencePolicy {
+    ReferencePolicy::new(TokenCounter::new(Arc::new(CharEstimate)))
+        .inline_threshold(inline_threshold)
+}
+
+/// The rules every exporter starts from, built by exporting to a target
+/// that keeps everything and parsing it back.
+fn source_rules() -> Vec<Rule> {
+    let exporter = export::exporter("cursor", references(0)).unwrap();
+    exporter.parse(&exporter.export(&fixture()).unwrap())
+}
+
+#[test]
+fn source_rules_match_the_fixture() {
+    let rules = source_rules();
+    let names: Vec<&str> = rules.iter().map(|r| r.name.as_str()).collect();
+    assert_eq!(names, [GLOBAL_RULE, "api-testing", "pnpm-extension"]);
+    assert!(rules[0].always);
+
+    let api = &rules[1];
+    assert_eq!(
+        api.description.as_deref(),
+        Some("Workflow para testing de APIs con Postman y Jest, incluyendo casos de error")
+    );
+    assert_eq!(
+        api.globs,
+        ["*.test.ts", "src/**/*.{spec,test}.ts", "!fixtures/"]
+    );
+    assert!(api.body.starts_with("# API Testing\n"));
+    assert!(api.body.contains("```bash\npnpm test\n```"));
+}
+
+#[test]
+fn every_target_round_trips() {
+    let rules = source_rules();
+    for target in TARGETS {
+        let exporter = export::exporter(target, references(0)).unwrap();
+        let parsed = exporter.parse(&exporter.render(&rules));
+        assert_eq!(parsed.len(), rules.len(), "{target}: rule count");
+        for (before, after) in rules.iter().zip(&parsed) {
+            assert_eq!(after.name, before.name, "{target}: name");
+            assert_eq!(after.always, before.always, "{target}: always");
+            assert_eq!(after.body, before.body, "{target}: body of {}", before.name);
+            if before.always {
+                continue;
+            }
+            assert_eq!(
+                after.description, before.description,
+                "{target}: description"
+            );
+            if *target == "copilot" {
+                // `applyTo` has no negation and is anchored at the root.
+                assert_eq!(
+                    after.globs,
+                    globs_without_negation(&before.globs),
+                    "{target}: globs"
+                );
+            } else {
+                assert_eq!(after.globs, before.globs, "{target}: globs");
+            }
+        }
+    }
+}
+
+fn globs_without_negation(globs: &[String]) -> Vec<String> {
+    agent_skills::activation::root_globs(globs)
+        .into_iter()
+        .filter(|(negated, _)| !negated)
+        .map(|(_, glob)| glob)
+        .collect()
+}
+
+#[test]
+fn small_references_are_inlined_and_large_ones_linked() {
+    let project = fixture();
+    let inlined = export::exporter("cursor", references(1000))
+        .unwrap()
+        .export(&project)
+        .unwrap();
+    let api = inlined
+        .iter()
+        .find(|f| f.path.ends_with("api-testing.mdc"))
+        .unwrap();
+    assert!(api
+        .contents
+        .contains("## references/test-template.md\n\n# Test template"));
+
+    let linked = export::exporter("cursor", references(1))
+        .unwrap()
+        .export(&project)
+        .unwrap();
+    let api = linked
+        .iter()
+        .find(|f| f.path.ends_with("api-testing.mdc"))
+        .unwrap();
+    assert!(api.contents.contains(
+        "- `references/test-template.md`: @.claude/skills/api-testing/references/test-template.md"
+    ));
+}
+
+#[test]
+fn unknown_targets_are_rejected() {
+    assert!(export::exporter("notepad", references(0)).is_err());
+}
+
+#[test]
+fn names_that_climb_out_are_rejected_by_every_target() {
+    let project =
+        Project::new(Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/traversal"));
+    let hostile = Rule {
+        name: "../../../README".into(),
+        body: "Body\n".into(),
+        ..Rule::default()
+    };
+    for target in TARGETS {
+        let exporter = export::exporter(target, references(0)).unwrap();
+        assert!(
+            matches!(exporter.export(&project), Err(Error::InvalidName(_))),
+            "{target}"
+        );
+    }
+    // Targets with a file per rule; `write` checks every path before it
+    // creates anything.
+    for target in ["amp", "copilot", "cursor", "opencode", "windsurf"] {
+        let exporter = export::exporter(target, references(0)).unwrap();
+        let files = exporter.render(std::slice::from_ref(&hostile));
+        assert!(export::write(&project, &files).is_err(), "{target}");
+    }
+}
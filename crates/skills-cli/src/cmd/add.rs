@@ -0,0 +1,99 @@
+use std::path::Path;
+use std::process::ExitCode;
+
+use agent_skills::install::{Installer, SourceSpec};
+use agent_skills::lint::Diagnostic;
+use agent_skills::package::Unpacker;
+use agent_skills::resolve::Resolver;
+use agent_skills::{Error, Project};
+use anyhow::bail;
+
+#[derive(clap::Args)]
+pub struct Args {
+    /// `<path-or-git-url>#<skill>`, e.g.
+    /// `https://github.com/carloss765/agent-skills.git#typescript-extension`,
+    /// or a `.skill` archive, which must pass the `[trust]` policy.
+    source: String,
+    /// Replace a skill directory that already exists.
+    #[arg(long)]
+    force: bool,
+    /// Install even if the skill hides text from review or addresses the
+    /// agent directly.
+    #[arg(long)]
+    allow_hidden: bool,
+    /// Install even if the skills would no longer satisfy each other's
+    /// `requires`.
+    #[arg(long)]
+    allow_conflicts: bool,
+}
+
+pub fn run(project: &Project, args: Args) -> anyhow::Result<ExitCode> {
+    let archive = Path::new(&args.source);
+    if archive.extension().is_some_and(|e| e == "skill") && archive.is_file() {
+        let (name, _, signer) = super::unpack::unpack(
+            project,
+            archive,
+            None,
+            Unpacker::new()
+                .force(args.force)
+                .allow_hidden(args.allow_hidden),
+        )?;
+        super::emit(&format!("added {name} from {}{signer}", archive.display()))?;
+        return Ok(ExitCode::SUCCESS);
+    }
+    let spec: SourceSpec = args.source.parse()?;
+    let installed = screened(
+        Installer::new(project)
+            .force(args.force)
+            .allow_hidden(args.allow_hidden)
+            .allow_conflicts(args.allow_conflicts)
+            .add(&spec),
+    )?;
+    print_findings(&installed.findings);
+    let commit = match &installed.lock.commit {
+        Some(commit) => format!(" at {}", short(commit)),
+        None => String::new(),
+    };
+    super::emit(&format!(
+        "added `{}` from {}{commit}",
+        installed.name, installed.lock.source
+    ))?;
+    if args.allow_conflicts {
+        warn_unresolved(project)?;
+    }
+    Ok(ExitCode::SUCCESS)
+}
+
+/// Show what made the hidden-content scan refuse a skill before failing,
+/// and how to install past a `requires` conflict.
+pub fn screened<T>(result: agent_skills::Result<T>) -> anyhow::Result<T> {
+    match &result {
+        Err(Error::HiddenContent { findings, .. }) => print_findings(findings),
+        Err(Error::Conflict(conflict)) => {
+            bail!("{conflict}; pass --allow-conflicts to install anyway")
+        }
+        _ => {}
+    }
+    Ok(result?)
+}
+
+/// Warn when the installed skills no longer satisfy each other's
+/// `requires`, after installing with `--allow-conflicts`; `skills resolve`
+/// explains the rest.
+pub fn warn_unresolved(project: &Project) -> anyhow::Result<()> {
+    if let Err(Error::Conflict(conflict)) = Resolver::from_project(project)?.resolve_all() {
+        eprintln!("warning: {conflict}");
+    }
+    Ok(())
+}
+
+pub fn print_findings(findings: &[Diagnostic]) {
+    for finding in findings {
+        eprintln!("{finding}");
+    }
+}
+
+/// Abbreviated commit hash, as git prints it.
+pub fn short(commit: &str) -> &str {
+    commit.get(..7).unwrap_or(commit)
+}
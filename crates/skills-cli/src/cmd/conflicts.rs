@@ -0,0 +1,122 @@
+use std::fmt::Write as _;
+use std::process::ExitCode;
+
+use agent_skills::conflicts::{Detector, PairReport, Recommendation};
+use agent_skills::{Error, Project};
+use clap::ValueEnum;
+
+#[derive(clap::Args)]
+pub struct Args {
+    /// Compare only this skill with `OTHER`.
+    #[arg(requires = "other")]
+    skill: Option<String>,
+    other: Option<String>,
+    /// Similarity, from 0 to 1, from which paragraphs count as duplicates.
+    #[arg(long, value_name = "RATIO", default_value_t = 0.5)]
+    duplicate_threshold: f64,
+    /// Similarity, from 0 to 1, from which descriptions count as
+    /// overlapping.
+    #[arg(long, value_name = "RATIO", default_value_t = 0.5)]
+    description_threshold: f64,
+    /// Output format.
+    #[arg(long, value_enum, default_value_t = Format::Human)]
+    format: Format,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum Format {
+    Human,
+    Json,
+}
+
+pub fn run(project: &Project, args: Args) -> anyhow::Result<ExitCode> {
+    let detector = Detector::from_project(project)?
+        .duplicate_threshold(args.duplicate_threshold)
+        .description_threshold(args.description_threshold);
+    let reports = match (&args.skill, &args.other) {
+        (Some(a), Some(b)) => {
+            for name in [a, b] {
+                if !detector.contains(name) {
+                    return Err(Error::UnknownSkill(name.clone()).into());
+                }
+            }
+            detector.pair(a, b).into_iter().collect()
+        }
+        _ => detector.reports(),
+    };
+    let output = match args.format {
+        Format::Json => serde_json::to_string_pretty(&reports)?,
+        Format::Human => human(&reports),
+    };
+    super::emit(&output)?;
+    Ok(match reports.iter().any(PairReport::has_conflicts) {
+        true => ExitCode::FAILURE,
+        false => ExitCode::SUCCESS,
+    })
+}
+
+fn human(reports: &[PairReport]) -> String {
+    let mut out = String::new();
+    for report in reports.iter().filter(|r| !r.is_empty()) {
+        let _ = writeln!(out, "{} <> {}", report.a, report.b);
+        for glob in &report.globs {
+            let _ = writeln!(
+                out,
+                "  overlap: both activate on `{}` (`{}`, `{}`)",
+                glob.path, glob.a, glob.b
+            );
+        }
+        if let Some(descriptions) = &report.descriptions {
+            let _ = writeln!(
+                out,
+                "  overlap: descriptions are {:.0}% alike ({})",
+                descriptions.similarity * 100.0,
+                descriptions.shared.join(", ")
+            );
+        }
+        for duplicate in &report.duplicates {
+            let _ = writeln!(
+                out,
+                "  duplicate ({:.0}%): line {} \"{}\"\n                   line {} \"{}\"",
+                duplicate.similarity * 100.0,
+                duplicate.a.line,
+                duplicate.a.text,
+                duplicate.b.line,
+                duplicate.b.text
+            );
+        }
+        for contradiction in &report.contradictions {
+            let _ = writeln!(
+                out,
+                "  contradiction: to {}, {} but {}",
+                contradiction.task,
+                stance(&report.a, &contradiction.a),
+                stance(&report.b, &contradiction.b)
+            );
+        }
+    }
+    let overlapping = reports.iter().filter(|r| r.overlaps()).count();
+    let conflicting = reports.iter().filter(|r| r.has_conflicts()).count();
+    match (overlapping, conflicting) {
+        (0, 0) => out.push_str("no overlapping or conflicting skills\n"),
+        _ => {
+            let _ = writeln!(
+                out,
+                "{overlapping} pair(s) overlap, {conflicting} repeat or contradict each other"
+            );
+        }
+    }
+    out
+}
+
+/// E.g. "`npm-extension` uses `npm install` (line 12)".
+fn stance(skill: &str, recommendation: &Recommendation) -> String {
+    let verb = match recommendation.discouraged {
+        true => "warns against",
+        false => "uses",
+    };
+    format!(
+        "`{skill}` {verb} `{}` (line {})",
+        recommendation.command, recommendation.line
+    )
+}
@@ -0,0 +1,145 @@
+use std::fmt::Write as _;
+use std::path::PathBuf;
+use std::process::ExitCode;
+
+use agent_skills::layers::{Effective, Layers};
+use agent_skills::{Error, Project};
+use clap::ValueEnum;
+use serde_json::json;
+
+#[derive(clap::Args)]
+pub struct Args {
+    /// Skill to explain; the global configuration and a summary of every
+    /// skill when omitted.
+    skill: Option<String>,
+    /// Directory to explain for, which picks the directory-level
+    /// `.claude/` trees that apply.
+    #[arg(long, value_name = "DIR", default_value = ".")]
+    dir: PathBuf,
+    /// Output format.
+    #[arg(long, value_enum, default_value_t = Format::Human)]
+    format: Format,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum Format {
+    Human,
+    Json,
+}
+
+pub fn run(project: &Project, args: Args) -> anyhow::Result<ExitCode> {
+    let layers = Layers::discover(project, &args.dir)?;
+    if let Some(name) = &args.skill {
+        let effective = layers
+            .skill(name)?
+            .ok_or_else(|| Error::UnknownSkill(name.clone()))?;
+        let output = match args.format {
+            Format::Json => serde_json::to_string_pretty(&effective)?,
+            Format::Human => match &effective.removed_by {
+                Some(by) => format!(
+                    "`{name}` is disabled by {} ({})",
+                    by.path.display(),
+                    by.scope
+                ),
+                None => annotated(&effective),
+            },
+        };
+        super::emit(&output)?;
+        return Ok(ExitCode::SUCCESS);
+    }
+
+    let global = layers.global()?;
+    let mut skills = Vec::new();
+    for name in layers.skill_names()? {
+        if let Some(effective) = layers.skill(&name)? {
+            skills.push((name, effective));
+        }
+    }
+    if matches!(args.format, Format::Json) {
+        let skills: serde_json::Map<String, serde_json::Value> = skills
+            .into_iter()
+            .map(|(name, effective)| Ok((name, serde_json::to_value(effective)?)))
+            .collect::<serde_json::Result<_>>()?;
+        super::emit(&serde_json::to_string_pretty(&json!({
+            "layers": layers.layers(),
+            "global": global,
+            "skills": skills,
+        }))?)?;
+        return Ok(ExitCode::SUCCESS);
+    }
+
+    let mut out = String::from("Layers, lowest precedence first:\n");
+    for layer in layers.layers() {
+        let _ = writeln!(out, "  {:<10} {}", layer.scope, layer.dir.display());
+    }
+    out.push_str("\nSkills:\n");
+    if skills.is_empty() {
+        out.push_str("  (none)\n");
+    }
+    for (name, effective) in &skills {
+        let _ = writeln!(out, "  {name}: {}", summary(effective));
+    }
+    match &global {
+        Some(global) => {
+            out.push_str(
+                "\nGlobal configuration, which takes precedence over skill instructions:\n\n",
+            );
+            out.push_str(&annotated(global));
+        }
+        None => out.push_str("\nNo global SKILL.md in any layer.\n"),
+    }
+    super::emit(&out)?;
+    Ok(ExitCode::SUCCESS)
+}
+
+/// Where a skill comes from, e.g. `repo, patched by directory (disables
+/// Troubleshooting)`.
+fn summary(effective: &Effective) -> String {
+    if let Some(by) = &effective.removed_by {
+        return format!("disabled by {}", by.scope);
+    }
+    let mut scopes = effective.files.iter().map(|f| f.scope.to_string());
+    let mut text = scopes.next().unwrap_or_default();
+    for scope in scopes {
+        let _ = write!(text, ", patched by {scope}");
+    }
+    if !effective.disabled.is_empty() {
+        let titles: Vec<&str> = effective
+            .disabled
+            .iter()
+            .map(|d| d.title.as_str())
+            .collect();
+        let _ = write!(text, " (disables {})", titles.join(", "));
+    }
+    text
+}
+
+/// The merged markdown with a comment before each run of parts from the
+/// same file, and one per section an override dropped.
+fn annotated(effective: &Effective) -> String {
+    let mut out = String::new();
+    let mut previous = None;
+    for part in &effective.parts {
+        if previous != Some(&part.from) {
+            let _ = writeln!(
+                out,
+                "<!-- {}: {} -->",
+                part.from.scope,
+                part.from.path.display()
+            );
+            previous = Some(&part.from);
+        }
+        out.push_str(&part.text);
+        out.push_str("\n\n");
+    }
+    for disabled in &effective.disabled {
+        let _ = writeln!(
+            out,
+            "<!-- \"{}\" disabled by {}: {} -->",
+            disabled.title,
+            disabled.by.scope,
+            disabled.by.path.display()
+        );
+    }
+    out
+}
@@ -0,0 +1,48 @@
+use std::fmt::Write as _;
+use std::process::ExitCode;
+
+use agent_skills::export::{self, ReferencePolicy, TARGETS};
+use agent_skills::tokens::{self, TokenCounter};
+use agent_skills::Project;
+use clap::builder::PossibleValuesParser;
+
+#[derive(clap::Args)]
+pub struct Args {
+    /// Tool to export rules for.
+    #[arg(long, value_parser = PossibleValuesParser::new(TARGETS))]
+    target: String,
+    /// Inline references of up to this many tokens and link larger ones.
+    /// Defaults to `export.inline-threshold` from skills.toml.
+    #[arg(long, value_name = "TOKENS")]
+    inline_threshold: Option<usize>,
+    /// Print the files that would be written without writing them.
+    #[arg(long)]
+    dry_run: bool,
+}
+
+pub fn run(project: &Project, args: Args) -> anyhow::Result<ExitCode> {
+    super::secrets::deny_new(project)?;
+    let config = project.config()?;
+    let counter = TokenCounter::new(tokens::tokenizer(config.tokens.tokenizer.as_deref())?);
+    let references = ReferencePolicy::new(counter).inline_threshold(
+        args.inline_threshold
+            .unwrap_or(config.export.inline_threshold),
+    );
+    let files = export::exporter(&args.target, references)?.export(project)?;
+    if files.is_empty() {
+        super::emit("no skills to export")?;
+        return Ok(ExitCode::FAILURE);
+    }
+    let mut out = String::new();
+    if args.dry_run {
+        for file in &files {
+            let _ = writeln!(out, "==> {} <==\n{}", file.path.display(), file.contents);
+        }
+    } else {
+        for path in export::write(project, &files)? {
+            let _ = writeln!(out, "wrote {}", path.display());
+        }
+    }
+    super::emit(&out)?;
+    Ok(ExitCode::SUCCESS)
+}
@@ -0,0 +1,98 @@
+use std::fmt::Write as _;
+use std::process::ExitCode;
+
+use agent_skills::graph::Graph;
+use agent_skills::Project;
+use clap::ValueEnum;
+use serde_json::json;
+
+#[derive(clap::Args)]
+pub struct Args {
+    /// Show what loading this skill pulls into context, in order.
+    skill: Option<String>,
+    /// Output format.
+    #[arg(long, value_enum, default_value_t = Format::Human)]
+    format: Format,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum Format {
+    Human,
+    Json,
+    /// Graphviz, e.g. `skills graph --format dot | dot -Tsvg`.
+    Dot,
+    /// A Mermaid flowchart, for markdown that renders it.
+    Mermaid,
+}
+
+pub fn run(project: &Project, args: Args) -> anyhow::Result<ExitCode> {
+    let graph = Graph::from_project(project)?;
+    if let Some(skill) = &args.skill {
+        return load_order(&graph, skill, args.format);
+    }
+    let unknown = graph.unknown();
+    let cycles = graph.cycles();
+    let output = match args.format {
+        Format::Dot => graph.to_dot(),
+        Format::Mermaid => graph.to_mermaid(),
+        Format::Json => serde_json::to_string_pretty(&json!({
+            "skills": graph.names().collect::<Vec<_>>(),
+            "edges": graph.edges(),
+            "unknown": unknown,
+            "cycles": cycles,
+        }))?,
+        Format::Human => {
+            let mut out = String::new();
+            for edge in graph.edges() {
+                let _ = writeln!(out, "{} {} {}", edge.from, edge.kind, edge.to);
+            }
+            for edge in &unknown {
+                let _ = writeln!(
+                    out,
+                    "error: `{}` {} `{}`, which is not installed",
+                    edge.from, edge.kind, edge.to
+                );
+            }
+            for cycle in &cycles {
+                let _ = writeln!(
+                    out,
+                    "error: skills depend on each other: {}",
+                    cycle.join(" -> ")
+                );
+            }
+            if out.is_empty() {
+                out.push_str("no skill uses depends_on or extends");
+            }
+            out
+        }
+    };
+    super::emit(&output)?;
+    Ok(match unknown.is_empty() && cycles.is_empty() {
+        true => ExitCode::SUCCESS,
+        false => ExitCode::FAILURE,
+    })
+}
+
+fn load_order(graph: &Graph, skill: &str, format: Format) -> anyhow::Result<ExitCode> {
+    if !graph.contains(skill) {
+        return Err(agent_skills::Error::UnknownSkill(skill.to_string()).into());
+    }
+    let prerequisites = graph.prerequisites(skill)?;
+    let bases = graph.bases(skill)?;
+    let output = match format {
+        Format::Json => serde_json::to_string_pretty(&json!({
+            "prerequisites": prerequisites,
+            "bases": &bases[..bases.len() - 1],
+        }))?,
+        _ => {
+            let mut out = String::new();
+            for prerequisite in &prerequisites {
+                let _ = writeln!(out, "{prerequisite}");
+            }
+            let _ = writeln!(out, "{}", bases.join(" + "));
+            out
+        }
+    };
+    super::emit(&output)?;
+    Ok(ExitCode::SUCCESS)
+}
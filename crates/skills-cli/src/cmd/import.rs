@@ -0,0 +1,66 @@
+use std::fmt::Write as _;
+use std::path::PathBuf;
+use std::process::ExitCode;
+
+use agent_skills::import::{Import, Importer};
+use agent_skills::Project;
+
+#[derive(clap::Args)]
+pub struct Args {
+    /// Rule files to import. Defaults to every known file in the project:
+    /// .cursorrules, AGENTS.md, CLAUDE.md, .cursor/rules/*.mdc and
+    /// Copilot instruction files.
+    paths: Vec<PathBuf>,
+    /// Print the skills that would be created without writing them.
+    #[arg(long)]
+    dry_run: bool,
+    /// Replace existing skills and the global SKILL.md.
+    #[arg(long)]
+    force: bool,
+}
+
+pub fn run(project: &Project, args: Args) -> anyhow::Result<ExitCode> {
+    let sources = match args.paths.is_empty() {
+        true => Importer::discover(project)?,
+        false => args.paths,
+    };
+    if sources.is_empty() {
+        super::emit("no rule files to import")?;
+        return Ok(ExitCode::FAILURE);
+    }
+    let import = Importer::from_config(&project.config()?).import(&sources)?;
+    let mut out = summary(&import);
+    if args.dry_run {
+        let files = import
+            .global
+            .iter()
+            .chain(import.skills.iter().flat_map(|s| &s.files));
+        for file in files {
+            let _ = writeln!(out, "\n==> {} <==\n{}", file.path.display(), file.contents);
+        }
+    } else {
+        let written = import.write(project, args.force)?;
+        let _ = writeln!(out, "wrote {} files", written.len());
+    }
+    super::emit(&out)?;
+    Ok(ExitCode::SUCCESS)
+}
+
+fn summary(import: &Import) -> String {
+    let mut out = String::new();
+    if let Some(global) = &import.global {
+        let _ = writeln!(out, "{}", global.path.display());
+    }
+    for skill in &import.skills {
+        let _ = write!(out, "{}  <- {}", skill.name, skill.source.display());
+        match skill.moved.len() {
+            0 => {}
+            1 => out.push_str(" (1 section moved to references/)"),
+            n => {
+                let _ = write!(out, " ({n} sections moved to references/)");
+            }
+        }
+        out.push('\n');
+    }
+    out
+}
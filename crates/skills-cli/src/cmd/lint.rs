@@ -0,0 +1,42 @@
+use std::path::PathBuf;
+use std::process::ExitCode;
+
+use agent_skills::lint::Linter;
+use agent_skills::Project;
+use clap::ValueEnum;
+
+#[derive(clap::Args)]
+pub struct Args {
+    /// Skill directories or SKILL.md files to check. Defaults to every skill
+    /// under `.claude/skills`.
+    paths: Vec<PathBuf>,
+    /// Output format.
+    #[arg(long, value_enum, default_value_t = Format::Human)]
+    format: Format,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum Format {
+    Human,
+    Json,
+    Sarif,
+}
+
+pub fn run(project: &Project, args: Args) -> anyhow::Result<ExitCode> {
+    let linter = Linter::from_config(&project.config()?)?;
+    let mut report = match args.paths.is_empty() {
+        true => linter.lint_project(project)?,
+        false => linter.lint_paths(&args.paths),
+    };
+    report.sort();
+    let output = match args.format {
+        Format::Human => report.to_human(),
+        Format::Json => report.to_json(),
+        Format::Sarif => report.to_sarif(),
+    };
+    super::emit(&output)?;
+    Ok(match report.has_errors() {
+        true => ExitCode::FAILURE,
+        false => ExitCode::SUCCESS,
+    })
+}
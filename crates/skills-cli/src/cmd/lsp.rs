@@ -0,0 +1,18 @@
+use std::io;
+use std::process::ExitCode;
+
+use agent_skills::lsp::Server;
+use agent_skills::Project;
+
+#[derive(clap::Args)]
+pub struct Args {
+    /// Accepted for editors that pass it; stdio is the only transport.
+    #[arg(long, hide = true)]
+    stdio: bool,
+}
+
+pub fn run(project: &Project, _args: Args) -> anyhow::Result<ExitCode> {
+    let mut server = Server::from_project(project)?;
+    server.serve(io::stdin().lock(), io::stdout().lock())?;
+    Ok(ExitCode::SUCCESS)
+}
@@ -0,0 +1,60 @@
+use std::fmt::Write as _;
+use std::path::PathBuf;
+use std::process::ExitCode;
+
+use agent_skills::relevance::{Match, Matcher};
+use agent_skills::{Project, SkillLoader};
+use clap::ValueEnum;
+
+#[derive(clap::Args)]
+pub struct Args {
+    /// What the user asked for.
+    utterance: String,
+    /// A file open in the editor; repeat for several.
+    #[arg(long = "open", value_name = "FILE")]
+    open_files: Vec<PathBuf>,
+    /// Show at most this many skills.
+    #[arg(long, default_value_t = 5)]
+    limit: usize,
+    /// Output format.
+    #[arg(long, value_enum, default_value_t = Format::Human)]
+    format: Format,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum Format {
+    Human,
+    Json,
+}
+
+pub fn run(project: &Project, args: Args) -> anyhow::Result<ExitCode> {
+    let mut loader = SkillLoader::from_project(project)?;
+    let matcher = Matcher::new(&loader.metadata_catalog());
+    let mut matches = matcher.rank(&args.utterance, &args.open_files);
+    matches.truncate(args.limit);
+    let output = match args.format {
+        Format::Human => human(&matches),
+        Format::Json => serde_json::to_string_pretty(&matches)?,
+    };
+    super::emit(&output)?;
+    Ok(match matches.is_empty() {
+        true => ExitCode::FAILURE,
+        false => ExitCode::SUCCESS,
+    })
+}
+
+fn human(matches: &[Match]) -> String {
+    if matches.is_empty() {
+        return "no relevant skill\n".into();
+    }
+    let mut out = String::new();
+    for m in matches {
+        let terms: Vec<String> = m
+            .terms
+            .iter()
+            .map(|t| format!("{} ({:.2})", t.word, t.score))
+            .collect();
+        let _ = writeln!(out, "{:6.2}  {}  [{}]", m.score, m.name, terms.join(", "));
+    }
+    out
+}
@@ -0,0 +1,31 @@
+use std::io;
+use std::process::ExitCode;
+use std::time::Duration;
+
+use agent_skills::mcp::Server;
+use agent_skills::{Project, SkillLoader};
+
+#[derive(clap::Args)]
+pub struct Args {
+    /// Do not offer the `run_script` tool, so clients cannot execute skill
+    /// code.
+    #[arg(long)]
+    no_scripts: bool,
+    /// Seconds a script may run before it is killed. Defaults to
+    /// `scripts.timeout` from skills.toml.
+    #[arg(long)]
+    timeout: Option<u64>,
+}
+
+pub fn run(project: &Project, args: Args) -> anyhow::Result<ExitCode> {
+    let mut loader = SkillLoader::from_project(project)?;
+    if let Some(seconds) = args.timeout {
+        loader = loader.timeout(Duration::from_secs(seconds));
+    }
+    for error in loader.invalid() {
+        eprintln!("warning: {error}");
+    }
+    let mut server = Server::new(loader).scripts(!args.no_scripts);
+    server.serve(io::stdin().lock(), io::stdout().lock())?;
+    Ok(ExitCode::SUCCESS)
+}
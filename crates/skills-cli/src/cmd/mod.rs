@@ -0,0 +1,44 @@
+//! One module per subcommand, each with an `Args` struct and a `run`
+//! function returning the process exit code.
+
+pub mod add;
+pub mod conflicts;
+pub mod explain;
+pub mod export;
+pub mod graph;
+pub mod import;
+pub mod lint;
+pub mod lsp;
+pub mod matches;
+pub mod mcp;
+pub mod new;
+pub mod pack;
+pub mod registry;
+pub mod remove;
+pub mod resolve;
+pub mod run;
+pub mod secrets;
+pub mod sign;
+pub mod tokens;
+pub mod unpack;
+pub mod update;
+pub mod verify;
+pub mod which;
+
+use std::io::{self, Write};
+
+/// Write command output to stdout, adding a trailing newline if missing.
+/// A closed pipe (`skills lint | head`) is not an error.
+pub fn emit(output: &str) -> io::Result<()> {
+    let mut stdout = io::stdout().lock();
+    let result = stdout
+        .write_all(output.as_bytes())
+        .and_then(|()| match output.ends_with('\n') {
+            true => Ok(()),
+            false => stdout.write_all(b"\n"),
+        });
+    match result {
+        Err(e) if e.kind() == io::ErrorKind::BrokenPipe => Ok(()),
+        other => other,
+    }
+}
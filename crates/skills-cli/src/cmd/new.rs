@@ -0,0 +1,64 @@
+use std::fmt::Write as _;
+use std::process::ExitCode;
+
+use agent_skills::scaffold::{self, NewSkill, Template};
+use agent_skills::Project;
+use clap::ValueEnum;
+
+#[derive(clap::Args)]
+pub struct Args {
+    /// Skill name in kebab-case; also the directory name.
+    name: String,
+    /// Starting layout.
+    #[arg(short, long, value_enum, default_value_t = TemplateArg::Basic)]
+    template: TemplateArg,
+    /// Frontmatter `description`, instead of the template placeholder.
+    #[arg(short, long)]
+    description: Option<String>,
+    /// Frontmatter `globs`; may be repeated.
+    #[arg(short, long = "glob", value_name = "GLOB")]
+    globs: Vec<String>,
+    /// Overwrite an existing skill directory.
+    #[arg(long)]
+    force: bool,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum TemplateArg {
+    Basic,
+    WithReferences,
+    WithScripts,
+    ApiTesting,
+}
+
+impl From<TemplateArg> for Template {
+    fn from(arg: TemplateArg) -> Self {
+        match arg {
+            TemplateArg::Basic => Template::Basic,
+            TemplateArg::WithReferences => Template::WithReferences,
+            TemplateArg::WithScripts => Template::WithScripts,
+            TemplateArg::ApiTesting => Template::ApiTesting,
+        }
+    }
+}
+
+pub fn run(project: &Project, args: Args) -> anyhow::Result<ExitCode> {
+    let options = NewSkill {
+        description: args.description,
+        globs: args.globs,
+        force: args.force,
+        ..NewSkill::new(args.name, args.template.into())
+    };
+    let written = scaffold::create(&project.skills_dir(), &options)?;
+    let mut out = String::new();
+    let _ = writeln!(
+        out,
+        "created `{}` from the {} template",
+        options.name, options.template
+    );
+    for path in &written {
+        let _ = writeln!(out, "  {}", path.display());
+    }
+    super::emit(&out)?;
+    Ok(ExitCode::SUCCESS)
+}
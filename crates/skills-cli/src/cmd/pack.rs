@@ -0,0 +1,44 @@
+use std::path::PathBuf;
+use std::process::ExitCode;
+
+use agent_skills::package::Package;
+use agent_skills::tokens::{self, TokenCounter};
+use agent_skills::{Error, Project};
+
+#[derive(clap::Args)]
+pub struct Args {
+    /// Skill to pack.
+    name: String,
+    /// Archive to write. Defaults to `<name>-<version>.skill` in the
+    /// current directory.
+    #[arg(short, long, value_name = "FILE")]
+    output: Option<PathBuf>,
+}
+
+pub fn run(project: &Project, args: Args) -> anyhow::Result<ExitCode> {
+    super::secrets::deny_new(project)?;
+    let dir = project.skill(&args.name);
+    if !dir.skill_file().is_file() {
+        return Err(Error::UnknownSkill(args.name).into());
+    }
+    let config = project.config()?;
+    let counter = TokenCounter::new(tokens::tokenizer(config.tokens.tokenizer.as_deref())?);
+    let package = Package::from_dir(&dir, &counter)?;
+    let manifest = &package.manifest;
+    let output = args
+        .output
+        .unwrap_or_else(|| PathBuf::from(manifest.file_name()));
+    package.write(&output)?;
+    let tokens = &manifest.tokens;
+    super::emit(&format!(
+        "packed `{}` into {} ({} files; {} tokens: {} metadata, {} instructions, {} on demand)",
+        manifest.name,
+        output.display(),
+        manifest.files.len(),
+        tokens.tokenizer,
+        tokens.metadata,
+        tokens.instructions,
+        tokens.on_demand
+    ))?;
+    Ok(ExitCode::SUCCESS)
+}
@@ -0,0 +1,163 @@
+use std::env;
+use std::fmt::Write as _;
+use std::fs;
+use std::path::PathBuf;
+use std::process::ExitCode;
+
+use agent_skills::registry::{Client, Release, Server, Store, TOKEN_ENV};
+use agent_skills::signing::signature_path;
+use agent_skills::{Error, Project};
+use clap::ValueEnum;
+
+#[derive(clap::Args)]
+pub struct Args {
+    #[command(subcommand)]
+    command: Command,
+    /// Registry URL. Defaults to `registry.url` from skills.toml.
+    #[arg(long, global = true, value_name = "URL")]
+    registry: Option<String>,
+}
+
+#[derive(clap::Subcommand)]
+enum Command {
+    /// Serve packages stored in a directory over HTTP.
+    Serve {
+        /// Directory holding published packages.
+        #[arg(long, default_value = "registry")]
+        dir: PathBuf,
+        /// Address to listen on.
+        #[arg(long, default_value = "127.0.0.1:8080")]
+        listen: String,
+    },
+    /// Upload a `.skill` archive, and its `.sig` file if present.
+    Publish { archive: PathBuf },
+    /// Find skills whose name or description holds every word.
+    Search {
+        query: Vec<String>,
+        /// Output format.
+        #[arg(long, value_enum, default_value_t = Format::Human)]
+        format: Format,
+    },
+    /// List the published versions of a skill.
+    Versions {
+        name: String,
+        /// Output format.
+        #[arg(long, value_enum, default_value_t = Format::Human)]
+        format: Format,
+    },
+    /// Save a package, and its signature, for `skills unpack`.
+    Download {
+        /// `<name>` for the latest version, or `<name>@<version>`.
+        skill: String,
+        /// Directory to save into.
+        #[arg(short, long, value_name = "DIR", default_value = ".")]
+        output: PathBuf,
+    },
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum Format {
+    Human,
+    Json,
+}
+
+pub fn run(project: &Project, args: Args) -> anyhow::Result<ExitCode> {
+    // Publishers present the token the server was started with.
+    let token = env::var(TOKEN_ENV).ok().filter(|t| !t.is_empty());
+    if let Command::Serve { dir, listen } = &args.command {
+        let server = Server::bind(Store::new(dir), listen)?.token(token);
+        if let Some(addr) = server.addr() {
+            eprintln!("serving {} on http://{addr}", dir.display());
+        }
+        server.run()?;
+        return Ok(ExitCode::SUCCESS);
+    }
+    let url = match args.registry {
+        Some(url) => url,
+        None => project.config()?.registry.url.ok_or_else(|| {
+            anyhow::anyhow!("no registry; pass --registry or set registry.url in skills.toml")
+        })?,
+    };
+    let client = Client::new(&url)?.token(token);
+    match args.command {
+        Command::Serve { .. } => unreachable!("handled above"),
+        Command::Publish { archive } => {
+            let release = client.publish(&archive)?;
+            super::emit(&format!(
+                "published `{}` {} to {}{}",
+                release.name,
+                release.version,
+                client.url(),
+                signed(&release)
+            ))?;
+        }
+        Command::Search { query, format } => {
+            let found = client.search(&query.join(" "))?;
+            if found.is_empty() && matches!(format, Format::Human) {
+                super::emit("no skills found")?;
+                return Ok(ExitCode::FAILURE);
+            }
+            super::emit(&output(&found, format, |r| {
+                let description = r.description.as_deref().unwrap_or("");
+                format!("{} {}  {description}", r.name, r.version)
+            })?)?;
+        }
+        Command::Versions { name, format } => {
+            let releases = client.releases(&name)?;
+            super::emit(&output(&releases, format, |r| {
+                format!("{}  {}{}", r.version, r.hash, signed(r))
+            })?)?;
+        }
+        Command::Download { skill, output } => {
+            let (name, version) = match skill.split_once('@') {
+                Some((name, version)) => (name, Some(version)),
+                None => (skill.as_str(), None),
+            };
+            let path = client.download(name, version, &output)?;
+            // Keep nothing the project would refuse to unpack.
+            let signer = fs::read(&path)
+                .map_err(|source| {
+                    Error::Io {
+                        path: path.clone(),
+                        source,
+                    }
+                    .into()
+                })
+                .and_then(|bytes| super::unpack::trusted(project, &path, &bytes));
+            let signer = match signer {
+                Ok(signer) => signer,
+                Err(e) => {
+                    let _ = fs::remove_file(signature_path(&path));
+                    let _ = fs::remove_file(&path);
+                    return Err(e);
+                }
+            };
+            super::emit(&format!("saved {}{signer}", path.display()))?;
+        }
+    }
+    Ok(ExitCode::SUCCESS)
+}
+
+fn output(
+    releases: &[Release],
+    format: Format,
+    line: impl Fn(&Release) -> String,
+) -> anyhow::Result<String> {
+    Ok(match format {
+        Format::Human => {
+            let mut out = String::new();
+            for release in releases {
+                let _ = writeln!(out, "{}", line(release));
+            }
+            out
+        }
+        Format::Json => serde_json::to_string_pretty(releases)?,
+    })
+}
+
+fn signed(release: &Release) -> &'static str {
+    match release.signed {
+        true => " (signed)",
+        false => "",
+    }
+}
@@ -0,0 +1,21 @@
+use std::process::ExitCode;
+
+use agent_skills::install::Installer;
+use agent_skills::Project;
+
+#[derive(clap::Args)]
+pub struct Args {
+    /// Installed skill to delete.
+    name: String,
+    /// Also delete a skill that is not in skills.lock.
+    #[arg(long)]
+    force: bool,
+}
+
+pub fn run(project: &Project, args: Args) -> anyhow::Result<ExitCode> {
+    Installer::new(project)
+        .force(args.force)
+        .remove(&args.name)?;
+    super::emit(&format!("removed `{}`", args.name))?;
+    Ok(ExitCode::SUCCESS)
+}
@@ -0,0 +1,81 @@
+use std::fmt::Write as _;
+use std::process::ExitCode;
+
+use agent_skills::registry::Client;
+use agent_skills::resolve::{Origin, Resolver};
+use agent_skills::{Error, Project};
+use clap::ValueEnum;
+use serde_json::json;
+
+#[derive(clap::Args)]
+pub struct Args {
+    /// Skills to resolve besides the installed ones, e.g. before adding
+    /// them.
+    names: Vec<String>,
+    /// Registry to take missing versions from. Defaults to `registry.url`
+    /// from skills.toml; without one only installed skills are considered.
+    #[arg(long, value_name = "URL")]
+    registry: Option<String>,
+    /// Output format.
+    #[arg(long, value_enum, default_value_t = Format::Human)]
+    format: Format,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum Format {
+    Human,
+    Json,
+}
+
+pub fn run(project: &Project, args: Args) -> anyhow::Result<ExitCode> {
+    let mut resolver = Resolver::from_project(project)?;
+    let url = match args.registry {
+        Some(url) => Some(url),
+        None => project.config()?.registry.url,
+    };
+    if let Some(url) = url {
+        resolver = resolver.registry(&Client::new(&url)?, &args.names)?;
+    }
+    let mut roots = resolver.installed();
+    roots.extend(args.names);
+    let resolved = match resolver.resolve(&roots) {
+        Ok(resolved) => resolved,
+        Err(Error::Conflict(conflict)) => {
+            match args.format {
+                Format::Human => eprintln!("error: {conflict}"),
+                Format::Json => super::emit(&serde_json::to_string_pretty(
+                    &json!({ "conflict": conflict }),
+                )?)?,
+            }
+            return Ok(ExitCode::FAILURE);
+        }
+        Err(e) => return Err(e.into()),
+    };
+    if matches!(args.format, Format::Json) {
+        super::emit(&serde_json::to_string_pretty(
+            &json!({ "resolved": resolved }),
+        )?)?;
+        return Ok(ExitCode::SUCCESS);
+    }
+    let mut out = String::new();
+    let mut missing = Vec::new();
+    for skill in &resolved {
+        let version = skill.version.as_deref().unwrap_or("(unversioned)");
+        let _ = match skill.origin {
+            Origin::Installed => writeln!(out, "{} {version}", skill.name),
+            Origin::Registry => {
+                missing.push(format!("{}@{version}", skill.name));
+                writeln!(out, "{} {version}  (from the registry)", skill.name)
+            }
+        };
+    }
+    if !missing.is_empty() {
+        let _ = writeln!(
+            out,
+            "\nnot installed; fetch each with `skills registry download` and `skills unpack`: {}",
+            missing.join(", ")
+        );
+    }
+    super::emit(&out)?;
+    Ok(ExitCode::SUCCESS)
+}
@@ -0,0 +1,99 @@
+use std::process::ExitCode;
+use std::time::Duration;
+
+use agent_skills::script::{Runner, ScriptOutput};
+use agent_skills::tokens::{self, TokenCounter};
+use agent_skills::{Project, SkillLoader};
+use clap::ValueEnum;
+
+#[derive(clap::Args)]
+pub struct Args {
+    /// Skill the script belongs to.
+    skill: String,
+    /// Script path, relative to the skill's `scripts/` directory.
+    script: String,
+    /// Arguments passed to the script; put them after `--` when they start
+    /// with a dash.
+    #[arg(trailing_var_arg = true)]
+    args: Vec<String>,
+    /// Seconds the script may run. Defaults to `scripts.timeout`.
+    #[arg(long)]
+    timeout: Option<u64>,
+    /// Bytes kept from each of stdout and stderr; 0 keeps everything.
+    /// Defaults to `scripts.max-output`.
+    #[arg(long)]
+    max_output: Option<usize>,
+    /// Pass an environment variable through to the script.
+    #[arg(long = "env", value_name = "NAME")]
+    env: Vec<String>,
+    /// Let the script use the network.
+    #[arg(long)]
+    allow_network: bool,
+    /// Run the script without isolation.
+    #[arg(long)]
+    no_sandbox: bool,
+    /// Output format.
+    #[arg(long, value_enum, default_value_t = Format::Human)]
+    format: Format,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum Format {
+    Human,
+    Json,
+}
+
+pub fn run(project: &Project, args: Args) -> anyhow::Result<ExitCode> {
+    let config = project.config()?;
+    let counter = TokenCounter::new(tokens::tokenizer(config.tokens.tokenizer.as_deref())?);
+    let mut runner = Runner::new(counter)
+        .config(&config.scripts)
+        .working_dir(project.root());
+    if let Some(seconds) = args.timeout {
+        runner = runner.timeout(Duration::from_secs(seconds));
+    }
+    if let Some(bytes) = args.max_output {
+        runner = runner.max_output(bytes);
+    }
+    for name in args.env {
+        runner = runner.allow_env(name);
+    }
+    if args.allow_network {
+        runner = runner.network(true);
+    }
+    if args.no_sandbox {
+        runner = runner.sandbox(false);
+    }
+    let mut loader = SkillLoader::from_project(project)?.runner(runner);
+    let output = loader.run_script(&args.skill, &args.script, &args.args)?;
+    match args.format {
+        Format::Human => {
+            super::emit(&output.stdout)?;
+            eprint!("{}", output.stderr);
+            eprintln!("{}", summary(&output));
+        }
+        Format::Json => super::emit(&serde_json::to_string_pretty(&output)?)?,
+    }
+    Ok(match output.success() {
+        true => ExitCode::SUCCESS,
+        false => ExitCode::FAILURE,
+    })
+}
+
+fn summary(output: &ScriptOutput) -> String {
+    let mut parts = vec![match (output.code, output.signal) {
+        _ if output.timed_out => "timed out".to_string(),
+        (Some(code), _) => format!("exit {code}"),
+        (None, Some(signal)) => format!("killed by signal {signal}"),
+        (None, None) => "killed".to_string(),
+    }];
+    parts.push(format!("{} tokens", output.tokens));
+    if output.truncated > 0 {
+        parts.push(format!("{} bytes truncated", output.truncated));
+    }
+    parts.push(format!("{} ms", output.elapsed_ms));
+    if !output.sandboxed {
+        parts.push("not sandboxed".to_string());
+    }
+    format!("[{}]", parts.join(", "))
+}
@@ -0,0 +1,99 @@
+use std::fmt::Write as _;
+use std::process::ExitCode;
+
+use agent_skills::secrets::{self, Baseline, Leak, BASELINE_FILE};
+use agent_skills::{Error, Project};
+use clap::ValueEnum;
+use serde_json::Value;
+
+#[derive(clap::Args)]
+pub struct Args {
+    /// Accept every current finding, rewriting skills.secrets.toml.
+    #[arg(long)]
+    update_baseline: bool,
+    /// Also list findings the baseline accepts.
+    #[arg(long)]
+    all: bool,
+    /// Output format.
+    #[arg(long, value_enum, default_value_t = Format::Human)]
+    format: Format,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum Format {
+    Human,
+    Json,
+}
+
+struct Entry<'a> {
+    leak: &'a Leak,
+    accepted: bool,
+}
+
+pub fn run(project: &Project, args: Args) -> anyhow::Result<ExitCode> {
+    let leaks = secrets::scan_project(project)?;
+    let path = project.secrets_baseline();
+    if args.update_baseline {
+        let baseline = Baseline::new(project, &leaks);
+        baseline.save(&path)?;
+        super::emit(&format!(
+            "accepted {} in {BASELINE_FILE}",
+            secrets::count(baseline.accepted.len())
+        ))?;
+        return Ok(ExitCode::SUCCESS);
+    }
+    let baseline = Baseline::load(&path)?;
+    let entries: Vec<Entry> = leaks
+        .iter()
+        .map(|leak| Entry {
+            leak,
+            accepted: baseline.accepts(project, leak),
+        })
+        .collect();
+    let new = entries.iter().filter(|e| !e.accepted).count();
+    let shown = entries.iter().filter(|e| args.all || !e.accepted);
+    let output = match args.format {
+        Format::Human => {
+            let mut out = String::new();
+            for entry in shown {
+                let _ = match entry.accepted {
+                    true => writeln!(out, "{} (accepted)", entry.leak),
+                    false => writeln!(out, "{}", entry.leak),
+                };
+            }
+            let _ = writeln!(
+                out,
+                "{} new, {} accepted in {BASELINE_FILE}",
+                secrets::count(new),
+                entries.len() - new
+            );
+            out
+        }
+        Format::Json => {
+            let mut list = Vec::new();
+            for entry in shown {
+                let mut value = serde_json::to_value(entry.leak)?;
+                value["accepted"] = Value::Bool(entry.accepted);
+                list.push(value);
+            }
+            serde_json::to_string_pretty(&list)?
+        }
+    };
+    super::emit(&output)?;
+    Ok(match new {
+        0 => ExitCode::SUCCESS,
+        _ => ExitCode::FAILURE,
+    })
+}
+
+/// Refuse to publish skills holding secrets the baseline does not accept,
+/// listing them first.
+pub fn deny_new(project: &Project) -> anyhow::Result<()> {
+    let result = secrets::deny_new(project);
+    if let Err(Error::Secrets(leaks)) = &result {
+        for leak in leaks {
+            eprintln!("{leak}");
+        }
+    }
+    Ok(result?)
+}
@@ -0,0 +1,43 @@
+use std::path::PathBuf;
+use std::process::ExitCode;
+
+use agent_skills::package::Package;
+use agent_skills::signing::{self, SecretKey};
+use agent_skills::Project;
+
+#[derive(clap::Args)]
+pub struct Args {
+    /// `.skill` archive to sign; the signature goes to `<archive>.sig`.
+    #[arg(required_unless_present = "generate_key")]
+    archive: Option<PathBuf>,
+    /// Secret key file to sign with.
+    #[arg(long, value_name = "FILE")]
+    key: PathBuf,
+    /// Create a new secret key at `--key` and print its public key.
+    #[arg(long, conflicts_with = "archive")]
+    generate_key: bool,
+}
+
+pub fn run(_project: &Project, args: Args) -> anyhow::Result<ExitCode> {
+    let Some(archive) = args.archive else {
+        let key = SecretKey::generate()?;
+        key.save(&args.key)?;
+        super::emit(&format!(
+            "wrote {}; trust it in skills.toml with\n\n[trust.keys]\n<publisher> = \"{}\"",
+            args.key.display(),
+            key.public()
+        ))?;
+        return Ok(ExitCode::SUCCESS);
+    };
+    let key = SecretKey::load(&args.key)?;
+    // Refuse to vouch for an archive that is not a valid package.
+    let package = Package::open(&archive)?;
+    let signature = signing::sign(&archive, &key)?;
+    super::emit(&format!(
+        "signed `{}` with {} into {}",
+        package.manifest.name,
+        signature.key,
+        signing::signature_path(&archive).display()
+    ))?;
+    Ok(ExitCode::SUCCESS)
+}
@@ -0,0 +1,92 @@
+use std::fmt::Write as _;
+use std::process::ExitCode;
+
+use agent_skills::tokens::{self, ProjectCost, TokenCounter};
+use agent_skills::Project;
+use clap::ValueEnum;
+
+#[derive(clap::Args)]
+pub struct Args {
+    /// Tokenizer to count with. Defaults to `tokens.tokenizer` from
+    /// skills.toml, then to the bundled BPE vocabulary.
+    #[arg(long)]
+    tokenizer: Option<String>,
+    /// Run every script to measure its output (executes skill code).
+    #[arg(long)]
+    run_scripts: bool,
+    /// Output format.
+    #[arg(long, value_enum, default_value_t = Format::Human)]
+    format: Format,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum Format {
+    Human,
+    Json,
+}
+
+pub fn run(project: &Project, args: Args) -> anyhow::Result<ExitCode> {
+    let config = project.config()?;
+    let name = args.tokenizer.or(config.tokens.tokenizer);
+    let counter = TokenCounter::new(tokens::tokenizer(name.as_deref())?)
+        .run_scripts(args.run_scripts)
+        .scripts(config.scripts);
+    let cost = counter.project(project)?;
+    let output = match args.format {
+        Format::Human => human(&cost),
+        Format::Json => serde_json::to_string_pretty(&cost)?,
+    };
+    super::emit(&output)?;
+    Ok(ExitCode::SUCCESS)
+}
+
+fn human(cost: &ProjectCost) -> String {
+    let width = cost
+        .skills
+        .iter()
+        .map(|s| s.name.len())
+        .chain([5])
+        .max()
+        .unwrap_or(5);
+    let mut out = String::new();
+    let _ = writeln!(
+        out,
+        "{:width$}  {:>8}  {:>8}  {:>8}",
+        "skill", "level 1", "level 2", "level 3"
+    );
+    for skill in &cost.skills {
+        let unmeasured = skill.scripts.iter().filter(|s| s.output.is_none()).count();
+        let _ = write!(
+            out,
+            "{:width$}  {:>8}  {:>8}  {:>8}",
+            skill.name,
+            skill.metadata,
+            skill.instructions,
+            skill.on_demand()
+        );
+        if unmeasured > 0 {
+            let _ = write!(out, "  (+{unmeasured} script(s) not run)");
+        }
+        out.push('\n');
+    }
+    let _ = writeln!(
+        out,
+        "{:width$}  {:>8}  {:>8}  {:>8}",
+        "total",
+        cost.startup,
+        cost.skills.iter().map(|s| s.instructions).sum::<usize>(),
+        cost.skills.iter().map(|s| s.on_demand()).sum::<usize>(),
+    );
+    out.push('\n');
+    if let Some(global) = cost.global {
+        let _ = writeln!(out, "global .claude/SKILL.md: {global} tokens");
+    }
+    let _ = writeln!(
+        out,
+        "startup cost: {} tokens of metadata for {} skills ({})",
+        cost.startup,
+        cost.skills.len(),
+        cost.tokenizer
+    );
+    out
+}
@@ -0,0 +1,81 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+
+use agent_skills::package::{Package, Unpacker};
+use agent_skills::signing::{self, Checked};
+use agent_skills::{Error, Project};
+
+use super::add::{print_findings, screened};
+
+#[derive(clap::Args)]
+pub struct Args {
+    /// `.skill` archive to unpack.
+    archive: PathBuf,
+    /// Directory to unpack into. Defaults to `.claude/skills/<name>`.
+    #[arg(short, long, value_name = "DIR")]
+    output: Option<PathBuf>,
+    /// Replace a skill directory that already exists.
+    #[arg(long)]
+    force: bool,
+    /// Unpack even if the skill hides text from review or addresses the
+    /// agent directly.
+    #[arg(long)]
+    allow_hidden: bool,
+}
+
+pub fn run(project: &Project, args: Args) -> anyhow::Result<ExitCode> {
+    let (name, dest, signer) = unpack(
+        project,
+        &args.archive,
+        args.output,
+        Unpacker::new()
+            .force(args.force)
+            .allow_hidden(args.allow_hidden),
+    )?;
+    super::emit(&format!("unpacked {name} into {}{signer}", dest.display()))?;
+    Ok(ExitCode::SUCCESS)
+}
+
+/// Unpack `archive` into `output`, or the project's skill of the same
+/// name, once it passes the project's trust policy. Returns the skill as
+/// "`name` version", where it went, and who signed it.
+pub fn unpack(
+    project: &Project,
+    archive: &Path,
+    output: Option<PathBuf>,
+    unpacker: Unpacker,
+) -> anyhow::Result<(String, PathBuf, String)> {
+    // Verify and unpack the same bytes, not two reads of a file that may
+    // change in between.
+    let bytes = fs::read(archive).map_err(|source| Error::Io {
+        path: archive.to_path_buf(),
+        source,
+    })?;
+    let signer = trusted(project, archive, &bytes)?;
+    let package = Package::from_bytes(archive, &bytes)?;
+    let manifest = &package.manifest;
+    let dest = output.unwrap_or_else(|| project.skill(&manifest.name).path);
+    let findings = screened(unpacker.unpack(&package, &dest))?;
+    print_findings(&findings);
+    let name = match &manifest.version {
+        Some(version) => format!("`{}` {version}", manifest.name),
+        None => format!("`{}`", manifest.name),
+    };
+    Ok((name, dest, signer))
+}
+
+/// Apply the project's trust policy to the `bytes` of `archive`, warning
+/// about what the policy lets through unverified. Returns
+/// ", signed by `<publisher>`" for a verified archive.
+pub fn trusted(project: &Project, archive: &Path, bytes: &[u8]) -> anyhow::Result<String> {
+    let config = project.config()?;
+    Ok(match signing::check(archive, bytes, &config.trust)? {
+        Checked::Verified(verified) => format!(", signed by `{}`", verified.publisher),
+        Checked::Unverified(e) => {
+            eprintln!("warning: {e}");
+            String::new()
+        }
+        Checked::Skipped => String::new(),
+    })
+}
@@ -0,0 +1,53 @@
+use std::fmt::Write as _;
+use std::process::ExitCode;
+
+use agent_skills::install::Installer;
+use agent_skills::Project;
+
+use super::add::{print_findings, screened, short, warn_unresolved};
+
+#[derive(clap::Args)]
+pub struct Args {
+    /// Skills to update; all locked skills when omitted.
+    names: Vec<String>,
+    /// Discard local edits to installed skills.
+    #[arg(long)]
+    force: bool,
+    /// Install new versions even if they hide text from review or address
+    /// the agent directly.
+    #[arg(long)]
+    allow_hidden: bool,
+    /// Install new versions even if the skills would no longer satisfy
+    /// each other's `requires`.
+    #[arg(long)]
+    allow_conflicts: bool,
+}
+
+pub fn run(project: &Project, args: Args) -> anyhow::Result<ExitCode> {
+    let updated = screened(
+        Installer::new(project)
+            .force(args.force)
+            .allow_hidden(args.allow_hidden)
+            .allow_conflicts(args.allow_conflicts)
+            .update(&args.names),
+    )?;
+    if updated.is_empty() {
+        super::emit("no skills in skills.lock")?;
+        return Ok(ExitCode::SUCCESS);
+    }
+    let mut out = String::new();
+    for installed in &updated {
+        print_findings(&installed.findings);
+        let commit = installed.lock.commit.as_deref().map(short);
+        let _ = match (installed.changed(), commit) {
+            (true, Some(commit)) => writeln!(out, "{}: updated to {commit}", installed.name),
+            (true, None) => writeln!(out, "{}: updated", installed.name),
+            (false, _) => writeln!(out, "{}: up to date", installed.name),
+        };
+    }
+    super::emit(&out)?;
+    if args.allow_conflicts {
+        warn_unresolved(project)?;
+    }
+    Ok(ExitCode::SUCCESS)
+}
@@ -0,0 +1,35 @@
+use std::fs;
+use std::path::PathBuf;
+use std::process::ExitCode;
+
+use agent_skills::package::Package;
+use agent_skills::{signing, Error, Project};
+
+#[derive(clap::Args)]
+pub struct Args {
+    /// `.skill` archive to check against its `.sig` file and the keys in
+    /// `[trust.keys]`.
+    archive: PathBuf,
+}
+
+pub fn run(project: &Project, args: Args) -> anyhow::Result<ExitCode> {
+    let config = project.config()?;
+    let bytes = fs::read(&args.archive).map_err(|source| Error::Io {
+        path: args.archive.clone(),
+        source,
+    })?;
+    let package = Package::from_bytes(&args.archive, &bytes)?;
+    let verified = match signing::verify(&args.archive, &bytes, &config.trust.keys) {
+        Ok(verified) => verified,
+        Err(e @ Error::Signature { .. }) => {
+            eprintln!("{e}");
+            return Ok(ExitCode::FAILURE);
+        }
+        Err(e) => return Err(e.into()),
+    };
+    super::emit(&format!(
+        "`{}` is signed by `{}` ({})",
+        package.manifest.name, verified.publisher, verified.key
+    ))?;
+    Ok(ExitCode::SUCCESS)
+}
@@ -0,0 +1,93 @@
+use std::fmt::Write as _;
+use std::path::PathBuf;
+use std::process::{Command, ExitCode};
+
+use agent_skills::activation::{Activation, Activator};
+use agent_skills::{Project, SkillLoader};
+use anyhow::{bail, Context};
+use clap::ValueEnum;
+
+#[derive(clap::Args)]
+pub struct Args {
+    /// Files to evaluate, relative to the project root.
+    paths: Vec<PathBuf>,
+    /// Also evaluate files changed in the git working tree.
+    #[arg(long)]
+    changed: bool,
+    /// Output format.
+    #[arg(long, value_enum, default_value_t = Format::Human)]
+    format: Format,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum Format {
+    Human,
+    Json,
+}
+
+pub fn run(project: &Project, args: Args) -> anyhow::Result<ExitCode> {
+    let mut files = args.paths;
+    if args.changed {
+        files.extend(changed_files(project)?);
+    }
+    if files.is_empty() {
+        bail!("no files given; pass paths or --changed");
+    }
+    let mut loader = SkillLoader::from_project(project)?;
+    let activator = Activator::new(&loader.metadata_catalog())?.root(project.root());
+    let activations = activator.activate(&files);
+    let output = match args.format {
+        Format::Human => human(&activations),
+        Format::Json => serde_json::to_string_pretty(&activations)?,
+    };
+    super::emit(&output)?;
+    Ok(match activations.iter().any(Activation::is_active) {
+        true => ExitCode::SUCCESS,
+        false => ExitCode::FAILURE,
+    })
+}
+
+/// Paths reported by `git status`, relative to the project root.
+fn changed_files(project: &Project) -> anyhow::Result<Vec<PathBuf>> {
+    let output = Command::new("git")
+        .arg("-C")
+        .arg(project.root())
+        .args(["status", "--porcelain", "--untracked-files=all"])
+        .output()
+        .context("running git status")?;
+    if !output.status.success() {
+        bail!(
+            "git status failed: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+    Ok(String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .filter_map(|line| line.get(3..))
+        .map(|path| match path.split_once(" -> ") {
+            Some((_, renamed)) => PathBuf::from(renamed),
+            None => PathBuf::from(path),
+        })
+        .collect())
+}
+
+fn human(activations: &[Activation]) -> String {
+    if activations.is_empty() {
+        return "no skill matches these files\n".into();
+    }
+    let mut out = String::new();
+    for activation in activations {
+        let state = match activation.is_active() {
+            true => "active",
+            false => "inactive",
+        };
+        let _ = writeln!(out, "{} ({state})", activation.skill);
+        for m in &activation.matched {
+            let _ = writeln!(out, "  {}  matched `{}`", m.file.display(), m.pattern);
+        }
+        for m in &activation.excluded {
+            let _ = writeln!(out, "  {}  excluded by `{}`", m.file.display(), m.pattern);
+        }
+    }
+    out
+}
@@ -0,0 +1,110 @@
+//! `skills`: command line tooling for Agent Skills collections.
+
+mod cmd;
+
+use std::path::PathBuf;
+use std::process::ExitCode;
+
+use agent_skills::Project;
+use clap::{Parser, Subcommand};
+
+#[derive(Parser)]
+#[command(
+    name = "skills",
+    version,
+    about = "Lint, inspect and manage Agent Skills"
+)]
+struct Cli {
+    /// Project root containing the `.claude/` directory.
+    #[arg(short = 'C', long, global = true, default_value = ".")]
+    project: PathBuf,
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Install a skill from a local path, git repository or `.skill` archive.
+    Add(cmd::add::Args),
+    /// Find skills that overlap, repeat or contradict each other.
+    Conflicts(cmd::conflicts::Args),
+    /// Show the instructions in effect after merging every `.claude/` layer.
+    Explain(cmd::explain::Args),
+    /// Convert skills into another tool's rule format.
+    Export(cmd::export::Args),
+    /// Show how skills depend on and extend each other.
+    Graph(cmd::graph::Args),
+    /// Convert other tools' rule files into skills.
+    Import(cmd::import::Args),
+    /// Check skills against the contribution quality checklist.
+    Lint(cmd::lint::Args),
+    /// Run a language server for editing SKILL.md files over stdio.
+    Lsp(cmd::lsp::Args),
+    /// Serve skills to MCP clients over stdio.
+    Mcp(cmd::mcp::Args),
+    /// Rank skills by relevance to a request and the files open.
+    Match(cmd::matches::Args),
+    /// Create a skill from a template.
+    New(cmd::new::Args),
+    /// Write a skill to a `.skill` archive with a hashed manifest.
+    Pack(cmd::pack::Args),
+    /// Serve a skill registry, or publish to, search and download from one.
+    Registry(cmd::registry::Args),
+    /// Delete an installed skill and its skills.lock entry.
+    Remove(cmd::remove::Args),
+    /// Pick versions of all skills that satisfy every `requires` range.
+    Resolve(cmd::resolve::Args),
+    /// Run a skill script in the sandbox and report its output.
+    Run(cmd::run::Args),
+    /// Find credentials in skills and manage accepted findings.
+    Secrets(cmd::secrets::Args),
+    /// Sign a `.skill` archive, or create a signing key.
+    Sign(cmd::sign::Args),
+    /// Report token cost per progressive-disclosure level.
+    Tokens(cmd::tokens::Args),
+    /// Extract a `.skill` archive after checking its hashes.
+    Unpack(cmd::unpack::Args),
+    /// Re-fetch installed skills from their sources.
+    Update(cmd::update::Args),
+    /// Check a `.skill` archive's signature against the trusted keys.
+    Verify(cmd::verify::Args),
+    /// Show which skills a file activates through `globs`.
+    Which(cmd::which::Args),
+}
+
+fn main() -> ExitCode {
+    let cli = Cli::parse();
+    let project = Project::new(cli.project);
+    let result = match cli.command {
+        Command::Add(args) => cmd::add::run(&project, args),
+        Command::Conflicts(args) => cmd::conflicts::run(&project, args),
+        Command::Explain(args) => cmd::explain::run(&project, args),
+        Command::Export(args) => cmd::export::run(&project, args),
+        Command::Graph(args) => cmd::graph::run(&project, args),
+        Command::Import(args) => cmd::import::run(&project, args),
+        Command::Lint(args) => cmd::lint::run(&project, args),
+        Command::Lsp(args) => cmd::lsp::run(&project, args),
+        Command::Mcp(args) => cmd::mcp::run(&project, args),
+        Command::Match(args) => cmd::matches::run(&project, args),
+        Command::New(args) => cmd::new::run(&project, args),
+        Command::Pack(args) => cmd::pack::run(&project, args),
+        Command::Registry(args) => cmd::registry::run(&project, args),
+        Command::Remove(args) => cmd::remove::run(&project, args),
+        Command::Resolve(args) => cmd::resolve::run(&project, args),
+        Command::Run(args) => cmd::run::run(&project, args),
+        Command::Secrets(args) => cmd::secrets::run(&project, args),
+        Command::Sign(args) => cmd::sign::run(&project, args),
+        Command::Tokens(args) => cmd::tokens::run(&project, args),
+        Command::Unpack(args) => cmd::unpack::run(&project, args),
+        Command::Update(args) => cmd::update::run(&project, args),
+        Command::Verify(args) => cmd::verify::run(&project, args),
+        Command::Which(args) => cmd::which::run(&project, args),
+    };
+    match result {
+        Ok(code) => code,
+        Err(e) => {
+            eprintln!("error: {e}");
+            ExitCode::from(2)
+        }
+    }
+}
@@ -0,0 +1,192 @@
+//! The `[trust]` policy holds on every way a package reaches a project:
+//! `skills unpack`, `skills add` and `skills registry download`.
+
+use std::fs;
+use std::io::{BufRead, BufReader};
+use std::path::{Path, PathBuf};
+use std::process::{Child, Command, Output, Stdio};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+const SKILL: &str = "---
+name: deploy
+description: Deploy the service to staging
+version: 1.0.0
+---
+
+# Deploy
+
+## Instructions
+
+1. Run `make deploy`
+";
+
+/// A scratch directory under the target directory, removed when dropped.
+struct Scratch(PathBuf);
+
+impl Scratch {
+    fn new() -> Self {
+        static NEXT: AtomicUsize = AtomicUsize::new(0);
+        let path = Path::new(env!("CARGO_TARGET_TMPDIR")).join(format!(
+            "trust-{}-{}",
+            std::process::id(),
+            NEXT.fetch_add(1, Ordering::Relaxed)
+        ));
+        fs::create_dir_all(&path).unwrap();
+        Self(path)
+    }
+
+    /// A project under `name` whose `[trust]` policy is `policy`.
+    fn project(&self, name: &str, policy: &str) -> PathBuf {
+        let root = self.0.join(name);
+        fs::create_dir_all(root.join(".claude/skills")).unwrap();
+        fs::write(
+            root.join("skills.toml"),
+            format!("[trust]\npolicy = \"{policy}\"\n"),
+        )
+        .unwrap();
+        root
+    }
+}
+
+impl Drop for Scratch {
+    fn drop(&mut self) {
+        let _ = fs::remove_dir_all(&self.0);
+    }
+}
+
+fn skills(project: &Path, args: &[&str]) -> Command {
+    let mut command = Command::new(env!("CARGO_BIN_EXE_skills"));
+    command.arg("-C").arg(project).args(args);
+    command
+}
+
+fn run(project: &Path, args: &[&str]) -> Output {
+    skills(project, args).output().unwrap()
+}
+
+/// Pack an unsigned `deploy` 1.0.0 from a project that does not check.
+fn unsigned_archive(scratch: &Scratch) -> PathBuf {
+    let source = scratch.project("source", "off");
+    fs::create_dir_all(source.join(".claude/skills/deploy")).unwrap();
+    fs::write(source.join(".claude/skills/deploy/SKILL.md"), SKILL).unwrap();
+    let archive = scratch.0.join("deploy-1.0.0.skill");
+    let output = run(
+        &source,
+        &["pack", "deploy", "-o", archive.to_str().unwrap()],
+    );
+    assert!(output.status.success(), "{output:?}");
+    archive
+}
+
+fn refused(output: &Output) -> String {
+    assert_eq!(output.status.code(), Some(2), "{output:?}");
+    String::from_utf8_lossy(&output.stderr).into_owned()
+}
+
+#[test]
+fn unsigned_packages_are_not_unpacked_or_added() {
+    let scratch = Scratch::new();
+    let archive = unsigned_archive(&scratch);
+    let archive = archive.to_str().unwrap();
+    let strict = scratch.project("strict", "require-signature");
+
+    for args in [["unpack", archive], ["add", archive]] {
+        let stderr = refused(&run(&strict, &args));
+        assert!(stderr.contains("not signed"), "{args:?}: {stderr}");
+        assert!(!strict.join(".claude/skills/deploy").exists(), "{args:?}");
+    }
+
+    let lenient = scratch.project("lenient", "warn");
+    let output = run(&lenient, &["add", archive]);
+    assert!(output.status.success(), "{output:?}");
+    assert!(String::from_utf8_lossy(&output.stderr).contains("warning: "));
+    assert!(lenient.join(".claude/skills/deploy/SKILL.md").is_file());
+}
+
+/// `skills registry serve` on a free port, stopped when dropped.
+struct Registry {
+    child: Child,
+    url: String,
+}
+
+impl Registry {
+    fn serve(scratch: &Scratch) -> Self {
+        let dir = scratch.0.join("registry");
+        let mut child = skills(
+            &scratch.0,
+            &[
+                "registry",
+                "serve",
+                "--dir",
+                dir.to_str().unwrap(),
+                "--listen",
+                "127.0.0.1:0",
+            ],
+        )
+        .env_remove("SKILLS_REGISTRY_TOKEN")
+        .stderr(Stdio::piped())
+        .spawn()
+        .unwrap();
+        let mut line = String::new();
+        BufReader::new(child.stderr.take().unwrap())
+            .read_line(&mut line)
+            .unwrap();
+        let url = line
+            .split_whitespace()
+            .last()
+            .expect("serve prints its address")
+            .to_string();
+        Self { child, url }
+    }
+}
+
+impl Drop for Registry {
+    fn drop(&mut self) {
+        let _ = self.child.kill();
+        let _ = self.child.wait();
+    }
+}
+
+#[test]
+fn unsigned_downloads_are_not_kept() {
+    let scratch = Scratch::new();
+    let archive = unsigned_archive(&scratch);
+    let registry = Registry::serve(&scratch);
+    let strict = scratch.project("strict", "require-signature");
+    let output = run(
+        &strict,
+        &[
+            "registry",
+            "--registry",
+            &registry.url,
+            "publish",
+            archive.to_str().unwrap(),
+        ],
+    );
+    assert!(output.status.success(), "{output:?}");
+
+    let saved = scratch.0.join("downloads");
+    fs::create_dir_all(&saved).unwrap();
+    let download = |project: &Path| {
+        run(
+            project,
+            &[
+                "registry",
+                "--registry",
+                &registry.url,
+                "download",
+                "deploy",
+                "-o",
+                saved.to_str().unwrap(),
+            ],
+        )
+    };
+    let stderr = refused(&download(&strict));
+    assert!(stderr.contains("not signed"), "{stderr}");
+    assert_eq!(fs::read_dir(&saved).unwrap().count(), 0);
+
+    let lenient = scratch.project("lenient", "warn");
+    let output = download(&lenient);
+    assert!(output.status.success(), "{output:?}");
+    assert!(saved.join("deploy-1.0.0.skill").is_file());
+}